@@ -0,0 +1,55 @@
+//! Counts the visits of each Session using axum 0.6 Routers with state.
+//!
+//! Run with `cargo run --example counter` and open http://127.0.0.1:3000 within a browser.
+
+use axum::{extract::State, routing::get, Router};
+use axum_database_sessions::{
+    AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore,
+    ReadOnlySession,
+};
+use std::net::SocketAddr;
+
+#[derive(Clone)]
+struct AppState {
+    greeting: &'static str,
+}
+
+#[tokio::main]
+async fn main() {
+    // Plain http so the browser keeps the cookie while testing locally.
+    let session_config = AxumSessionConfig::default().with_secure(false);
+    let session_store = AxumSessionStore::<AxumNullPool>::new(None, session_config);
+
+    let app = Router::new()
+        .route("/", get(count))
+        .route("/peek", get(peek))
+        .route("/optional", get(optional))
+        .layer(AxumSessionLayer::new(session_store))
+        .with_state(AppState { greeting: "Hello" });
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
+}
+
+async fn count(State(state): State<AppState>, session: AxumSession<AxumNullPool>) -> String {
+    let count: usize = session.get("count").await.unwrap_or(0) + 1;
+    session.set("count", count).await;
+
+    format!("{}, you visited {} times", state.greeting, count)
+}
+
+async fn peek(session: ReadOnlySession<AxumNullPool>) -> String {
+    let count: usize = session.get("count").await.unwrap_or(0);
+
+    format!("You visited {} times", count)
+}
+
+async fn optional(session: Option<AxumSession<AxumNullPool>>) -> &'static str {
+    match session {
+        Some(_) => "The session layer is enabled",
+        None => "The session layer is missing",
+    }
+}
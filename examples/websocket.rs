@@ -0,0 +1,75 @@
+//! Counts the messages sent over WebSockets within the Session so the count survives reconnects.
+//!
+//! Run with `cargo run --example websocket`, open http://127.0.0.1:3000 within a browser and
+//! send a few messages. Reloading the page reconnects using the same Session and count.
+
+use axum::{
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    response::{Html, Response},
+    routing::get,
+    Router,
+};
+use axum_database_sessions::{
+    AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore,
+    DetachedSession,
+};
+use std::net::SocketAddr;
+
+const PAGE: &str = r#"<!doctype html>
+<input id="text"><button onclick="ws.send(text.value)">Send</button>
+<pre id="log"></pre>
+<script>
+  const ws = new WebSocket(`ws://${location.host}/ws`);
+  ws.onmessage = (event) => log.textContent += event.data + "\n";
+</script>
+"#;
+
+#[tokio::main]
+async fn main() {
+    // Plain http so the browser keeps the cookie while testing locally.
+    let session_config = AxumSessionConfig::default().with_secure(false);
+    let session_store = AxumSessionStore::<AxumNullPool>::new(None, session_config);
+
+    let app = Router::new()
+        .route("/", get(page))
+        .route("/ws", get(upgrade))
+        .layer(AxumSessionLayer::new(session_store));
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
+}
+
+// Extracting the Session sets its cookie before the socket connects with it.
+async fn page(_session: AxumSession<AxumNullPool>) -> Html<&'static str> {
+    Html(PAGE)
+}
+
+async fn upgrade(ws: WebSocketUpgrade, session: AxumSession<AxumNullPool>) -> Response {
+    // The response is returned before the socket runs so the Session is detached to keep using it.
+    let session = session.detach();
+
+    ws.on_upgrade(move |socket| count_messages(socket, session))
+}
+
+async fn count_messages(mut socket: WebSocket, session: DetachedSession<AxumNullPool>) {
+    while let Some(Ok(message)) = socket.recv().await {
+        if let Message::Text(text) = message {
+            let count: usize = session.get("messages").await.unwrap_or(0) + 1;
+            session.set("messages", count).await;
+
+            // Writes the count now instead of once the socket closes.
+            if let Err(err) = session.flush().await {
+                tracing::error!("Failed to store session: {}", err);
+            }
+
+            let reply = format!("{} (message {} of this session)", text, count);
+
+            if socket.send(Message::Text(reply)).await.is_err() {
+                break;
+            }
+        }
+    }
+}
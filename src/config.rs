@@ -1,6 +1,11 @@
+use crate::{
+    SessionConfigError, SessionEventHandler, SessionIdGenerator, SessionRejection,
+    SessionSerializer, UuidGenerator, MAX_LIFESPAN_SECS,
+};
+use axum_core::response::{IntoResponse, Response};
 use chrono::Duration;
 pub use cookie::{Key, SameSite};
-use std::borrow::Cow;
+use std::{borrow::Cow, net::IpAddr, sync::Arc};
 
 /// Mode at which the Session will function As.
 ///
@@ -15,7 +20,7 @@ use std::borrow::Cow;
 pub enum AxumSessionMode {
     /// Deletes Session Data if session.storable is false, if session.storable is true saves data.
     Storable,
-    /// Always in Memory and Database. regardless of if storable.
+    /// Always in Memory and Database. regardless of if storable. New Sessions start as storable.
     Always,
 }
 
@@ -27,6 +32,154 @@ impl AxumSessionMode {
     }
 }
 
+/// How the Session's expiration time is updated on each request.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, ExpiryPolicy};
+/// use chrono::Duration;
+///
+/// let config = AxumSessionConfig::default().with_expiry_policy(ExpiryPolicy::Hybrid {
+///     idle: Duration::minutes(30),
+///     max: Duration::hours(12),
+/// });
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryPolicy {
+    /// Each request pushes the expiration to now + lifespan. This is the Default.
+    Sliding,
+    /// The Session expires lifespan after it was created no matter how often it is used.
+    Absolute,
+    /// Each request pushes the expiration to now + idle but never past created + max.
+    Hybrid {
+        /// How long the Session can go unused before it expires.
+        idle: Duration,
+        /// How long after creation the Session expires no matter how often it is used.
+        max: Duration,
+    },
+}
+
+/// How the Session's cookies are secured when a [`Key`] is set.
+///
+/// Cookies are always sent as plain text when no Key is set.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, CookieSecurity, Key};
+///
+/// let config = AxumSessionConfig::default()
+///     .with_key(Key::generate())
+///     .with_cookie_security(CookieSecurity::Signed);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CookieSecurity {
+    /// Sends the cookies as plain text even when a Key is set.
+    None,
+    /// Signs the cookies using HMAC-SHA256 so forged or tampered values are rejected
+    /// before the database is checked. The value can still be read by the client.
+    Signed,
+    /// Encrypts the cookies using AES-GCM so the value can not be read or tampered with.
+    Private,
+}
+
+/// What AxumSessionStore::initiate does to the database's table.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, Migrations};
+///
+/// let config = AxumSessionConfig::default().with_migrations(Migrations::CheckOnly);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Migrations {
+    /// Creates or updates the table. This is the Default.
+    Run,
+    /// Checks the table and its columns exist, returning SessionError::MissingSchema listing what is missing.
+    /// Use this when the database user can not run DDL.
+    CheckOnly,
+    /// Does nothing to the table.
+    Skip,
+}
+
+/// What the layer does when a Session is used from another IP than the one it was created from.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, IpBinding};
+///
+/// let config = AxumSessionConfig::default().with_ip_binding(IpBinding::Reject);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpBinding {
+    /// Sessions are not bound to an IP. This is the Default.
+    Disabled,
+    /// The Session is treated as missing and the request continues with a new Session.
+    /// The original Session is kept for requests from its own IP.
+    Reject,
+    /// The Session is kept and SessionEventHandler::on_ip_mismatch is called.
+    Notify,
+}
+
+/// What the layer does for clients past AxumSessionConfig::with_session_rate_limit.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, RateLimitAction};
+/// use chrono::Duration;
+///
+/// let config = AxumSessionConfig::default()
+///     .with_session_rate_limit(30, Duration::minutes(1), RateLimitAction::TooManyRequests);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitAction {
+    /// The request gets a Session that only exists for the request. It is never stored or sent as a cookie.
+    Throwaway,
+    /// The request is answered with 429 Too Many Requests without calling the handler.
+    TooManyRequests,
+}
+
+/// How the `DNT` and `Sec-GPC` privacy headers change if a request accepts cookies.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, PrivacySignals};
+///
+/// let config = AxumSessionConfig::default().with_privacy_signals(PrivacySignals::Honor);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivacySignals {
+    /// The headers are ignored.
+    Ignore,
+    /// Requests sending `DNT: 1` or `Sec-GPC: 1` decline cookies.
+    Honor,
+    /// Like Honor, and requests sending neither header accept cookies unless they already
+    /// have an acceptance cookie.
+    HonorOrAccept,
+}
+
+/// What session.set_store_user_id() does when the user already has the most Sessions allowed.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, OverflowPolicy};
+///
+/// let config = AxumSessionConfig::default().with_max_sessions_per_user(2, OverflowPolicy::DestroyOldest);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// The user id is not set and SessionError::TooManySessions is returned.
+    Reject,
+    /// The user's least recently used Sessions are destroyed to make room.
+    DestroyOldest,
+}
+
 /// Configuration for how the Session and Cookies are used.
 ///
 /// # Examples
@@ -48,12 +201,22 @@ pub struct AxumSessionConfig {
     pub(crate) cookie_http_only: bool,
     /// Session cookie max age None means the browser deletes cookie on close
     pub(crate) cookie_max_age: Option<Duration>,
+    /// Removes Max-Age from the cookies so they are deleted on browser close. Default is false
+    pub(crate) browser_session_cookie: bool,
+    /// Request and Response header used for the Session ID instead of cookies. Default is None
+    pub(crate) session_header: Option<Cow<'static, str>>,
+    /// Response header used to return new Session IDs when bearer tokens are enabled. Default is None
+    pub(crate) bearer_header: Option<Cow<'static, str>>,
+    /// How often unchanged cookies are sent again to refresh their Max-Age. Default is 1 day
+    pub(crate) cookie_refresh_interval: Duration,
+    /// How far session.touch() must move the expiration before the Session is written to the database.
+    pub(crate) touch_granularity: Duration,
     /// Session cookie name
     pub(crate) cookie_name: Cow<'static, str>,
     /// Session cookie path
     pub(crate) cookie_path: Cow<'static, str>,
-    /// Resticts how Cookies are sent cross-site. Default is `SameSite::None`
-    /// Only works if domain is also set.
+    /// Resticts how Cookies are sent cross-site. Default is `SameSite::Lax`
+    /// `SameSite::None` always sets the secure flag.
     pub(crate) cookie_same_site: SameSite,
     /// Session cookie secure flag
     pub(crate) cookie_secure: bool,
@@ -61,6 +224,10 @@ pub struct AxumSessionConfig {
     pub(crate) session_mode: AxumSessionMode,
     /// Sessions lifespan within the Database.
     pub(crate) lifespan: Duration,
+    /// How the Sessions expiration is updated on each request. Default is `ExpiryPolicy::Sliding`
+    pub(crate) expiry_policy: ExpiryPolicy,
+    /// How long a Session can go without requests before it is cleared. Default is None
+    pub(crate) idle_timeout: Option<Duration>,
     /// Session Database Max Poll Connections. Can not be 0
     pub(crate) max_connections: u32,
     /// This is the long term lifespan for things like Remember Me.
@@ -70,10 +237,58 @@ pub struct AxumSessionConfig {
     /// this works fine since the data can stay in the database till its needed
     /// if not yet expired.
     pub(crate) memory_lifespan: Duration,
+    /// Most Sessions kept within memory before the least recently used are evicted. Default is None
+    pub(crate) memory_max_sessions: Option<usize>,
+    /// How long AxumSessionStore::shutdown writes the changed Sessions before giving up. Default is 30 seconds
+    pub(crate) shutdown_timeout: Duration,
+    /// Skips the memory store loading and storing the Session each request. Default is false
+    pub(crate) database_only: bool,
+    /// Only creates new Sessions once something is set within them. Default is false
+    pub(crate) lazy_sessions: bool,
+    /// Gives the Session a new ID when it is accepted as storable. Default is true
+    pub(crate) renew_on_accept: bool,
+    /// Paths passed through without a Session. Default is empty
+    pub(crate) excluded_paths: Vec<String>,
     /// Session Database table name default is async_sessions
     pub(crate) table_name: Cow<'static, str>,
+    /// Session Database schema name. Only used by Postgres and Scylla. Default is None
+    pub(crate) schema_name: Option<Cow<'static, str>>,
+    /// What initiate does to the table. Default is `Migrations::Run`
+    pub(crate) migrations: Migrations,
     ///Encyption Key used to encypt cookies for confidentiality, integrity, and authenticity.
     pub(crate) key: Option<Key>,
+    /// Old Keys used to read cookies during key rotation. Cookies are always written with key.
+    pub(crate) secondary_keys: Vec<Key>,
+    /// How cookies are secured when a Key is set. Default is `CookieSecurity::Private`
+    pub(crate) cookie_security: CookieSecurity,
+    /// Generates and validates the Session IDs. Default is `UuidGenerator`
+    pub(crate) id_generator: Arc<dyn SessionIdGenerator>,
+    /// Receives the Session lifecycle events. Default is None
+    pub(crate) event_handler: Option<Arc<dyn SessionEventHandler>>,
+    /// Makes the responses of the rejections passing through the layer. Default is None
+    pub(crate) rejection_handler: Option<Arc<dyn Fn(SessionRejection) -> Response + Send + Sync>>,
+    /// What happens when a Session is used from another IP. Default is `IpBinding::Disabled`
+    pub(crate) ip_binding: IpBinding,
+    /// Leading bits of an IPv4 address that must match the Session's IP. Default is 32
+    pub(crate) ip_prefix_v4: u8,
+    /// Leading bits of an IPv6 address that must match the Session's IP. Default is 128
+    pub(crate) ip_prefix_v6: u8,
+    /// Proxies whose `Forwarded` and `X-Forwarded-For` headers are used for the client's IP. Default is empty
+    pub(crate) trusted_proxies: Vec<IpAddr>,
+    /// Gives requests with another User-Agent than the Session was created with a new Session. Default is false
+    pub(crate) user_agent_binding: bool,
+    /// Removes the version numbers from the User-Agent before it is compared. Default is false
+    pub(crate) user_agent_normalization: bool,
+    /// Most new Sessions per client IP within the period and what happens past it. Default is None
+    pub(crate) session_rate_limit: Option<(u32, Duration, RateLimitAction)>,
+    /// How the `DNT` and `Sec-GPC` headers change the request's acceptance. Default is `PrivacySignals::Ignore`
+    pub(crate) privacy_signals: PrivacySignals,
+    /// Most Sessions a user id can be set on and what happens past it. Default is None
+    pub(crate) max_sessions_per_user: Option<(usize, OverflowPolicy)>,
+    /// Format used to store the Session's data within the database. Default is `SessionSerializer::Json`
+    pub(crate) serializer: SessionSerializer,
+    /// Prefix of the metric names. Only used with the `metrics` feature. Default is axum_session
+    pub(crate) metrics_prefix: Cow<'static, str>,
 }
 
 impl std::fmt::Debug for AxumSessionConfig {
@@ -84,17 +299,48 @@ impl std::fmt::Debug for AxumSessionConfig {
             .field("cookie_domain", &self.cookie_domain)
             .field("cookie_http_only", &self.cookie_http_only)
             .field("cookie_max_age", &self.cookie_max_age)
+            .field("browser_session_cookie", &self.browser_session_cookie)
+            .field("session_header", &self.session_header)
+            .field("bearer_header", &self.bearer_header)
+            .field("cookie_refresh_interval", &self.cookie_refresh_interval)
+            .field("touch_granularity", &self.touch_granularity)
             .field("cookie_name", &self.cookie_name)
             .field("cookie_path", &self.cookie_path)
             .field("cookie_same_site", &self.cookie_same_site)
             .field("cookie_secure", &self.cookie_secure)
             .field("session_mode", &self.session_mode)
             .field("lifespan", &self.lifespan)
+            .field("expiry_policy", &self.expiry_policy)
+            .field("idle_timeout", &self.idle_timeout)
             .field("max_connections", &self.max_connections)
             .field("max_lifespan", &self.max_lifespan)
             .field("memory_lifespan", &self.memory_lifespan)
+            .field("memory_max_sessions", &self.memory_max_sessions)
+            .field("shutdown_timeout", &self.shutdown_timeout)
+            .field("database_only", &self.database_only)
+            .field("lazy_sessions", &self.lazy_sessions)
+            .field("renew_on_accept", &self.renew_on_accept)
+            .field("excluded_paths", &self.excluded_paths)
             .field("table_name", &self.table_name)
+            .field("schema_name", &self.schema_name)
+            .field("migrations", &self.migrations)
             .field("key", &"key hidden")
+            .field("secondary_keys", &"keys hidden")
+            .field("cookie_security", &self.cookie_security)
+            .field("id_generator", &self.id_generator)
+            .field("event_handler", &self.event_handler)
+            .field("rejection_handler", &self.rejection_handler.is_some())
+            .field("ip_binding", &self.ip_binding)
+            .field("ip_prefix_v4", &self.ip_prefix_v4)
+            .field("ip_prefix_v6", &self.ip_prefix_v6)
+            .field("trusted_proxies", &self.trusted_proxies)
+            .field("user_agent_binding", &self.user_agent_binding)
+            .field("user_agent_normalization", &self.user_agent_normalization)
+            .field("session_rate_limit", &self.session_rate_limit)
+            .field("privacy_signals", &self.privacy_signals)
+            .field("max_sessions_per_user", &self.max_sessions_per_user)
+            .field("serializer", &self.serializer)
+            .field("metrics_prefix", &self.metrics_prefix)
             .finish()
     }
 }
@@ -124,11 +370,13 @@ impl AxumSessionConfig {
 
     /// Set the session's storable cookie name.
     ///
+    /// Must not be empty, contain whitespace or separators, or match the session cookie name.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
     ///
-    /// let config = AxumSessionConfig::default().with_accepted_cookie_name("my_accepted_cookie");
+    /// let config = AxumSessionConfig::default().with_storable_cookie_name("my_accepted_cookie");
     /// ```
     ///
     #[must_use]
@@ -147,7 +395,7 @@ impl AxumSessionConfig {
     /// use axum_database_sessions::AxumSessionConfig;
     /// use chrono::Duration;
     ///
-    /// let config = AxumSessionConfig::default().with_accepted_max_age(Some(Duration::days(64)));
+    /// let config = AxumSessionConfig::default().with_storable_max_age(Some(Duration::days(64)));
     /// ```
     ///
     #[must_use]
@@ -158,11 +406,14 @@ impl AxumSessionConfig {
 
     /// Set's the session's cookie's domain name.
     ///
+    /// Set this to share the Session across sub domains like `www.example.com` and `api.example.com`.
+    /// Default is None which restricts the cookie to the host that set it.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
     ///
-    /// let config = AxumSessionConfig::default().with_cookie_domain(Some("www.helpme.com".to_string()));
+    /// let config = AxumSessionConfig::default().with_cookie_domain(Some("www.helpme.com".into()));
     /// ```
     ///
     #[must_use]
@@ -171,8 +422,54 @@ impl AxumSessionConfig {
         self
     }
 
+    /// Set's how often the session's cookies are sent again when nothing about them changed.
+    ///
+    /// Cookies are only sent when the Session is new, destroyed, its storable or long term state
+    /// changed, or this interval has passed since they were last sent. This keeps responses
+    /// cacheable while still refreshing the cookies Max-Age. Must be greater than 0.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    /// use chrono::Duration;
+    ///
+    /// let config = AxumSessionConfig::default().with_cookie_refresh_interval(Duration::hours(12));
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_cookie_refresh_interval(mut self, time: Duration) -> Self {
+        self.cookie_refresh_interval = time;
+        self
+    }
+
+    /// Set's how far session.touch() must move the expiration before it writes the Session to the database.
+    ///
+    /// This keeps session.touch() cheap enough to call on every tick of a long lived request
+    /// like Server Sent Events. It is also how far the Session's last access time must move
+    /// before it is written when no idle timeout is set. Requests that do not change the Session
+    /// are not written until then, so this is also how stale the database's sliding expiration
+    /// can get. Must not be negative.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    /// use chrono::Duration;
+    ///
+    /// let config = AxumSessionConfig::default().with_touch_granularity(Duration::minutes(5));
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_touch_granularity(mut self, time: Duration) -> Self {
+        self.touch_granularity = time;
+        self
+    }
+
     /// Set's the session's cookie's name.
     ///
+    /// Names starting with `__Secure-` require the secure flag. Names starting with `__Host-`
+    /// also require no cookie domain and a cookie path of `/`. Browsers drop cookies that break
+    /// these rules so AxumSessionStore::new() panics and try_new() returns an error for them.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
@@ -190,6 +487,7 @@ impl AxumSessionConfig {
     ///
     /// This is used to deturmine when the cookie takes effect within the website path.
     /// Leave as default ("/") for cookie to be used site wide.
+    /// The path must start with `/`. This is checked when the AxumSessionStore is constructed.
     ///
     /// # Examples
     /// ```rust
@@ -206,7 +504,9 @@ impl AxumSessionConfig {
 
     /// Set's the session's cookie's Same Site Setting for Cross-Site restrictions.
     ///
-    /// Only works if Domain is also set to restrict it to that domain only.
+    /// Use `SameSite::Lax` for things like OAuth callbacks and `SameSite::None` for embedded content.
+    /// Browsers reject `SameSite::None` cookies without the secure flag so it is always set when
+    /// `SameSite::None` is used regardless of [`AxumSessionConfig::with_secure`].
     ///
     /// # Examples
     /// ```rust
@@ -222,12 +522,59 @@ impl AxumSessionConfig {
         self
     }
 
-    /// Set's whether the session Always stores data or on stores if storable.
+    /// Set's a header used to send the Session ID instead of cookies.
+    ///
+    /// Useful for clients without a cookie jar like native mobile apps. The Session ID is read from
+    /// this request header and returned within the same response header. No cookies are sent and
+    /// since there is no cookie to accept the Session is always treated as storable.
+    /// Default is None which uses cookies.
     ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
-    /// use cookie::SameSite;
+    ///
+    /// let config = AxumSessionConfig::default().with_session_header(Some("X-Session-Token".into()));
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_session_header(mut self, name: impl Into<Option<Cow<'static, str>>>) -> Self {
+        self.session_header = name.into();
+        self
+    }
+
+    /// Enables reading the Session ID from `Authorization: Bearer <token>` headers.
+    ///
+    /// The name set is the response header used to return the Session ID of new Sessions.
+    /// Requests with a bearer token never get cookies and are always treated as storable.
+    /// Other schemes like Basic are ignored. Requests without a bearer token fall back to cookies
+    /// so browsers and API clients can use the same Router. Once destroyed the token no longer
+    /// loads the old Session. Default is None which disables bearer tokens.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_bearer_token(Some("X-Session-Token".into()));
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_bearer_token(
+        mut self,
+        response_header: impl Into<Option<Cow<'static, str>>>,
+    ) -> Self {
+        self.bearer_header = response_header.into();
+        self
+    }
+
+    /// Set's whether the session Always stores data or on stores if storable.
+    ///
+    /// `AxumSessionMode::Always` is the default and disables the GDPR acceptance gating so
+    /// there is no need to call session.set_store(true). New Sessions start as storable.
+    /// Use `AxumSessionMode::Storable` to only store Sessions once they are set as storable.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionMode};
     ///
     /// let config = AxumSessionConfig::default().with_mode(AxumSessionMode::Always);
     /// ```
@@ -240,6 +587,8 @@ impl AxumSessionConfig {
 
     /// Set's the session's cookie's to http only.
     ///
+    /// Default is true which hides the cookie from javascript.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
@@ -255,6 +604,9 @@ impl AxumSessionConfig {
 
     /// Set's the session's lifetime (expiration time) within database storage.
     ///
+    /// This is separate from the memory lifetime set by [`AxumSessionConfig::with_memory_lifetime`].
+    /// Must be greater than 0 and not longer than the max lifetime.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
@@ -269,10 +621,50 @@ impl AxumSessionConfig {
         self
     }
 
+    /// Set's how the session's expiration time is updated on each request.
+    ///
+    /// `ExpiryPolicy::Sliding` and `ExpiryPolicy::Absolute` use the lifetime, or the max lifetime
+    /// for long term Sessions. `ExpiryPolicy::Hybrid` uses its own idle and max durations.
+    /// Sessions past their absolute expiration are treated as new Sessions when loaded.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, ExpiryPolicy};
+    ///
+    /// let config = AxumSessionConfig::default().with_expiry_policy(ExpiryPolicy::Absolute);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_expiry_policy(mut self, policy: ExpiryPolicy) -> Self {
+        self.expiry_policy = policy;
+        self
+    }
+
+    /// Set's how long a session can go without any requests before its data is cleared.
+    ///
+    /// This is checked alongside the lifetime and expiry policy. The last access time is only
+    /// written to the database once it has moved by more than a tenth of the idle timeout so
+    /// Sessions may time out up to a tenth of the idle timeout early. Must be greater than 0.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    /// use chrono::Duration;
+    ///
+    /// let config = AxumSessionConfig::default().with_idle_timeout(Some(Duration::minutes(20)));
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_idle_timeout(mut self, time: Option<Duration>) -> Self {
+        self.idle_timeout = time;
+        self
+    }
+
     /// Set's the session's cookies max_age (expiration time).
     ///
     /// If this is set to None then the Cookie will be unloaded on browser Close.
     /// Set this to be the duration of max_lifespan or longer to prevent session drops.
+    /// Must be greater than 0 when set.
     ///
     /// # Examples
     /// ```rust
@@ -288,8 +680,30 @@ impl AxumSessionConfig {
         self
     }
 
+    /// Set's the session's cookies to be deleted when the browser closes.
+    ///
+    /// This removes Max-Age from the cookies while the Session within the database still
+    /// expires based on the lifespan. Sessions set with set_longterm(true) are treated as
+    /// Remember Me sessions and still get a cookie Max-Age of max_lifespan.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_browser_session_cookie(true);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_browser_session_cookie(mut self, is_set: bool) -> Self {
+        self.browser_session_cookie = is_set;
+        self
+    }
+
     /// Set's the session's long term lifetime (expiration time) within database storage.
     ///
+    /// Must not be shorter than the lifetime set by [`AxumSessionConfig::with_lifetime`]
+    /// or longer than 100 years.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
@@ -306,6 +720,12 @@ impl AxumSessionConfig {
 
     /// Set's the session's lifetime (expiration time) within memory storage.
     ///
+    /// When a database is used the memory copy is dropped and reloaded from the database once
+    /// this has passed since it was loaded. Keep this short when running several instances
+    /// against the same database so a stale memory copy does not hide changes from another instance.
+    /// Without a database this is how long the session lives in memory since it was last used.
+    /// Must be greater than 0.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
@@ -320,13 +740,55 @@ impl AxumSessionConfig {
         self
     }
 
+    /// Set's the most Sessions kept within memory.
+    ///
+    /// Once reached the least recently used Sessions are evicted from memory. Persistent Sessions
+    /// are loaded from the database again when used, but memory only Sessions are lost.
+    /// Sessions used by a request are never evicted. Default is None which has no limit.
+    /// Must be greater than 0 or None.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_memory_max_sessions(Some(100_000));
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_memory_max_sessions(mut self, max: Option<usize>) -> Self {
+        self.memory_max_sessions = max;
+        self
+    }
+
+    /// Set's how long AxumSessionStore::shutdown writes the changed Sessions to the database.
+    ///
+    /// Sessions not written once it has passed are counted as failed. Default is 30 seconds.
+    /// Must be greater than 0.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    /// use chrono::Duration;
+    ///
+    /// let config = AxumSessionConfig::default().with_shutdown_timeout(Duration::seconds(5));
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
     /// Set's the session's secure flag for if it gets sent over https.
     ///
+    /// Default is true. Set this to false for local development over plain http.
+    /// This is ignored when `SameSite::None` is used as browsers require secure cookies for it.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
     ///
-    /// let config = AxumSessionConfig::default().with_secure(true);
+    /// let config = AxumSessionConfig::default().with_secure(false);
     /// ```
     ///
     #[must_use]
@@ -335,8 +797,151 @@ impl AxumSessionConfig {
         self
     }
 
+    /// Set's the session to only use the database and not keep it within memory between requests.
+    ///
+    /// Each request loads the Session from the database and writes any changes back once the
+    /// response is returned. Use this when several instances share a database without sticky
+    /// sessions so an instance never uses a stale memory copy. Does nothing without a database.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_database_only(true);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_database_only(mut self, is_set: bool) -> Self {
+        self.database_only = is_set;
+        self
+    }
+
+    /// Set's new Sessions to only be created once something is set within them.
+    ///
+    /// Requests without a Session, like bots and health checks, get a Session that only exists
+    /// for the request. Reading it returns None and nothing is kept in memory, stored or sent as a
+    /// cookie. The first call that sets something, like set, set_store or set_longterm, creates
+    /// the Session and the layer sends its cookie once the response is returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum::{body::Body, routing::get, Router};
+    /// use axum_database_sessions::{AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+    /// use http::{header::SET_COOKIE, Request};
+    /// use tower_service::Service;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let config = AxumSessionConfig::default().with_lazy_sessions(true);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// let mut app = Router::new()
+    ///     .route("/", get(|| async { "hello" }))
+    ///     .route("/read", get(|session: AxumSession<AxumNullPool>| async move {
+    ///         session.get::<String>("name").await.unwrap_or_default()
+    ///     }))
+    ///     .route("/login", get(|session: AxumSession<AxumNullPool>| async move {
+    ///         assert_eq!(session.get::<String>("name").await, None);
+    ///         session.set("name", "bob").await;
+    ///         session.get::<String>("name").await.unwrap_or_default()
+    ///     }))
+    ///     .layer(AxumSessionLayer::new(session_store.clone()));
+    ///
+    /// // Sessions that are never set are not kept and get no cookie.
+    /// for uri in ["/", "/read"] {
+    ///     let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    ///     let response = app.call(request).await.unwrap();
+    ///     assert!(response.headers().get(SET_COOKIE).is_none());
+    /// }
+    ///
+    /// assert_eq!(session_store.health().await.unwrap().memory_sessions, 0);
+    ///
+    /// // Setting a value after reading creates the Session and sends its cookie.
+    /// let request = Request::builder().uri("/login").body(Body::empty()).unwrap();
+    /// let response = app.call(request).await.unwrap();
+    /// assert!(response.headers().get(SET_COOKIE).is_some());
+    /// assert_eq!(session_store.health().await.unwrap().memory_sessions, 1);
+    /// # }
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_lazy_sessions(mut self, is_set: bool) -> Self {
+        self.lazy_sessions = is_set;
+        self
+    }
+
+    /// Set's the session to get a new ID once it is accepted as storable. Default is true
+    ///
+    /// The ID given out before the user accepted was not protected like a stored Session's ID
+    /// and could have leaked, so it is replaced during the request session.set_store(true) is
+    /// called within, like session.renew() does but keeping the user id. Set this to false if
+    /// the app needs the ID to stay the same once accepted.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_renew_on_accept(false);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_renew_on_accept(mut self, is_set: bool) -> Self {
+        self.renew_on_accept = is_set;
+        self
+    }
+
+    /// Set's the paths whose requests pass through the layer without a Session.
+    ///
+    /// Use this for health checks, metrics and static files so they never load or create a
+    /// Session or get a cookie. Patterns are exact paths where `*` matches anything within one
+    /// path segment and `**` matches anything including further segments, like `/health`,
+    /// `/static/**` or `/*.ico`. Each pattern must start with `/`.
+    ///
+    /// Extracting `Option<AxumSession>` within those paths returns None.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use axum::{body::Body, routing::get, Router};
+    /// use axum_database_sessions::{AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+    /// use http::{header::SET_COOKIE, Request};
+    /// use tower_service::Service;
+    ///
+    /// let config = AxumSessionConfig::default().with_excluded_paths(["/health", "/static/**"]);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// let mut app = Router::new()
+    ///     .route("/health", get(|session: Option<AxumSession<AxumNullPool>>| async move {
+    ///         assert!(session.is_none());
+    ///     }))
+    ///     .route("/static/css/site.css", get(|| async { "body {}" }))
+    ///     .layer(AxumSessionLayer::new(session_store.clone()));
+    ///
+    /// for uri in ["/health", "/static/css/site.css"] {
+    ///     let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    ///     let response = app.call(request).await.unwrap();
+    ///     assert!(response.headers().get(SET_COOKIE).is_none());
+    /// }
+    ///
+    /// assert_eq!(session_store.stats().await.unwrap().memory_sessions, 0);
+    /// # }
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_excluded_paths(
+        mut self,
+        patterns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.excluded_paths = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Set's the session's database table name.
     ///
+    /// The table name may only contain `[A-Za-z0-9_]` as it is inserted directly into queries.
+    /// This is checked when the AxumSessionStore is constructed. For Redis it is used as the key prefix.
+    ///
     /// # Examples
     /// ```rust
     /// use axum_database_sessions::AxumSessionConfig;
@@ -350,51 +955,858 @@ impl AxumSessionConfig {
         self
     }
 
-    /// Set's the session's cookie encyption key enabling private cookies.
+    /// Set's the session's database schema name for Postgres or keyspace for Scylla.
     ///
-    /// When Set it will enforce Private cookies across all Sessions.
-    /// If you use Key::generate() it will make a new key each server reboot.
-    /// To prevent this make and save a key to a config file for long term usage.
-    /// For Extra Security Regenerate the key every so many months to a year.
-    /// A new key will invalidate all old Sessions so it be wise to run session_store.clear_store() on reboot.
+    /// When set the table is created and queried as `schema_name.table_name`.
+    /// For Postgres the schema is created during AxumSessionStore::initiate() if it does not exist.
+    /// For Scylla the keyspace must already exist since it requires a replication strategy.
+    /// The schema name may only contain `[A-Za-z0-9_]`.
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{Key, AxumSessionConfig};
+    /// use axum_database_sessions::AxumSessionConfig;
     ///
-    /// let config = AxumSessionConfig::default().with_key(Key::generate());
+    /// let config = AxumSessionConfig::default().with_schema_name("infra");
     /// ```
     ///
+    #[cfg(any(
+        feature = "postgres-rustls",
+        feature = "postgres-native",
+        feature = "postgres-deadpool",
+        feature = "scylla-db"
+    ))]
     #[must_use]
-    pub fn with_key(mut self, key: Key) -> Self {
-        self.key = Some(key);
+    pub fn with_schema_name(mut self, schema_name: impl Into<Cow<'static, str>>) -> Self {
+        self.schema_name = Some(schema_name.into());
         self
     }
-}
 
-impl Default for AxumSessionConfig {
-    fn default() -> Self {
-        Self {
-            /// Set to a 6 hour default in Database Session stores unloading.
-            lifespan: Duration::hours(6),
-            cookie_name: "sqlx_session".into(),
-            cookie_path: "/".into(),
-            cookie_max_age: Some(Duration::days(100)),
-            cookie_http_only: true,
-            cookie_secure: false,
-            cookie_domain: None,
-            cookie_same_site: SameSite::None,
-            storable_cookie_name: "session_acceptance".into(),
-            storable_cookie_max_age: Some(Duration::days(100)),
+    /// Set's what initiate does to the database's table.
+    ///
+    /// Use `Migrations::CheckOnly` or `Migrations::Skip` when the table is created by other
+    /// migration tools, like using the statements from AxumSessionStore::migration_sql.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, Migrations};
+    ///
+    /// let config = AxumSessionConfig::default().with_migrations(Migrations::Skip);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_migrations(mut self, migrations: Migrations) -> Self {
+        self.migrations = migrations;
+        self
+    }
+
+    /// Checks the configuration returning every problem found.
+    ///
+    /// This is called by AxumSessionStore::try_new() which returns any problems and by
+    /// AxumSessionStore::new() which panics listing them. Call this yourself to check the
+    /// config before the store is made.
+    ///
+    /// # Errors
+    /// - ['SessionConfigError'] listing every problem such as empty cookie names, lifetimes of 0,
+    ///   cookie paths not starting with `/` or invalid table names.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    /// use chrono::Duration;
+    ///
+    /// let config = AxumSessionConfig::default().validate().unwrap();
+    ///
+    /// let error = AxumSessionConfig::default()
+    ///     .with_cookie_name("")
+    ///     .with_lifetime(Duration::zero())
+    ///     .validate()
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(error.problems.len(), 2);
+    /// ```
+    ///
+    pub fn validate(self) -> Result<Self, SessionConfigError> {
+        let problems = self.problems();
+
+        if problems.is_empty() {
+            Ok(self)
+        } else {
+            Err(SessionConfigError { problems })
+        }
+    }
+
+    /// Set's the session's cookie encyption key enabling private cookies.
+    ///
+    /// When Set it will enforce Private or Signed cookies across all Sessions.
+    /// See [`AxumSessionConfig::with_cookie_security`].
+    /// Cookies that fail to decrypt or verify are ignored and a new Session is created.
+    /// If you use Key::generate() it will make a new key each server reboot.
+    /// To prevent this make and save a key to a config file for long term usage.
+    /// For Extra Security Regenerate the key every so many months to a year.
+    /// A new key will invalidate all old Sessions so it be wise to run session_store.clear_store() on reboot.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{Key, AxumSessionConfig};
+    ///
+    /// let config = AxumSessionConfig::default().with_key(Key::generate());
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_key(mut self, key: Key) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Set's old keys that are still accepted when reading the session's cookies.
+    ///
+    /// This allows rotating the Key without logging everyone out. Cookies are read using the
+    /// Key set with [`AxumSessionConfig::with_key`] first and then each secondary key in order.
+    /// A response to cookies read using a secondary key re-issues them using the primary Key
+    /// so once all active Sessions have made a request the old keys can be removed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, Key};
+    ///
+    /// let old_key = Key::generate();
+    /// let config = AxumSessionConfig::default()
+    ///     .with_key(Key::generate())
+    ///     .with_secondary_keys(vec![old_key]);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_secondary_keys(mut self, keys: Vec<Key>) -> Self {
+        self.secondary_keys = keys;
+        self
+    }
+
+    /// Set's the generator used to create and validate Session IDs.
+    ///
+    /// Cookies with a Session ID that fails validation are ignored and a new Session is created.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, UuidGenerator};
+    ///
+    /// let config = AxumSessionConfig::default().with_id_generator(UuidGenerator);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_id_generator(mut self, generator: impl SessionIdGenerator + 'static) -> Self {
+        self.id_generator = Arc::new(generator);
+        self
+    }
+
+    /// Set's the handler that receives the Session lifecycle events.
+    ///
+    /// See [`SessionEventHandler`] for when each event is called.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_event_handler(AuditLog);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_event_handler(mut self, handler: impl SessionEventHandler + 'static) -> Self {
+        self.event_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Set's the handler making the response of a SessionRejection, like to return the
+    /// application's own error body or redirect to an error page.
+    ///
+    /// It is used for the rejections passing back through this layer: Sessions of another layer
+    /// extracted within its routes, CsrfLayer's invalid tokens and rate limited new Sessions.
+    /// Routes without any AxumSessionLayer always get the default response.
+    /// Default is None which uses the SessionRejection's own response.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use axum::{body::Body, response::IntoResponse, routing::get, Json, Router};
+    /// use axum_database_sessions::{AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, SessionRejection};
+    /// use http::{Request, StatusCode};
+    /// use tower_service::Service;
+    ///
+    /// struct Admin;
+    ///
+    /// let config = AxumSessionConfig::default().with_rejection_handler(|rejection: SessionRejection| {
+    ///     (rejection.status(), Json(serde_json::json!({ "error": "session_unavailable" })))
+    /// });
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// let mut app = Router::new()
+    ///     .route("/admin", get(|_: AxumSession<AxumNullPool, Admin>| async {}))
+    ///     .layer(AxumSessionLayer::new(session_store))
+    ///     .route("/plain", get(|_: AxumSession<AxumNullPool>| async {}));
+    ///
+    /// async fn body(response: axum::response::Response) -> String {
+    ///     let mut body = response.into_body();
+    ///     let bytes = http_body::Body::data(&mut body).await.unwrap().unwrap();
+    ///     String::from_utf8(bytes.to_vec()).unwrap()
+    /// }
+    ///
+    /// // The Admin Session has no layer but its rejection passes through the default layer.
+    /// let request = Request::builder().uri("/admin").body(Body::empty()).unwrap();
+    /// let response = app.call(request).await.unwrap();
+    /// assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    /// assert_eq!(body(response).await, r#"{"error":"session_unavailable"}"#);
+    ///
+    /// // Routes without any layer get the default response.
+    /// let request = Request::builder().uri("/plain").body(Body::empty()).unwrap();
+    /// let response = app.call(request).await.unwrap();
+    /// assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    /// assert_eq!(
+    ///     body(response).await,
+    ///     "Can't extract AxumSession<axum_database_sessions::databases::null::AxumNullPool, ()>. Is `AxumSessionLayer` enabled for it?"
+    /// );
+    /// # }
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_rejection_handler<R: IntoResponse>(
+        mut self,
+        handler: impl Fn(SessionRejection) -> R + Send + Sync + 'static,
+    ) -> Self {
+        self.rejection_handler = Some(Arc::new(move |rejection| {
+            handler(rejection).into_response()
+        }));
+        self
+    }
+
+    /// Set's what happens when a Session is used from another IP than the one it was created from.
+    ///
+    /// The IP is read from axum's `ConnectInfo<SocketAddr>` so the app must be served using
+    /// `into_make_service_with_connect_info::<SocketAddr>()`. Requests without it are not checked.
+    /// Use [`AxumSessionConfig::with_trusted_proxies`] when the app runs behind a reverse proxy.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, IpBinding};
+    ///
+    /// let config = AxumSessionConfig::default().with_ip_binding(IpBinding::Reject);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_ip_binding(mut self, binding: IpBinding) -> Self {
+        self.ip_binding = binding;
+        self
+    }
+
+    /// Set's how many leading bits of the client's IP must match the Session's IP.
+    ///
+    /// IPv6 privacy extensions change the last 64 bits often so a /64 prefix keeps those
+    /// clients from losing their Session. The Defaults are 32 and 128 which must match exactly.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_ip_prefix(32, 64);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_ip_prefix(mut self, ipv4: u8, ipv6: u8) -> Self {
+        self.ip_prefix_v4 = ipv4;
+        self.ip_prefix_v6 = ipv6;
+        self
+    }
+
+    /// Set's the reverse proxies whose `Forwarded` and `X-Forwarded-For` headers are trusted.
+    ///
+    /// These headers are ignored unless the request came from one of these IPs, since
+    /// otherwise any client could set them. The client's IP is the last address within the
+    /// headers that is not one of these proxies.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// let config = AxumSessionConfig::default()
+    ///     .with_trusted_proxies([IpAddr::V4(Ipv4Addr::LOCALHOST)]);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_trusted_proxies(mut self, proxies: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.trusted_proxies = proxies.into_iter().collect();
+        self
+    }
+
+    /// Set's the session to be bound to the User-Agent it was created with.
+    ///
+    /// Only a SHA-256 hash of the User-Agent is stored. Requests with another User-Agent get a
+    /// new Session and SessionEventHandler::on_user_agent_mismatch is called. Sessions created
+    /// without a User-Agent are bound to the first one sent, while requests that stop sending
+    /// one are treated as a mismatch.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_user_agent_binding(true);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_user_agent_binding(mut self, is_set: bool) -> Self {
+        self.user_agent_binding = is_set;
+        self
+    }
+
+    /// Set's the User-Agent to have its digits removed before it is hashed.
+    ///
+    /// Browsers update themselves mid Session which changes the version numbers within the
+    /// User-Agent. Removing them keeps these clients from getting a new Session. Changing this
+    /// gives every bound Session a new Session on its next request.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default()
+    ///     .with_user_agent_binding(true)
+    ///     .with_user_agent_normalization(true);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_user_agent_normalization(mut self, is_set: bool) -> Self {
+        self.user_agent_normalization = is_set;
+        self
+    }
+
+    /// Set's the most new Sessions a client IP can create within the period.
+    ///
+    /// Requests from clients past the limit that do not have an existing Session either get a
+    /// Session that is dropped once the request is done or are answered with 429 Too Many
+    /// Requests. The IP is read the same way as for [`AxumSessionConfig::with_ip_binding`] so
+    /// [`AxumSessionConfig::with_trusted_proxies`] applies and requests without it are not limited.
+    /// Lazy Sessions are created by the handler so they are not limited. The counts are kept
+    /// within memory for each instance.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, RateLimitAction};
+    /// use chrono::Duration;
+    ///
+    /// let config = AxumSessionConfig::default()
+    ///     .with_session_rate_limit(30, Duration::minutes(1), RateLimitAction::Throwaway);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_session_rate_limit(
+        mut self,
+        max: u32,
+        period: Duration,
+        action: RateLimitAction,
+    ) -> Self {
+        self.session_rate_limit = Some((max, period, action));
+        self
+    }
+
+    /// Set's how the `DNT: 1` and `Sec-GPC: 1` privacy headers are used as the client declining cookies.
+    ///
+    /// Requests declining cookies get a Session that only exists for the request. It is never
+    /// stored or sent as a cookie, no matter the AxumSessionMode, and the client's own Session is
+    /// left alone. Sessions using the session header or bearer tokens ignore the headers.
+    ///
+    /// The acceptance of a request is decided in this order:
+    /// 1. session.set_store(true) within a handler always wins. The Session is stored and sent,
+    ///    and its later requests are no longer declined by the headers.
+    /// 2. A request sending either header declines cookies.
+    /// 3. The acceptance cookie sent by an earlier response.
+    /// 4. `PrivacySignals::HonorOrAccept` accepts requests sending neither header.
+    /// 5. The AxumSessionMode.
+    ///
+    /// Default is `PrivacySignals::Ignore`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use axum::{body::Body, routing::get, Router};
+    /// use axum_database_sessions::{AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionMode, AxumSessionStore, PrivacySignals};
+    /// use http::{header::{COOKIE, SET_COOKIE}, Request, Response};
+    /// use tower_service::Service;
+    ///
+    /// fn app(signals: PrivacySignals, mode: AxumSessionMode) -> Router {
+    ///     let config = AxumSessionConfig::default().with_privacy_signals(signals).with_mode(mode);
+    ///     let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    ///     Router::new()
+    ///         .route("/", get(|session: AxumSession<AxumNullPool>| async move {
+    ///             session.set("visited", true).await;
+    ///         }))
+    ///         .route("/accept", get(|session: AxumSession<AxumNullPool>| async move {
+    ///             session.set_store(true).await;
+    ///         }))
+    ///         .layer(AxumSessionLayer::new(session_store))
+    /// }
+    ///
+    /// fn cookies<B>(response: &Response<B>) -> Vec<String> {
+    ///     response.headers().get_all(SET_COOKIE).iter()
+    ///         .map(|value| value.to_str().unwrap().split(';').next().unwrap().to_owned())
+    ///         .collect()
+    /// }
+    ///
+    /// let request = |uri: &str, header: Option<(&str, &str)>, cookie: &[String]| {
+    ///     let mut builder = Request::builder().uri(uri).header(COOKIE, cookie.join("; "));
+    ///
+    ///     if let Some((name, value)) = header {
+    ///         builder = builder.header(name, value);
+    ///     }
+    ///
+    ///     builder.body(Body::empty()).unwrap()
+    /// };
+    ///
+    /// // The headers decline cookies even when every Session is storable.
+    /// let mut honor = app(PrivacySignals::Honor, AxumSessionMode::Always);
+    /// let response = honor.call(request("/", Some(("DNT", "1")), &[])).await.unwrap();
+    /// assert!(cookies(&response).is_empty());
+    /// let response = honor.call(request("/", Some(("Sec-GPC", "1")), &[])).await.unwrap();
+    /// assert!(cookies(&response).is_empty());
+    ///
+    /// // Other values and requests without the headers work as usual.
+    /// let response = honor.call(request("/", Some(("DNT", "0")), &[])).await.unwrap();
+    /// assert!(!cookies(&response).is_empty());
+    ///
+    /// // Accepting within a handler wins over the headers, also on the requests after it.
+    /// let response = honor.call(request("/accept", Some(("Sec-GPC", "1")), &[])).await.unwrap();
+    /// let accepted = cookies(&response);
+    /// assert!(accepted.contains(&"session_acceptance=true".to_owned()));
+    /// let response = honor.call(request("/", Some(("Sec-GPC", "1")), &accepted)).await.unwrap();
+    /// assert!(response.status().is_success());
+    ///
+    /// // Ignore leaves the headers unused.
+    /// let mut ignore = app(PrivacySignals::Ignore, AxumSessionMode::Always);
+    /// let response = ignore.call(request("/", Some(("DNT", "1")), &[])).await.unwrap();
+    /// assert!(!cookies(&response).is_empty());
+    ///
+    /// // HonorOrAccept accepts new clients without the headers and still declines with them.
+    /// let mut implicit = app(PrivacySignals::HonorOrAccept, AxumSessionMode::Storable);
+    /// let response = implicit.call(request("/", None, &[])).await.unwrap();
+    /// assert!(cookies(&response).contains(&"session_acceptance=true".to_owned()));
+    /// let response = implicit.call(request("/", Some(("DNT", "1")), &[])).await.unwrap();
+    /// assert!(cookies(&response).is_empty());
+    ///
+    /// // An acceptance cookie from before wins over accepting without the headers.
+    /// let declined = ["session_acceptance=false".to_owned()];
+    /// let response = implicit.call(request("/", None, &declined)).await.unwrap();
+    /// assert!(cookies(&response).contains(&"session_acceptance=false".to_owned()));
+    /// # }
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_privacy_signals(mut self, signals: PrivacySignals) -> Self {
+        self.privacy_signals = signals;
+        self
+    }
+
+    /// Set's the most Sessions a user id can be set on at once, like for a limit of devices per account.
+    ///
+    /// A max of 0 is raised to 1 so every user can have at least one Session.
+    ///
+    /// session.set_store_user_id() counts the user's other unexpired Sessions within the database
+    /// and memory so the limit holds across instances. Past the limit `OverflowPolicy::Reject`
+    /// returns SessionError::TooManySessions from session.try_set_store_user_id() while
+    /// `OverflowPolicy::DestroyOldest` destroys the Sessions used the longest time ago.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum::{body::Body, routing::get, Router};
+    /// use axum_database_sessions::{AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, OverflowPolicy};
+    /// use http::{header::{COOKIE, SET_COOKIE}, Request, StatusCode};
+    /// use tower_service::Service;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let config = AxumSessionConfig::default().with_max_sessions_per_user(2, OverflowPolicy::DestroyOldest);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// let mut app = Router::new()
+    ///     .route("/login", get(|session: AxumSession<AxumNullPool>| async move {
+    ///         session.set_store_user_id("42").await;
+    ///     }))
+    ///     .route("/user", get(|session: AxumSession<AxumNullPool>| async move {
+    ///         match session.store_user_id().await {
+    ///             Some(_) => StatusCode::OK,
+    ///             None => StatusCode::UNAUTHORIZED,
+    ///         }
+    ///     }))
+    ///     .layer(AxumSessionLayer::new(session_store.clone()));
+    ///
+    /// let mut cookies = Vec::new();
+    ///
+    /// for _ in 0..3 {
+    ///     let request = Request::builder().uri("/login").body(Body::empty()).unwrap();
+    ///     let response = app.call(request).await.unwrap();
+    ///     let cookie = response.headers().get_all(SET_COOKIE).iter()
+    ///         .map(|value| value.to_str().unwrap().split(';').next().unwrap())
+    ///         .find(|cookie| cookie.starts_with("sqlx_session"))
+    ///         .unwrap()
+    ///         .to_owned();
+    ///     cookies.push(cookie);
+    /// }
+    ///
+    /// // The third login destroyed the first device's Session.
+    /// for (cookie, status) in cookies.iter().zip([StatusCode::UNAUTHORIZED, StatusCode::OK, StatusCode::OK]) {
+    ///     let request = Request::builder().uri("/user").header(COOKIE, cookie).body(Body::empty()).unwrap();
+    ///     assert_eq!(app.call(request).await.unwrap().status(), status);
+    /// }
+    /// # }
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_max_sessions_per_user(mut self, max: usize, policy: OverflowPolicy) -> Self {
+        self.max_sessions_per_user = Some((std::cmp::max(max, 1), policy));
+        self
+    }
+
+    /// Set's the format used to store the session's data within the database.
+    ///
+    /// Changing this makes existing Sessions fail to load with ['SessionError::SerializerMismatch']
+    /// so they are replaced by new Sessions. Binary formats need the `bincode-serializer` or
+    /// `msgpack-serializer` feature.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, SessionSerializer};
+    ///
+    /// let config = AxumSessionConfig::default().with_serializer(SessionSerializer::Json);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_serializer(mut self, serializer: SessionSerializer) -> Self {
+        self.serializer = serializer;
+        self
+    }
+
+    /// Set's the prefix of the metric names recorded with the `metrics` feature.
+    ///
+    /// Metrics are named like `{prefix}_created_total`. Without the feature this does nothing.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::AxumSessionConfig;
+    ///
+    /// let config = AxumSessionConfig::default().with_metrics_prefix("my_app_session");
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_metrics_prefix(mut self, prefix: impl Into<Cow<'static, str>>) -> Self {
+        self.metrics_prefix = prefix.into();
+        self
+    }
+
+    /// Set's how the session's cookies are secured when a Key is set.
+    ///
+    /// `CookieSecurity::Signed` lets the layer reject forged Session IDs without a database lookup.
+    /// `CookieSecurity::Private` also hides the Session ID from the client.
+    /// This does nothing unless [`AxumSessionConfig::with_key`] is also used.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, CookieSecurity, Key};
+    ///
+    /// let config = AxumSessionConfig::default()
+    ///     .with_key(Key::generate())
+    ///     .with_cookie_security(CookieSecurity::Signed);
+    /// ```
+    ///
+    #[must_use]
+    pub fn with_cookie_security(mut self, security: CookieSecurity) -> Self {
+        self.cookie_security = security;
+        self
+    }
+}
+
+impl AxumSessionConfig {
+    /// Checks that the table and schema names are not empty and only contain `[A-Za-z0-9_]`.
+    pub(crate) fn is_valid_table_name(&self) -> bool {
+        let is_valid = |name: &str| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+
+        is_valid(&self.table_name) && self.schema_name.as_deref().is_none_or(is_valid)
+    }
+
+    /// Gathers every problem within the configuration as readable messages.
+    ///
+    /// Also checks the requirements browsers enforce for `__Host-` and `__Secure-` cookie names.
+    pub(crate) fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let is_valid = |value: &str| !value.chars().any(|c| c == ';' || c.is_ascii_control());
+        let is_valid_name = |value: &str| {
+            !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_graphic() && !"()<>@,;:\\\"/[]?={}".contains(c))
+        };
+
+        for name in [&self.cookie_name, &self.storable_cookie_name] {
+            if !is_valid_name(name) {
+                problems.push(format!(
+                    "cookie name \"{}\" must not be empty or contain whitespace or separators like ; and =",
+                    name
+                ));
+            }
+        }
+
+        for name in [&self.session_header, &self.bearer_header]
+            .into_iter()
+            .flatten()
+        {
+            if http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                problems.push(format!("header \"{}\" is not a valid header name", name));
+            }
+        }
+
+        if self.cookie_name == self.storable_cookie_name {
+            problems.push(format!(
+                "cookie name and storable cookie name must be different but are both \"{}\"",
+                self.cookie_name
+            ));
+        }
+
+        if !self.cookie_path.starts_with('/') || !is_valid(&self.cookie_path) {
+            problems.push(format!(
+                "cookie path \"{}\" must start with / and not contain ; or control characters",
+                self.cookie_path
+            ));
+        }
+
+        if let Some(domain) = &self.cookie_domain {
+            if domain.is_empty() || domain.contains(char::is_whitespace) || !is_valid(domain) {
+                problems.push(format!(
+                    "cookie domain \"{}\" must not be empty or contain whitespace, ; or control characters",
+                    domain
+                ));
+            }
+        }
+
+        let secure = self.cookie_secure || self.cookie_same_site == SameSite::None;
+
+        for name in [&self.cookie_name, &self.storable_cookie_name] {
+            if (name.starts_with("__Host-") || name.starts_with("__Secure-")) && !secure {
+                problems.push(format!(
+                    "cookie name \"{}\" requires with_secure(true)",
+                    name
+                ));
+            }
+
+            if name.starts_with("__Host-")
+                && (self.cookie_domain.is_some() || self.cookie_path != "/")
+            {
+                problems.push(format!(
+                    "cookie name \"{}\" requires with_cookie_domain(None) and with_cookie_path(\"/\")",
+                    name
+                ));
+            }
+        }
+
+        for (name, age) in [
+            ("max age", self.cookie_max_age),
+            ("storable max age", self.storable_cookie_max_age),
+        ] {
+            if age.is_some_and(|age| age <= Duration::zero()) {
+                problems.push(format!("cookie {} must be greater than 0 or None", name));
+            }
+
+            if age.is_some_and(|age| age > Duration::seconds(MAX_LIFESPAN_SECS)) {
+                problems.push(format!("cookie {} must not be longer than 100 years", name));
+            }
+        }
+
+        for (name, lifespan) in [
+            ("lifetime", self.lifespan),
+            ("max lifetime", self.max_lifespan),
+            ("memory lifetime", self.memory_lifespan),
+            ("shutdown timeout", self.shutdown_timeout),
+        ] {
+            if lifespan <= Duration::zero() {
+                problems.push(format!("{} must be greater than 0", name));
+            }
+
+            if lifespan > Duration::seconds(MAX_LIFESPAN_SECS) {
+                problems.push(format!("{} must not be longer than 100 years", name));
+            }
+        }
+
+        if let ExpiryPolicy::Hybrid { idle, max } = self.expiry_policy {
+            if idle <= Duration::zero() || max < idle {
+                problems.push(
+                    "expiry policy idle must be greater than 0 and not longer than max".to_owned(),
+                );
+            }
+
+            if max > Duration::seconds(MAX_LIFESPAN_SECS) {
+                problems.push("expiry policy max must not be longer than 100 years".to_owned());
+            }
+        }
+
+        if self.cookie_refresh_interval <= Duration::zero() {
+            problems.push("cookie refresh interval must be greater than 0".to_owned());
+        }
+
+        if self.touch_granularity < Duration::zero() {
+            problems.push("touch granularity must not be negative".to_owned());
+        }
+
+        if self
+            .idle_timeout
+            .is_some_and(|idle| idle <= Duration::zero())
+        {
+            problems.push("idle timeout must be greater than 0 or None".to_owned());
+        }
+
+        if self
+            .idle_timeout
+            .is_some_and(|idle| idle > Duration::seconds(MAX_LIFESPAN_SECS))
+        {
+            problems.push("idle timeout must not be longer than 100 years".to_owned());
+        }
+
+        if self.memory_max_sessions == Some(0) {
+            problems.push("memory max sessions must be greater than 0 or None".to_owned());
+        }
+
+        if self.ip_prefix_v4 > 32 || self.ip_prefix_v6 > 128 {
+            problems.push(format!(
+                "ip prefix /{} and /{} must not be longer than /32 and /128",
+                self.ip_prefix_v4, self.ip_prefix_v6
+            ));
+        }
+
+        for pattern in &self.excluded_paths {
+            if !pattern.starts_with('/') {
+                problems.push(format!("excluded path \"{}\" must start with /", pattern));
+            }
+        }
+
+        if self.max_lifespan < self.lifespan {
+            problems.push("max lifetime must not be shorter than lifetime".to_owned());
+        }
+
+        if !self.is_valid_table_name() {
+            problems.push(format!(
+                "table name \"{}\" may only contain [A-Za-z0-9_]",
+                self.database_table_name()
+            ));
+        }
+
+        problems
+    }
+
+    /// Checks if the client's IP is within the Session's IP prefix.
+    ///
+    /// IPv4 addresses mapped into IPv6 are compared as IPv4.
+    pub(crate) fn ip_matches(&self, bound: IpAddr, client: IpAddr) -> bool {
+        match (bound.to_canonical(), client.to_canonical()) {
+            (IpAddr::V4(bound), IpAddr::V4(client)) => {
+                let bits = u32::from(bound) ^ u32::from(client);
+                bits.checked_shr(32 - u32::from(self.ip_prefix_v4.min(32)))
+                    .unwrap_or(0)
+                    == 0
+            }
+            (IpAddr::V6(bound), IpAddr::V6(client)) => {
+                let bits = u128::from(bound) ^ u128::from(client);
+                bits.checked_shr(128 - u32::from(self.ip_prefix_v6.min(128)))
+                    .unwrap_or(0)
+                    == 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks if the path matches one of the excluded path patterns.
+    pub(crate) fn is_excluded(&self, path: &str) -> bool {
+        self.excluded_paths
+            .iter()
+            .any(|pattern| glob_matches(pattern.as_bytes(), path.as_bytes()))
+    }
+
+    /// Returns the table name used within the database queries.
+    ///
+    /// If a schema name is set this returns `schema_name.table_name`.
+    pub(crate) fn database_table_name(&self) -> Cow<'static, str> {
+        match &self.schema_name {
+            Some(schema) => format!("{}.{}", schema, self.table_name).into(),
+            None => self.table_name.clone(),
+        }
+    }
+}
+
+impl Default for AxumSessionConfig {
+    fn default() -> Self {
+        Self {
+            // Set to a 6 hour default in Database Session stores unloading.
+            lifespan: Duration::hours(6),
+            expiry_policy: ExpiryPolicy::Sliding,
+            idle_timeout: None,
+            cookie_name: "sqlx_session".into(),
+            cookie_path: "/".into(),
+            cookie_max_age: Some(Duration::days(100)),
+            browser_session_cookie: false,
+            cookie_refresh_interval: Duration::days(1),
+            touch_granularity: Duration::minutes(1),
+            session_header: None,
+            bearer_header: None,
+            cookie_http_only: true,
+            cookie_secure: true,
+            cookie_domain: None,
+            cookie_same_site: SameSite::Lax,
+            storable_cookie_name: "session_acceptance".into(),
+            storable_cookie_max_age: Some(Duration::days(100)),
             table_name: "async_sessions".into(),
+            database_only: false,
+            lazy_sessions: false,
+            renew_on_accept: true,
+            excluded_paths: Vec::new(),
+            schema_name: None,
+            migrations: Migrations::Run,
             max_connections: 5,
-            /// Unload memory after 60 minutes if it has not been accessed.
+            // Unload memory after 60 minutes if it has not been accessed.
             memory_lifespan: Duration::minutes(60),
-            /// Unload long term session after 60 days if it has not been accessed.
+            memory_max_sessions: None,
+            shutdown_timeout: Duration::seconds(30),
+            // Unload long term session after 60 days if it has not been accessed.
             max_lifespan: Duration::days(60),
             session_mode: AxumSessionMode::Always,
-            /// Makes a Random Key on each Boot if not set statically. Will affect long term cookies.
+            // Makes a Random Key on each Boot if not set statically. Will affect long term cookies.
             key: None,
+            secondary_keys: Vec::new(),
+            cookie_security: CookieSecurity::Private,
+            id_generator: Arc::new(UuidGenerator),
+            event_handler: None,
+            rejection_handler: None,
+            ip_binding: IpBinding::Disabled,
+            ip_prefix_v4: 32,
+            ip_prefix_v6: 128,
+            trusted_proxies: Vec::new(),
+            user_agent_binding: false,
+            user_agent_normalization: false,
+            session_rate_limit: None,
+            privacy_signals: PrivacySignals::Ignore,
+            max_sessions_per_user: None,
+            serializer: SessionSerializer::Json,
+            metrics_prefix: "axum_session".into(),
         }
     }
 }
+
+/// Matches a path against an excluded path pattern.
+///
+/// `*` matches anything besides `/` and `**` matches anything.
+fn glob_matches(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => (0..=path.len()).any(|i| glob_matches(rest, &path[i..])),
+        [b'*', rest @ ..] => (0..=path.len())
+            .take_while(|&i| i == 0 || path[i - 1] != b'/')
+            .any(|i| glob_matches(rest, &path[i..])),
+        [c, rest @ ..] => path.first() == Some(c) && glob_matches(rest, &path[1..]),
+    }
+}
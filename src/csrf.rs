@@ -0,0 +1,270 @@
+use crate::{AxumDatabasePool, AxumSession, SessionRejection};
+use axum_core::{
+    body::{self, BoxBody},
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use bytes::{Bytes, BytesMut};
+use futures::future::BoxFuture;
+use http::{header::CONTENT_TYPE, Method, Request};
+use http_body::Body as HttpBody;
+use std::{
+    convert::Infallible,
+    fmt::{self, Debug, Formatter},
+    marker::{PhantomData, Send, Sync},
+    task::{Context, Poll},
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Header checked for the CSRF token.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Form field checked for the CSRF token when the header is missing.
+pub const CSRF_FIELD: &str = "csrf_token";
+
+/// Largest form body read while looking for the CSRF token.
+const MAX_FORM_SIZE: usize = 1024 * 1024;
+
+/// Layer rejecting requests that change state without the Session's CSRF token.
+///
+/// Requests using any method besides GET, HEAD, OPTIONS and TRACE need the token returned by
+/// session.csrf_token() within the `X-CSRF-Token` header or, for
+/// `application/x-www-form-urlencoded` bodies, the `csrf_token` field. Requests without a
+/// valid token are rejected with SessionRejection::InvalidCsrfToken, which is 403 Forbidden.
+///
+/// The Session must exist when this runs so add it before the AxumSessionLayer.
+///
+/// # Examples
+/// ```rust
+/// use axum::{routing::post, Router};
+/// use axum_database_sessions::{AxumNullPool, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, CsrfLayer};
+///
+/// let config = AxumSessionConfig::default();
+/// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+///
+/// let app: Router = Router::new()
+///     .route("/transfer", post(|| async { "sent" }))
+///     .layer(CsrfLayer::<AxumNullPool>::new())
+///     .layer(AxumSessionLayer::new(session_store));
+/// ```
+///
+pub struct CsrfLayer<T, M = ()>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    marker: PhantomData<fn() -> (T, M)>,
+}
+
+impl<T, M> Clone for CsrfLayer<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        CsrfLayer {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, M> Debug for CsrfLayer<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsrfLayer")
+            .field("marker", &std::any::type_name::<M>())
+            .finish()
+    }
+}
+
+impl<T, M> Default for CsrfLayer<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn default() -> Self {
+        CsrfLayer {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, M> CsrfLayer<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    /// Constructs a CsrfLayer checking the Session extracted as `AxumSession<T, M>`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumNullPool, CsrfLayer};
+    ///
+    /// let layer = CsrfLayer::<AxumNullPool>::new();
+    /// ```
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S, T, M> Layer<S> for CsrfLayer<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    type Service = CsrfService<S, T, M>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CsrfService {
+            inner,
+            marker: PhantomData,
+        }
+    }
+}
+
+pub struct CsrfService<S, T, M = ()>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    inner: S,
+    marker: PhantomData<fn() -> (T, M)>,
+}
+
+impl<S, T, M> Clone for CsrfService<S, T, M>
+where
+    S: Clone,
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S, T, M> Debug for CsrfService<S, T, M>
+where
+    S: Debug,
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsrfService")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<S, T, M, ReqBody, ResBody> Service<Request<ReqBody>> for CsrfService<S, T, M>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    ReqBody: HttpBody<Data = Bytes> + From<Bytes> + Unpin + Send + 'static,
+    ReqBody::Error: Into<BoxError>,
+    ResBody: HttpBody<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+    M: Send + Sync + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let not_ready_inner = self.inner.clone();
+        let mut ready_inner = std::mem::replace(&mut self.inner, not_ready_inner);
+
+        Box::pin(async move {
+            if matches!(
+                *req.method(),
+                Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+            ) {
+                return Ok(ready_inner.call(req).await?.map(body::boxed));
+            }
+
+            let session = match req.extensions().get::<AxumSession<T, M>>().cloned() {
+                Some(session) => session,
+                None => {
+                    return Ok(SessionRejection::MissingLayer {
+                        pool: std::any::type_name::<T>(),
+                        marker: std::any::type_name::<M>(),
+                    }
+                    .into_response())
+                }
+            };
+
+            let header = req
+                .headers()
+                .get(CSRF_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned);
+            let is_form = req
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"));
+
+            let (token, req) = match header {
+                Some(token) => (Some(token), req),
+                None if is_form => {
+                    // The body is read to find the field and then given back to the request.
+                    let (parts, body) = req.into_parts();
+                    let bytes = match read_body(body).await {
+                        Ok(bytes) => bytes,
+                        Err(err) => {
+                            tracing::debug!("Failed to read form for CSRF token: {}", err);
+                            return Ok(forbidden());
+                        }
+                    };
+                    let token = serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+                        .ok()
+                        .and_then(|fields| {
+                            fields
+                                .into_iter()
+                                .find(|(key, _)| key == CSRF_FIELD)
+                                .map(|(_, value)| value)
+                        });
+
+                    (token, Request::from_parts(parts, ReqBody::from(bytes)))
+                }
+                None => (None, req),
+            };
+
+            match token {
+                Some(token) if session.verify_csrf(&token).await => {
+                    Ok(ready_inner.call(req).await?.map(body::boxed))
+                }
+                _ => Ok(forbidden()),
+            }
+        })
+    }
+}
+
+fn forbidden() -> Response<BoxBody> {
+    SessionRejection::InvalidCsrfToken.into_response()
+}
+
+/// Reads the whole body, failing once it is larger than MAX_FORM_SIZE.
+async fn read_body<B>(mut body: B) -> Result<Bytes, BoxError>
+where
+    B: HttpBody<Data = Bytes> + Unpin,
+    B::Error: Into<BoxError>,
+{
+    let mut bytes = BytesMut::new();
+
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk.map_err(Into::into)?);
+
+        if bytes.len() > MAX_FORM_SIZE {
+            return Err("form is too large".into());
+        }
+    }
+
+    Ok(bytes.freeze())
+}
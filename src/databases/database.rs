@@ -1,19 +1,117 @@
 use crate::SessionError;
 use async_trait::async_trait;
 
+/// A serialized session given to AxumDatabasePool::store_batch.
+#[derive(Debug, Clone)]
+pub struct SessionRecord {
+    /// The session's id.
+    pub id: String,
+    /// The serialized session.
+    pub session: String,
+    /// Unix timestamp of when the session expires.
+    pub expires: i64,
+    /// The user id set by session.set_store_user_id().
+    pub user_id: Option<String>,
+}
+
 /// The Trait used to identify a database pool.
 /// This can be freely implemented but default implementations for the supported database types are already included
 /// If you're using a custom database library than you should use the Generic*Error in the SessionError enum to indicate an error.
+///
+/// The pool must also be `Clone + Debug + Send + Sync` to be used within AxumSessionStore.
+///
+/// # Examples
+/// ```rust
+/// use async_trait::async_trait;
+/// use axum_database_sessions::{AxumDatabasePool, AxumSessionConfig, AxumSessionStore, SessionError};
+///
+/// #[derive(Debug, Clone)]
+/// pub struct MyPool;
+///
+/// #[async_trait]
+/// impl AxumDatabasePool for MyPool {
+///     async fn initiate(&self, _table_name: &str) -> Result<(), SessionError> {
+///         Ok(())
+///     }
+///
+///     async fn count(&self, _table_name: &str) -> Result<i64, SessionError> {
+///         Ok(0)
+///     }
+///
+///     async fn store(
+///         &self,
+///         _id: &str,
+///         _session: &str,
+///         _expires: i64,
+///         _table_name: &str,
+///     ) -> Result<(), SessionError> {
+///         Err(SessionError::GenericInsertError("my database is read only".into()))
+///     }
+///
+///     async fn load(&self, _id: &str, _table_name: &str) -> Result<Option<String>, SessionError> {
+///         Ok(None)
+///     }
+///
+///     async fn delete_one_by_id(&self, _id: &str, _table_name: &str) -> Result<(), SessionError> {
+///         Ok(())
+///     }
+///
+///     async fn delete_by_expiry(&self, _table_name: &str) -> Result<u64, SessionError> {
+///         Ok(0)
+///     }
+///
+///     async fn delete_all(&self, _table_name: &str) -> Result<(), SessionError> {
+///         Ok(())
+///     }
+/// }
+///
+/// let config = AxumSessionConfig::default();
+/// let session_store = AxumSessionStore::new(Some(MyPool), config);
+/// ```
+///
 #[async_trait]
 pub trait AxumDatabasePool {
     /// This a called to create the table in the database using the given table name.
     /// if an error occurs it should be propagated to the caller.
     async fn initiate(&self, table_name: &str) -> Result<(), SessionError>;
 
-    /// This a called to receive the session count in the database using the given table name.
+    /// This a called to check the table exists with every column using the given table name
+    /// when AxumSessionConfig::with_migrations is set to Migrations::CheckOnly.
+    /// Returns a description of each missing table or column.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default nothing is reported missing.
+    async fn check(&self, _table_name: &str) -> Result<Vec<String>, SessionError> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the statements initiate uses to create the table using the given table name,
+    /// so they can be run by other migration tools. By default this returns None.
+    fn migration_sql(_table_name: &str) -> Option<String>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// This a called to check the database can be reached using the given table name.
+    /// It should be cheap enough to be called every few seconds.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default this loads a session id that does not exist.
+    async fn ping(&self, table_name: &str) -> Result<(), SessionError> {
+        self.load("health-check", table_name).await.map(|_| ())
+    }
+
+    /// This a called to receive the count of unexpired sessions in the database using the given table name.
     /// if an error occurs it should be propagated to the caller.
     async fn count(&self, table_name: &str) -> Result<i64, SessionError>;
 
+    /// This a called to receive the count of expired sessions that were not deleted yet using the given table name.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default this returns 0 for databases that remove expired sessions themselves.
+    async fn count_expired(&self, _table_name: &str) -> Result<i64, SessionError> {
+        Ok(0)
+    }
+
     /// This a called to store a session in the database using the given table name.
     /// The session is a string and should be stored in its own field.
     /// if an error occurs it should be propagated to the caller.
@@ -27,19 +125,146 @@ pub trait AxumDatabasePool {
         table_name: &str,
     ) -> Result<(), SessionError>;
 
+    /// This a called to store a session along with the user id set by session.set_store_user_id().
+    /// The user id should be stored in its own indexed field so sessions can be found by it.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default the user id is ignored and the session is stored using store.
+    async fn store_with_user(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        _user_id: Option<&str>,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        self.store(id, session, expires, table_name).await
+    }
+
+    /// This a called to store many sessions at once when write behind is used.
+    /// Each id is only given once and the sessions should be stored the same as store_with_user.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default each session is stored one at a time using store_with_user.
+    async fn store_batch(
+        &self,
+        sessions: &[SessionRecord],
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        for record in sessions {
+            self.store_with_user(
+                &record.id,
+                &record.session,
+                record.expires,
+                record.user_id.as_deref(),
+                table_name,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
     /// This a called to receive the session from the database using the given table name.
     /// if an error occurs it should be propagated to the caller.
     async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError>;
 
+    /// This a called to receive the sessions stored with the given user id from the database using the given table name.
+    /// Expired sessions should be skipped and the sessions ordered by id so pages stay stable.
+    /// Returns at most limit sessions after skipping offset sessions.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default ['SessionError::GenericNotSupportedError'] is returned since store_with_user ignores the user id.
+    async fn load_by_user_id(
+        &self,
+        _user_id: &str,
+        _offset: i64,
+        _limit: i64,
+        _table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        Err(SessionError::GenericNotSupportedError(
+            "This database does not support loading sessions by user id".to_string(),
+        ))
+    }
+
+    /// This a called to receive a page of session ids from the database using the given table name.
+    /// Expired sessions should be skipped and the ids ordered by expires and then id.
+    /// Returns at most limit ids after skipping offset ids.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default ['SessionError::GenericNotSupportedError'] is returned.
+    async fn load_ids(
+        &self,
+        _offset: i64,
+        _limit: i64,
+        _table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        Err(SessionError::GenericNotSupportedError(
+            "This database does not support listing session ids".to_string(),
+        ))
+    }
+
     /// This a called to delete one session from the database using the given table name.
     /// if an error occurs it should be propagated to the caller.
     async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError>;
 
+    /// This a called to delete all sessions stored with the given user id from the database using the given table name.
+    /// Returns the ids of the deleted sessions.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default ['SessionError::GenericNotSupportedError'] is returned since store_with_user ignores the user id.
+    async fn delete_by_user_id(
+        &self,
+        _user_id: &str,
+        _table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        Err(SessionError::GenericNotSupportedError(
+            "This database does not support deleting sessions by user id".to_string(),
+        ))
+    }
+
     /// This a called to delete all sessions that expired from the database using the given table name.
+    /// Returns how many sessions were deleted or 0 if the database removes them itself.
     /// if an error occurs it should be propagated to the caller.
-    async fn delete_by_expiry(&self, table_name: &str) -> Result<(), SessionError>;
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError>;
+
+    /// This a called to delete all sessions that expired from the database using the given table name
+    /// returning their ids. Only used when a SessionEventHandler is set so it can be told which expired.
+    /// if an error occurs it should be propagated to the caller.
+    /// By default this calls delete_by_expiry and returns no ids.
+    async fn delete_expired_ids(&self, table_name: &str) -> Result<Vec<String>, SessionError> {
+        self.delete_by_expiry(table_name).await?;
+        Ok(Vec::new())
+    }
 
     /// This a called to delete all sessions from the database using the given table name.
     /// if an error occurs it should be propagated to the caller.
     async fn delete_all(&self, table_name: &str) -> Result<(), SessionError>;
 }
+
+/// Returns the short type name of the database pool, like AxumPgPool, for logs and metrics.
+pub(crate) fn backend_name<T>() -> &'static str {
+    std::any::type_name::<T>()
+        .rsplit("::")
+        .next()
+        .unwrap_or_default()
+}
+
+/// The columns every SQL session table needs.
+pub(crate) const SESSION_COLUMNS: [&str; 4] = ["id", "expires", "session", "user_id"];
+
+/// Returns what is missing from a table given the columns it has.
+pub(crate) fn missing_columns(
+    table_name: &str,
+    columns: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    let columns: Vec<String> = columns
+        .into_iter()
+        .map(|column| column.to_lowercase())
+        .collect();
+
+    if columns.is_empty() {
+        return vec![format!("table {}", table_name)];
+    }
+
+    SESSION_COLUMNS
+        .iter()
+        .filter(|column| !columns.iter().any(|found| found == *column))
+        .map(|column| format!("column {}.{}", table_name, column))
+        .collect()
+}
@@ -0,0 +1,301 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use deadpool_postgres::Pool;
+
+pub type AxumDeadpoolPgSession = AxumSession<AxumDeadpoolPgPool>;
+pub type AxumDeadpoolPgSessionStore = AxumSessionStore<AxumDeadpoolPgPool>;
+
+/// deadpool-postgres's Pool type for AxumDatabasePool
+///
+/// Uses the same table layout as AxumPgPool so you can switch between sqlx and
+/// tokio-postgres without migrating data. Statements are prepared and cached per connection.
+#[derive(Clone)]
+pub struct AxumDeadpoolPgPool {
+    pool: Pool,
+}
+
+impl std::fmt::Debug for AxumDeadpoolPgPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AxumDeadpoolPgPool")
+            .field("status", &self.pool.status())
+            .finish()
+    }
+}
+
+impl From<Pool> for AxumDeadpoolPgPool {
+    fn from(pool: Pool) -> Self {
+        AxumDeadpoolPgPool { pool }
+    }
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumDeadpoolPgPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        let client = self.pool.get().await?;
+
+        if let Some((schema_name, _)) = table_name.split_once('.') {
+            client
+                .batch_execute(&format!("CREATE SCHEMA IF NOT EXISTS {}", schema_name))
+                .await?;
+        }
+
+        // Index names can not include the schema so only the table's own name is used.
+        let index_name = table_name.rsplit('.').next().unwrap_or(table_name);
+
+        // Tables created by older versions are missing the user_id column.
+        client
+            .batch_execute(
+                &r#"
+            CREATE TABLE IF NOT EXISTS %%TABLE_NAME%% (
+                "id" VARCHAR(128) NOT NULL PRIMARY KEY,
+                "expires" BIGINT NULL,
+                "session" TEXT NOT NULL,
+                "user_id" VARCHAR(128) NULL
+            );
+            ALTER TABLE %%TABLE_NAME%% ADD COLUMN IF NOT EXISTS "user_id" VARCHAR(128) NULL;
+            CREATE INDEX IF NOT EXISTS %%INDEX_NAME%%_user_id_idx ON %%TABLE_NAME%% ("user_id");
+        "#
+                .replace("%%INDEX_NAME%%", index_name)
+                .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        // Tables created by older versions use a 32 bit expires column which can not go past 2038.
+        let schema_name = table_name
+            .split_once('.')
+            .map(|(schema_name, _)| schema_name.to_lowercase());
+        let data_type = client
+            .query_opt(
+                r#"
+            SELECT data_type::TEXT FROM information_schema.columns
+            WHERE table_schema = COALESCE($1, current_schema()) AND table_name = $2 AND column_name = 'expires'
+        "#,
+                &[&schema_name, &index_name.to_lowercase()],
+            )
+            .await?
+            .map(|row| row.try_get::<_, String>(0))
+            .transpose()?;
+
+        if data_type.as_deref() == Some("integer") {
+            client
+                .batch_execute(
+                    &r#"ALTER TABLE %%TABLE_NAME%% ALTER COLUMN "expires" TYPE BIGINT"#
+                        .replace("%%TABLE_NAME%%", table_name),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"DELETE FROM %%TABLE_NAME%% WHERE expires < $1::BIGINT"#
+                    .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        Ok(client
+            .execute(&statement, &[&Utc::now().timestamp()])
+            .await?)
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"SELECT COUNT(*) FROM %%TABLE_NAME%% WHERE expires IS NULL OR expires > $1::BIGINT"#
+                    .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        let count: i64 = client
+            .query_one(&statement, &[&Utc::now().timestamp()])
+            .await?
+            .try_get(0)?;
+        Ok(count)
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"
+        INSERT INTO %%TABLE_NAME%%
+            (id, session, expires) SELECT $1::VARCHAR, $2::TEXT, $3::BIGINT
+        ON CONFLICT(id) DO UPDATE SET
+            expires = EXCLUDED.expires,
+            session = EXCLUDED.session
+    "#
+                .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        client
+            .execute(&statement, &[&id, &session, &expires])
+            .await?;
+        Ok(())
+    }
+
+    async fn store_with_user(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        user_id: Option<&str>,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"
+        INSERT INTO %%TABLE_NAME%%
+            (id, session, expires, user_id) SELECT $1::VARCHAR, $2::TEXT, $3::BIGINT, $4::VARCHAR
+        ON CONFLICT(id) DO UPDATE SET
+            expires = EXCLUDED.expires,
+            session = EXCLUDED.session,
+            user_id = EXCLUDED.user_id
+    "#
+                .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        client
+            .execute(&statement, &[&id, &session, &expires, &user_id])
+            .await?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"
+            SELECT session FROM %%TABLE_NAME%%
+            WHERE id = $1 AND (expires IS NULL OR expires > $2::BIGINT)
+        "#
+                .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        let result = client
+            .query_opt(&statement, &[&id, &Utc::now().timestamp()])
+            .await?;
+
+        Ok(result.map(|row| row.try_get(0)).transpose()?)
+    }
+
+    async fn load_by_user_id(
+        &self,
+        user_id: &str,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"
+            SELECT session FROM %%TABLE_NAME%%
+            WHERE user_id = $1 AND (expires IS NULL OR expires > $2::BIGINT)
+            ORDER BY id
+            LIMIT $3::BIGINT OFFSET $4::BIGINT
+        "#
+                .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        let rows = client
+            .query(
+                &statement,
+                &[&user_id, &Utc::now().timestamp(), &limit, &offset],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.try_get(0))
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn load_ids(
+        &self,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"
+            SELECT id FROM %%TABLE_NAME%%
+            WHERE expires IS NULL OR expires > $1::BIGINT
+            ORDER BY expires, id
+            LIMIT $2::BIGINT OFFSET $3::BIGINT
+        "#
+                .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        let rows = client
+            .query(&statement, &[&Utc::now().timestamp(), &limit, &offset])
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.try_get(0))
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"DELETE FROM %%TABLE_NAME%% WHERE id = $1"#
+                    .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        client.execute(&statement, &[&id]).await?;
+        Ok(())
+    }
+
+    async fn delete_by_user_id(
+        &self,
+        user_id: &str,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let client = self.pool.get().await?;
+        let statement = client
+            .prepare_cached(
+                &r#"DELETE FROM %%TABLE_NAME%% WHERE user_id = $1 RETURNING id"#
+                    .replace("%%TABLE_NAME%%", table_name),
+            )
+            .await?;
+
+        let rows = client.query(&statement, &[&user_id]).await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.try_get(0))
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        let client = self.pool.get().await?;
+
+        client
+            .batch_execute(&r#"TRUNCATE %%TABLE_NAME%%"#.replace("%%TABLE_NAME%%", table_name))
+            .await?;
+        Ok(())
+    }
+}
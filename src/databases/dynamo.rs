@@ -0,0 +1,203 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use aws_sdk_dynamodb::{
+    model::{AttributeValue, Select, TimeToLiveSpecification, TimeToLiveStatus},
+    Client,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+
+pub type AxumDynamoSession = AxumSession<AxumDynamoPool>;
+pub type AxumDynamoSessionStore = AxumSessionStore<AxumDynamoPool>;
+
+/// DynamoDB's Pool type for AxumDatabasePool
+///
+/// The table named after the configured table name must already be provisioned
+/// with a String partition key named `id`. Items are stored as `{ id, data, expires }`
+/// and initiate registers `expires` as the table's TTL attribute.
+///
+/// DynamoDB can take up to 48 hours to remove expired items so load
+/// treats any item whose `expires` has passed as missing.
+#[derive(Debug, Clone)]
+pub struct AxumDynamoPool {
+    client: Client,
+}
+
+impl From<Client> for AxumDynamoPool {
+    fn from(client: Client) -> Self {
+        AxumDynamoPool { client }
+    }
+}
+
+impl AxumDynamoPool {
+    /// Scans the whole table returning only the ids of each item.
+    async fn ids(&self, table_name: &str) -> Result<Vec<String>, SessionError> {
+        let mut ids = Vec::new();
+        let mut start_key: Option<HashMap<String, AttributeValue>> = None;
+
+        loop {
+            let output = self
+                .client
+                .scan()
+                .table_name(table_name)
+                .projection_expression("id")
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| SessionError::GenericSelectError(e.to_string()))?;
+
+            ids.extend(
+                output
+                    .items()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|item| item.get("id")?.as_s().ok().cloned()),
+            );
+
+            start_key = output.last_evaluated_key().cloned();
+
+            if start_key.is_none() {
+                return Ok(ids);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumDynamoPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        let ttl = self
+            .client
+            .describe_time_to_live()
+            .table_name(table_name)
+            .send()
+            .await
+            .map_err(|e| SessionError::GenericCreateError(e.to_string()))?;
+
+        let enabled = matches!(
+            ttl.time_to_live_description()
+                .and_then(|desc| desc.time_to_live_status()),
+            Some(TimeToLiveStatus::Enabled | TimeToLiveStatus::Enabling)
+        );
+
+        if !enabled {
+            self.client
+                .update_time_to_live()
+                .table_name(table_name)
+                .time_to_live_specification(
+                    TimeToLiveSpecification::builder()
+                        .attribute_name("expires")
+                        .enabled(true)
+                        .build(),
+                )
+                .send()
+                .await
+                .map_err(|e| SessionError::GenericCreateError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, _table_name: &str) -> Result<u64, SessionError> {
+        // DynamoDB does this for us using the TTL attribute.
+        Ok(0)
+    }
+
+    /// This uses a Scan of the whole table which consumes read capacity
+    /// for every item. Avoid calling this often on large tables.
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let mut count = 0i64;
+        let mut start_key: Option<HashMap<String, AttributeValue>> = None;
+        // The TTL attribute can take a while to remove items so expired ones are filtered out.
+        let now = Utc::now().timestamp().to_string();
+
+        loop {
+            let output = self
+                .client
+                .scan()
+                .table_name(table_name)
+                .select(Select::Count)
+                .filter_expression("#expires > :now")
+                .expression_attribute_names("#expires", "expires")
+                .expression_attribute_values(":now", AttributeValue::N(now.clone()))
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| SessionError::GenericSelectError(e.to_string()))?;
+
+            count += output.count() as i64;
+            start_key = output.last_evaluated_key().cloned();
+
+            if start_key.is_none() {
+                return Ok(count);
+            }
+        }
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        self.client
+            .put_item()
+            .table_name(table_name)
+            .item("id", AttributeValue::S(id.to_string()))
+            .item("data", AttributeValue::S(session.to_string()))
+            .item("expires", AttributeValue::N(expires.to_string()))
+            .send()
+            .await
+            .map_err(|e| SessionError::GenericInsertError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let output = self
+            .client
+            .get_item()
+            .table_name(table_name)
+            .key("id", AttributeValue::S(id.to_string()))
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|e| SessionError::GenericSelectError(e.to_string()))?;
+
+        let item = match output.item() {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+
+        let expires = item
+            .get("expires")
+            .and_then(|expires| expires.as_n().ok())
+            .and_then(|expires| expires.parse::<i64>().ok())
+            .unwrap_or(0);
+
+        if expires <= Utc::now().timestamp() {
+            return Ok(None);
+        }
+
+        Ok(item.get("data").and_then(|data| data.as_s().ok()).cloned())
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        self.client
+            .delete_item()
+            .table_name(table_name)
+            .key("id", AttributeValue::S(id.to_string()))
+            .send()
+            .await
+            .map_err(|e| SessionError::GenericDeleteError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        for id in self.ids(table_name).await? {
+            self.delete_one_by_id(&id, table_name).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+
+pub type AxumFileSession = AxumSession<AxumFilePool>;
+pub type AxumFileSessionStore = AxumSessionStore<AxumFilePool>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileSession {
+    session: String,
+    expires: i64,
+}
+
+type FileTables = HashMap<String, HashMap<String, FileSession>>;
+
+/// File's Pool type for AxumDatabasePool
+///
+/// Keeps every session within a single JSON file so sessions survive restarts
+/// without any database. Meant for development and examples, not production.
+/// The whole file is rewritten on each change by writing a temporary file then renaming it.
+#[derive(Debug, Clone)]
+pub struct AxumFilePool {
+    path: PathBuf,
+    tables: Arc<Mutex<FileTables>>,
+}
+
+impl AxumFilePool {
+    /// Loads the session file at the given path, creating an empty store if it does not exist.
+    ///
+    /// Sessions that already expired are dropped while loading.
+    ///
+    /// # Errors
+    /// - ['SessionError::IO'] is returned if the file exists but could not be read.
+    /// - ['SessionError::SerdeJson'] is returned if the file could not be parsed.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// use axum_database_sessions::AxumFilePool;
+    ///
+    /// let pool = AxumFilePool::new("./sessions.json").unwrap();
+    /// ```
+    ///
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, SessionError> {
+        let path = path.as_ref().to_path_buf();
+        let mut tables: FileTables = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let now = Utc::now().timestamp();
+
+        for table in tables.values_mut() {
+            table.retain(|_, session| session.expires > now);
+        }
+
+        Ok(AxumFilePool {
+            path,
+            tables: Arc::new(Mutex::new(tables)),
+        })
+    }
+
+    /// Writes the tables to a temporary file and renames it over the real file
+    /// so a crash mid write never leaves a half written file behind.
+    async fn save(&self, tables: &FileTables) -> Result<(), SessionError> {
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+
+        tokio::fs::write(&temp_path, serde_json::to_vec(tables)?).await?;
+        tokio::fs::rename(&temp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumFilePool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        let mut tables = self.tables.lock().await;
+        tables.entry(table_name.to_string()).or_default();
+        self.save(&tables).await
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        let mut tables = self.tables.lock().await;
+        let now = Utc::now().timestamp();
+        let mut removed = 0;
+
+        if let Some(table) = tables.get_mut(table_name) {
+            let before = table.len();
+            table.retain(|_, session| session.expires > now);
+            removed = (before - table.len()) as u64;
+        }
+
+        self.save(&tables).await?;
+        Ok(removed)
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let tables = self.tables.lock().await;
+        let now = Utc::now().timestamp();
+
+        Ok(tables.get(table_name).map_or(0, |table| {
+            table.values().filter(|session| session.expires > now).count() as i64
+        }))
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let mut tables = self.tables.lock().await;
+
+        tables.entry(table_name.to_string()).or_default().insert(
+            id.to_string(),
+            FileSession {
+                session: session.to_string(),
+                expires,
+            },
+        );
+
+        self.save(&tables).await
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let tables = self.tables.lock().await;
+        let now = Utc::now().timestamp();
+
+        Ok(tables
+            .get(table_name)
+            .and_then(|table| table.get(id))
+            .filter(|session| session.expires > now)
+            .map(|session| session.session.clone()))
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        let mut tables = self.tables.lock().await;
+
+        if let Some(table) = tables.get_mut(table_name) {
+            table.remove(id);
+        }
+
+        self.save(&tables).await
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        let mut tables = self.tables.lock().await;
+
+        if let Some(table) = tables.get_mut(table_name) {
+            table.clear();
+        }
+
+        self.save(&tables).await
+    }
+}
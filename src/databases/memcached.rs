@@ -0,0 +1,122 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use memcache::Client;
+use std::fmt;
+
+pub type AxumMemcachedSession = AxumSession<AxumMemcachedPool>;
+pub type AxumMemcachedSessionStore = AxumSessionStore<AxumMemcachedPool>;
+
+/// Memcached's Pool type for AxumDatabasePool
+///
+/// Sessions are stored as `table_name:session_id` keys using the session's expiry as the TTL.
+/// Memcached may evict sessions under memory pressure, in which case a new session is created.
+/// Memcached can not list its keys so count and delete_all return
+/// ['SessionError::GenericNotSupportedError'].
+#[derive(Clone)]
+pub struct AxumMemcachedPool {
+    client: Client,
+}
+
+impl fmt::Debug for AxumMemcachedPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AxumMemcachedPool").finish()
+    }
+}
+
+impl From<Client> for AxumMemcachedPool {
+    fn from(client: Client) -> Self {
+        AxumMemcachedPool { client }
+    }
+}
+
+impl AxumMemcachedPool {
+    /// Connects to one or more memcached servers.
+    ///
+    /// Keys are spread across the servers using the Client's hash_function.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// use axum_database_sessions::AxumMemcachedPool;
+    ///
+    /// let pool = AxumMemcachedPool::connect(vec![
+    ///     "memcache://10.0.0.1:11211",
+    ///     "memcache://10.0.0.2:11211",
+    /// ]).unwrap();
+    /// ```
+    ///
+    pub fn connect(addresses: Vec<&str>) -> Result<Self, SessionError> {
+        Ok(Client::connect(addresses)?.into())
+    }
+
+    /// The memcache client is blocking so it is run on tokio's blocking threads.
+    async fn blocking<R, F>(&self, func: F) -> Result<R, SessionError>
+    where
+        R: Send + 'static,
+        F: FnOnce(Client) -> Result<R, memcache::MemcacheError> + Send + 'static,
+    {
+        let client = self.client.clone();
+
+        tokio::task::spawn_blocking(move || func(client))
+            .await
+            .map_err(|e| SessionError::GenericSelectError(e.to_string()))?
+            .map_err(SessionError::from)
+    }
+}
+
+fn memcached_key(id: &str, table_name: &str) -> String {
+    format!("{}:{}", table_name, id)
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumMemcachedPool {
+    async fn initiate(&self, _table_name: &str) -> Result<(), SessionError> {
+        // Memcached does not actually use Tables so there is no way we can make one.
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, _table_name: &str) -> Result<u64, SessionError> {
+        // Memcached does this for us using the TTL.
+        Ok(0)
+    }
+
+    async fn count(&self, _table_name: &str) -> Result<i64, SessionError> {
+        Err(SessionError::GenericNotSupportedError(
+            "Memcached does not support counting sessions".to_string(),
+        ))
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let key = memcached_key(id, table_name);
+        let session = session.to_string();
+
+        // Memcached treats expirations over 30 days as a unix timestamp so we can pass expires directly.
+        self.blocking(move |client| client.set(&key, session.as_str(), expires as u32))
+            .await
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let key = memcached_key(id, table_name);
+
+        self.blocking(move |client| client.get::<String>(&key))
+            .await
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        let key = memcached_key(id, table_name);
+
+        self.blocking(move |client| client.delete(&key)).await?;
+        Ok(())
+    }
+
+    async fn delete_all(&self, _table_name: &str) -> Result<(), SessionError> {
+        Err(SessionError::GenericNotSupportedError(
+            "Memcached does not support deleting all sessions".to_string(),
+        ))
+    }
+}
@@ -8,18 +8,74 @@ mod postgres;
 #[cfg(any(feature = "postgres-rustls", feature = "postgres-native"))]
 pub use postgres::*;
 
+#[cfg(feature = "postgres-deadpool")]
+mod deadpool_postgres;
+#[cfg(feature = "postgres-deadpool")]
+pub use self::deadpool_postgres::*;
+
 #[cfg(any(feature = "sqlite-rustls", feature = "sqlite-native"))]
 mod sqlite;
 #[cfg(any(feature = "sqlite-rustls", feature = "sqlite-native"))]
 pub use sqlite::*;
 
-#[cfg(feature = "redis-db")]
-mod redis_pool;
-#[cfg(feature = "redis-db")]
-pub use redis_pool::*;
+#[cfg(any(feature = "mssql-rustls", feature = "mssql-native"))]
+mod mssql;
+#[cfg(any(feature = "mssql-rustls", feature = "mssql-native"))]
+pub use mssql::*;
+
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "redis")]
+pub use self::redis::*;
+
+#[cfg(feature = "redis-clusterdb")]
+mod redis_cluster_pool;
+#[cfg(feature = "redis-clusterdb")]
+pub use redis_cluster_pool::*;
+
+#[cfg(feature = "mongo")]
+mod mongo;
+#[cfg(feature = "mongo")]
+pub use mongo::*;
+
+#[cfg(feature = "scylla-db")]
+mod scylla;
+#[cfg(feature = "scylla-db")]
+pub use self::scylla::*;
+
+#[cfg(feature = "dynamo")]
+mod dynamo;
+#[cfg(feature = "dynamo")]
+pub use dynamo::*;
+
+#[cfg(feature = "surreal")]
+mod surreal;
+#[cfg(feature = "surreal")]
+pub use surreal::*;
+
+#[cfg(feature = "sled-db")]
+mod sled;
+#[cfg(feature = "sled-db")]
+pub use self::sled::*;
+
+#[cfg(feature = "memcached")]
+mod memcached;
+#[cfg(feature = "memcached")]
+pub use memcached::*;
+
+#[cfg(feature = "file-store")]
+mod file;
+#[cfg(feature = "file-store")]
+pub use file::*;
+
+#[cfg(feature = "sea-orm-db")]
+mod sea_orm;
+#[cfg(feature = "sea-orm-db")]
+pub use self::sea_orm::*;
 
 mod database;
 mod null;
 
-pub use database::AxumDatabasePool;
+pub use database::{AxumDatabasePool, SessionRecord};
+pub(crate) use database::backend_name;
 pub use null::*;
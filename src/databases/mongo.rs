@@ -0,0 +1,121 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::{
+    bson::{doc, DateTime, Document},
+    options::{IndexOptions, ReplaceOptions},
+    Collection, Database, IndexModel,
+};
+use std::time::Duration;
+
+pub type AxumMongoSession = AxumSession<AxumMongoPool>;
+pub type AxumMongoSessionStore = AxumSessionStore<AxumMongoPool>;
+
+/// MongoDB's Pool type for AxumDatabasePool
+///
+/// Sessions are stored as documents `{ _id, data, expires }` within a collection
+/// named after the configured table name. Expiry is handled by a TTL index on expires.
+#[derive(Debug, Clone)]
+pub struct AxumMongoPool {
+    database: Database,
+}
+
+impl From<Database> for AxumMongoPool {
+    fn from(database: Database) -> Self {
+        AxumMongoPool { database }
+    }
+}
+
+impl AxumMongoPool {
+    fn collection(&self, table_name: &str) -> Collection<Document> {
+        self.database.collection(table_name)
+    }
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumMongoPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        // Mongo creates the collection on first insert, so we only need the TTL index.
+        let index = IndexModel::builder()
+            .keys(doc! { "expires": 1 })
+            .options(
+                IndexOptions::builder()
+                    .expire_after(Duration::from_secs(0))
+                    .build(),
+            )
+            .build();
+
+        self.collection(table_name)
+            .create_index(index, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, _table_name: &str) -> Result<u64, SessionError> {
+        // Mongo does this for us using the TTL index created in initiate.
+        Ok(0)
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let count = self
+            .collection(table_name)
+            .count_documents(
+                doc! { "expires": { "$gt": DateTime::from_millis(Utc::now().timestamp_millis()) } },
+                None,
+            )
+            .await?;
+
+        Ok(count as i64)
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        self.collection(table_name)
+            .replace_one(
+                doc! { "_id": id },
+                doc! {
+                    "_id": id,
+                    "data": session,
+                    "expires": DateTime::from_millis(expires * 1000),
+                },
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        // The TTL monitor only runs once a minute so we still filter out expired documents.
+        let result = self
+            .collection(table_name)
+            .find_one(
+                doc! {
+                    "_id": id,
+                    "expires": { "$gt": DateTime::from_millis(Utc::now().timestamp_millis()) },
+                },
+                None,
+            )
+            .await?;
+
+        Ok(result.and_then(|document| document.get_str("data").ok().map(str::to_string)))
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        self.collection(table_name)
+            .delete_one(doc! { "_id": id }, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        self.collection(table_name)
+            .delete_many(doc! {}, None)
+            .await?;
+        Ok(())
+    }
+}
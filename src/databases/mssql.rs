@@ -0,0 +1,278 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{pool::Pool, Mssql, MssqlPool};
+
+pub type AxumMssqlSession = AxumSession<AxumMssqlPool>;
+pub type AxumMssqlSessionStore = AxumSessionStore<AxumMssqlPool>;
+
+/// Mssql's Pool type for AxumDatabasePool
+#[derive(Debug, Clone)]
+pub struct AxumMssqlPool {
+    pool: Pool<Mssql>,
+}
+
+impl From<Pool<Mssql>> for AxumMssqlPool {
+    fn from(conn: MssqlPool) -> Self {
+        AxumMssqlPool { pool: conn }
+    }
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumMssqlPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        sqlx::query(
+            &r#"
+            IF OBJECT_ID('%%TABLE_NAME%%', 'U') IS NULL
+            CREATE TABLE %%TABLE_NAME%% (
+                id NVARCHAR(128) NOT NULL PRIMARY KEY,
+                expires BIGINT NULL,
+                session NVARCHAR(MAX) NOT NULL,
+                user_id NVARCHAR(128) NULL
+            )
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Tables created by older versions are missing the user_id column.
+        sqlx::query(
+            &r#"
+            IF COL_LENGTH('%%TABLE_NAME%%', 'user_id') IS NULL
+            ALTER TABLE %%TABLE_NAME%% ADD user_id NVARCHAR(128) NULL
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Index names can not include the schema so only the table's own name is used.
+        let index_name = table_name.rsplit('.').next().unwrap_or(table_name);
+
+        sqlx::query(
+            &r#"
+            IF NOT EXISTS (
+                SELECT 1 FROM sys.indexes
+                WHERE name = '%%INDEX_NAME%%_user_id_idx' AND object_id = OBJECT_ID('%%TABLE_NAME%%')
+            )
+            CREATE INDEX %%INDEX_NAME%%_user_id_idx ON %%TABLE_NAME%% (user_id)
+        "#
+            .replace("%%INDEX_NAME%%", index_name)
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        let result = sqlx::query(
+            &r#"DELETE FROM %%TABLE_NAME%% WHERE expires < @p1"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_expired_ids(&self, table_name: &str) -> Result<Vec<String>, SessionError> {
+        let ids: Vec<(String,)> = sqlx::query_as(
+            &r#"DELETE FROM %%TABLE_NAME%% OUTPUT DELETED.id WHERE expires < @p1"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn ping(&self, _table_name: &str) -> Result<(), SessionError> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let (count,) = sqlx::query_as(
+            &r#"SELECT COUNT_BIG(*) FROM %%TABLE_NAME%% WHERE expires IS NULL OR expires > @p1"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await?;
+
+        return Ok(count);
+    }
+
+    async fn count_expired(&self, table_name: &str) -> Result<i64, SessionError> {
+        let (count,) = sqlx::query_as(
+            &r#"SELECT COUNT(*) FROM %%TABLE_NAME%% WHERE expires < @p1"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        sqlx::query(
+            &r#"
+        MERGE INTO %%TABLE_NAME%% WITH (HOLDLOCK) AS target
+        USING (SELECT @p1 AS id, @p2 AS session, @p3 AS expires) AS source
+        ON target.id = source.id
+        WHEN MATCHED THEN UPDATE SET
+            target.expires = source.expires,
+            target.session = source.session
+        WHEN NOT MATCHED THEN
+            INSERT (id, session, expires) VALUES (source.id, source.session, source.expires);
+    "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(id)
+        .bind(session)
+        .bind(expires)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn store_with_user(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        user_id: Option<&str>,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        sqlx::query(
+            &r#"
+        MERGE INTO %%TABLE_NAME%% WITH (HOLDLOCK) AS target
+        USING (SELECT @p1 AS id, @p2 AS session, @p3 AS expires, @p4 AS user_id) AS source
+        ON target.id = source.id
+        WHEN MATCHED THEN UPDATE SET
+            target.expires = source.expires,
+            target.session = source.session,
+            target.user_id = source.user_id
+        WHEN NOT MATCHED THEN
+            INSERT (id, session, expires, user_id)
+            VALUES (source.id, source.session, source.expires, source.user_id);
+    "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(id)
+        .bind(session)
+        .bind(expires)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let result: Option<(String,)> = sqlx::query_as(
+            &r#"
+            SELECT session FROM %%TABLE_NAME%%
+            WHERE id = @p1 AND (expires IS NULL OR expires > @p2)
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(id)
+        .bind(Utc::now().timestamp())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.map(|(session,)| session))
+    }
+
+    async fn load_by_user_id(
+        &self,
+        user_id: &str,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let result: Vec<(String,)> = sqlx::query_as(
+            &r#"
+            SELECT session FROM %%TABLE_NAME%%
+            WHERE user_id = @p1 AND (expires IS NULL OR expires > @p2)
+            ORDER BY id
+            OFFSET @p3 ROWS FETCH NEXT @p4 ROWS ONLY
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(user_id)
+        .bind(Utc::now().timestamp())
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result.into_iter().map(|(session,)| session).collect())
+    }
+
+    async fn load_ids(
+        &self,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let result: Vec<(String,)> = sqlx::query_as(
+            &r#"
+            SELECT id FROM %%TABLE_NAME%%
+            WHERE expires IS NULL OR expires > @p1
+            ORDER BY expires, id
+            OFFSET @p2 ROWS FETCH NEXT @p3 ROWS ONLY
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        sqlx::query(
+            &r#"DELETE FROM %%TABLE_NAME%% WHERE id = @p1"#.replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_by_user_id(
+        &self,
+        user_id: &str,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let ids: Vec<(String,)> = sqlx::query_as(
+            &r#"DELETE FROM %%TABLE_NAME%% OUTPUT DELETED.id WHERE user_id = @p1"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        sqlx::query(&r#"TRUNCATE TABLE %%TABLE_NAME%%"#.replace("%%TABLE_NAME%%", table_name))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
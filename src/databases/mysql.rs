@@ -1,7 +1,10 @@
-use crate::{AxumSession, AxumSessionStore, AxumDatabasePool, SessionError};
+use crate::{
+    databases::database::missing_columns, AxumDatabasePool, AxumSession, AxumSessionStore,
+    SessionError, SessionRecord,
+};
 use async_trait::async_trait;
 use chrono::Utc;
-use sqlx::{pool::Pool, MySql, MySqlPool};
+use sqlx::{pool::Pool, MySql, MySqlPool, QueryBuilder};
 
 pub type AxumMySqlSession = AxumSession<AxumMySqlPool>;
 pub type AxumMySqlSessionStore = AxumSessionStore<AxumMySqlPool>;
@@ -18,46 +21,167 @@ impl From<Pool<MySql>> for AxumMySqlPool {
     }
 }
 
+const CREATE_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS %%TABLE_NAME%% (
+    `id` VARCHAR(128) NOT NULL PRIMARY KEY,
+    `expires` BIGINT NULL,
+    `session` TEXT NOT NULL,
+    `user_id` VARCHAR(128) NULL,
+    INDEX `user_id_idx` (`user_id`)
+)"#;
+
 #[async_trait]
 impl AxumDatabasePool for AxumMySqlPool {
     async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
-        sqlx::query(
-            &r#"
-            CREATE TABLE IF NOT EXISTS %%TABLE_NAME%% (
-                "id" VARCHAR(128) NOT NULL PRIMARY KEY,
-                "expires" INTEGER NULL,
-                "session" TEXT NOT NULL
+        sqlx::query(&CREATE_TABLE.replace("%%TABLE_NAME%%", table_name))
+            .execute(&self.pool)
+            .await?;
+
+        // Tables created by older versions are missing the user_id column.
+        let (schema_name, name) = match table_name.split_once('.') {
+            Some((schema_name, name)) => (Some(schema_name), name),
+            None => (None, table_name),
+        };
+
+        let (count,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM information_schema.COLUMNS
+            WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? AND COLUMN_NAME = 'user_id'
+        "#,
+        )
+        .bind(schema_name)
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if count == 0 {
+            sqlx::query(
+                &r#"
+                ALTER TABLE %%TABLE_NAME%%
+                    ADD COLUMN `user_id` VARCHAR(128) NULL,
+                    ADD INDEX `user_id_idx` (`user_id`)
+            "#
+                .replace("%%TABLE_NAME%%", table_name),
             )
-        "#
-            .replace("%%TABLE_NAME%%", table_name),
+            .execute(&self.pool)
+            .await?;
+        }
+
+        // Tables created by older versions use a 32 bit expires column which can not go past 2038.
+        let data_type: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT CAST(DATA_TYPE AS CHAR) FROM information_schema.COLUMNS
+            WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ? AND COLUMN_NAME = 'expires'
+        "#,
         )
-        .execute(&self.pool)
+        .bind(schema_name)
+        .bind(name)
+        .fetch_optional(&self.pool)
         .await?;
 
+        if data_type.is_some_and(|(data_type,)| data_type.eq_ignore_ascii_case("int")) {
+            sqlx::query(
+                &r#"ALTER TABLE %%TABLE_NAME%% MODIFY `expires` BIGINT NULL"#
+                    .replace("%%TABLE_NAME%%", table_name),
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+
         Ok(())
     }
 
-    async fn delete_by_expiry(&self, table_name: &str) -> Result<(), SessionError> {
-        sqlx::query(
-            &r#"DELETE FROM %%TABLE_NAME%% WHERE expires < $1"#
+    async fn check(&self, table_name: &str) -> Result<Vec<String>, SessionError> {
+        let (schema_name, name) = match table_name.split_once('.') {
+            Some((schema_name, name)) => (Some(schema_name), name),
+            None => (None, table_name),
+        };
+
+        let columns: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT COLUMN_NAME FROM information_schema.COLUMNS
+            WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?
+        "#,
+        )
+        .bind(schema_name)
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(missing_columns(
+            table_name,
+            columns.into_iter().map(|(column,)| column),
+        ))
+    }
+
+    fn migration_sql(table_name: &str) -> Option<String> {
+        Some(format!(
+            "{};\n",
+            CREATE_TABLE.replace("%%TABLE_NAME%%", table_name)
+        ))
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        let result = sqlx::query(
+            &r#"DELETE FROM %%TABLE_NAME%% WHERE expires < ?"#
                 .replace("%%TABLE_NAME%%", table_name),
         )
         .bind(Utc::now().timestamp())
         .execute(&self.pool)
         .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_expired_ids(&self, table_name: &str) -> Result<Vec<String>, SessionError> {
+        // MySQL has no DELETE ... RETURNING so the ids are locked and selected first.
+        let now = Utc::now().timestamp();
+        let mut tx = self.pool.begin().await?;
+        let ids: Vec<(String,)> = sqlx::query_as(
+            &r#"SELECT id FROM %%TABLE_NAME%% WHERE expires < ? FOR UPDATE"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(now)
+        .fetch_all(&mut tx)
+        .await?;
+        sqlx::query(
+            &r#"DELETE FROM %%TABLE_NAME%% WHERE expires < ?"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(now)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn ping(&self, _table_name: &str) -> Result<(), SessionError> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
         Ok(())
     }
 
     async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
         let (count,) = sqlx::query_as(
-            &r#"SELECT COUNT(*) FROM %%TABLE_NAME%%"#.replace("%%TABLE_NAME%%", table_name),
+            &r#"SELECT COUNT(*) FROM %%TABLE_NAME%% WHERE expires IS NULL OR expires > ?"#
+                .replace("%%TABLE_NAME%%", table_name),
         )
+        .bind(Utc::now().timestamp())
         .fetch_one(&self.pool)
         .await?;
 
         return Ok(count);
     }
 
+    async fn count_expired(&self, table_name: &str) -> Result<i64, SessionError> {
+        let (count,) = sqlx::query_as(
+            &r#"SELECT COUNT(*) FROM %%TABLE_NAME%% WHERE expires < ?"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
     async fn store(
         &self,
         id: &str,
@@ -68,30 +192,89 @@ impl AxumDatabasePool for AxumMySqlPool {
         sqlx::query(
             &r#"
         INSERT INTO %%TABLE_NAME%%
-            (id, session, expires) SELECT $1, $2, $3
-        ON CONFLICT(id) DO UPDATE SET
-            expires = EXCLUDED.expires,
-            session = EXCLUDED.session
+            (id, session, expires) VALUES (?, ?, ?)
+        ON DUPLICATE KEY UPDATE
+            expires = VALUES(expires),
+            session = VALUES(session)
+    "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(id)
+        .bind(session)
+        .bind(expires)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn store_with_user(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        user_id: Option<&str>,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        sqlx::query(
+            &r#"
+        INSERT INTO %%TABLE_NAME%%
+            (id, session, expires, user_id) VALUES (?, ?, ?, ?)
+        ON DUPLICATE KEY UPDATE
+            expires = VALUES(expires),
+            session = VALUES(session),
+            user_id = VALUES(user_id)
     "#
             .replace("%%TABLE_NAME%%", table_name),
         )
-        .bind(&id)
-        .bind(&session)
-        .bind(&expires)
+        .bind(id)
+        .bind(session)
+        .bind(expires)
+        .bind(user_id)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    async fn store_batch(
+        &self,
+        sessions: &[SessionRecord],
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        // Keeps each statement well under the bind parameter limit.
+        for chunk in sessions.chunks(1000) {
+            let mut query = QueryBuilder::<MySql>::new(
+                "INSERT INTO %%TABLE_NAME%% (id, session, expires, user_id) "
+                    .replace("%%TABLE_NAME%%", table_name),
+            );
+
+            query.push_values(chunk, |mut row, record| {
+                row.push_bind(&record.id)
+                    .push_bind(&record.session)
+                    .push_bind(record.expires)
+                    .push_bind(&record.user_id);
+            });
+
+            query.push(
+                r#" ON DUPLICATE KEY UPDATE
+                expires = VALUES(expires),
+                session = VALUES(session),
+                user_id = VALUES(user_id)"#,
+            );
+            query.build().execute(&self.pool).await?;
+        }
+
+        Ok(())
+    }
+
     async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
         let result: Option<(String,)> = sqlx::query_as(
             &r#"
             SELECT session FROM %%TABLE_NAME%%
-            WHERE id = $1 AND (expires IS NULL OR expires > $2)
+            WHERE id = ? AND (expires IS NULL OR expires > ?)
         "#
             .replace("%%TABLE_NAME%%", table_name),
         )
-        .bind(&id)
+        .bind(id)
         .bind(Utc::now().timestamp())
         .fetch_optional(&self.pool)
         .await?;
@@ -99,20 +282,91 @@ impl AxumDatabasePool for AxumMySqlPool {
         Ok(result.map(|(session,)| session))
     }
 
-    async fn delete_one_by_id(
+    async fn load_by_user_id(
         &self,
-        id: &str,
+        user_id: &str,
+        offset: i64,
+        limit: i64,
         table_name: &str,
-    ) -> Result<(), SessionError> {
+    ) -> Result<Vec<String>, SessionError> {
+        let result: Vec<(String,)> = sqlx::query_as(
+            &r#"
+            SELECT session FROM %%TABLE_NAME%%
+            WHERE user_id = ? AND (expires IS NULL OR expires > ?)
+            ORDER BY id
+            LIMIT ? OFFSET ?
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(user_id)
+        .bind(Utc::now().timestamp())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result.into_iter().map(|(session,)| session).collect())
+    }
+
+    async fn load_ids(
+        &self,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let result: Vec<(String,)> = sqlx::query_as(
+            &r#"
+            SELECT id FROM %%TABLE_NAME%%
+            WHERE expires IS NULL OR expires > ?
+            ORDER BY expires, id
+            LIMIT ? OFFSET ?
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
         sqlx::query(
-            &r#"DELETE FROM %%TABLE_NAME%% WHERE id = $1"#.replace("%%TABLE_NAME%%", table_name),
+            &r#"DELETE FROM %%TABLE_NAME%% WHERE id = ?"#.replace("%%TABLE_NAME%%", table_name),
         )
-        .bind(&id)
+        .bind(id)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    async fn delete_by_user_id(
+        &self,
+        user_id: &str,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        // MySQL has no DELETE ... RETURNING so the ids are locked and selected first.
+        let mut tx = self.pool.begin().await?;
+        let ids: Vec<(String,)> = sqlx::query_as(
+            &r#"SELECT id FROM %%TABLE_NAME%% WHERE user_id = ? FOR UPDATE"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(user_id)
+        .fetch_all(&mut tx)
+        .await?;
+        sqlx::query(
+            &r#"DELETE FROM %%TABLE_NAME%% WHERE user_id = ?"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(user_id)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+
     async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
         sqlx::query(&r#"TRUNCATE %%TABLE_NAME%%"#.replace("%%TABLE_NAME%%", table_name))
             .execute(&self.pool)
@@ -6,6 +6,7 @@ pub type AxumNullSessionStore = AxumSessionStore<AxumNullPool>;
 
 /// Null Pool type for AxumDatabasePool.
 /// Use this when you do not want to load any database.
+#[derive(Debug, Clone)]
 pub struct AxumNullPool;
 
 #[async_trait]
@@ -14,8 +15,8 @@ impl AxumDatabasePool for AxumNullPool {
         Ok(())
     }
 
-    async fn delete_by_expiry(&self, _table_name: &str) -> Result<(), SessionError> {
-        Ok(())
+    async fn delete_by_expiry(&self, _table_name: &str) -> Result<u64, SessionError> {
+        Ok(0)
     }
 
     async fn count(&self, _table_name: &str) -> Result<i64, SessionError> {
@@ -40,6 +41,33 @@ impl AxumDatabasePool for AxumNullPool {
         Ok(())
     }
 
+    async fn load_by_user_id(
+        &self,
+        _user_id: &str,
+        _offset: i64,
+        _limit: i64,
+        _table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        Ok(Vec::new())
+    }
+
+    async fn load_ids(
+        &self,
+        _offset: i64,
+        _limit: i64,
+        _table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        Ok(Vec::new())
+    }
+
+    async fn delete_by_user_id(
+        &self,
+        _user_id: &str,
+        _table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        Ok(Vec::new())
+    }
+
     async fn delete_all(&self, _table_name: &str) -> Result<(), SessionError> {
         Ok(())
     }
@@ -1,7 +1,10 @@
-use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use crate::{
+    databases::database::missing_columns, AxumDatabasePool, AxumSession, AxumSessionStore,
+    SessionError, SessionRecord,
+};
 use async_trait::async_trait;
 use chrono::Utc;
-use sqlx::{pool::Pool, PgPool, Postgres};
+use sqlx::{pool::Pool, PgPool, Postgres, QueryBuilder};
 
 pub type AxumPgSession = AxumSession<AxumPgPool>;
 pub type AxumPgSessionStore = AxumSessionStore<AxumPgPool>;
@@ -18,46 +21,165 @@ impl From<Pool<Postgres>> for AxumPgPool {
     }
 }
 
+const CREATE_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS %%TABLE_NAME%% (
+    "id" VARCHAR(128) NOT NULL PRIMARY KEY,
+    "expires" BIGINT NULL,
+    "session" TEXT NOT NULL,
+    "user_id" VARCHAR(128) NULL
+)"#;
+
+/// Index names can not include the schema so only the table's own name is used.
+fn create_index(table_name: &str) -> String {
+    let index_name = table_name.rsplit('.').next().unwrap_or(table_name);
+
+    r#"CREATE INDEX IF NOT EXISTS %%INDEX_NAME%%_user_id_idx ON %%TABLE_NAME%% ("user_id")"#
+        .replace("%%INDEX_NAME%%", index_name)
+        .replace("%%TABLE_NAME%%", table_name)
+}
+
 #[async_trait]
 impl AxumDatabasePool for AxumPgPool {
     async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        if let Some((schema_name, _)) = table_name.split_once('.') {
+            sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", schema_name))
+                .execute(&self.pool)
+                .await?;
+        }
+
+        sqlx::query(&CREATE_TABLE.replace("%%TABLE_NAME%%", table_name))
+            .execute(&self.pool)
+            .await?;
+
+        // Tables created by older versions are missing the user_id column.
         sqlx::query(
-            &r#"
-            CREATE TABLE IF NOT EXISTS %%TABLE_NAME%% (
-                "id" VARCHAR(128) NOT NULL PRIMARY KEY,
-                "expires" INTEGER NULL,
-                "session" TEXT NOT NULL
-            )
-        "#
-            .replace("%%TABLE_NAME%%", table_name),
+            &r#"ALTER TABLE %%TABLE_NAME%% ADD COLUMN IF NOT EXISTS "user_id" VARCHAR(128) NULL"#
+                .replace("%%TABLE_NAME%%", table_name),
         )
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(&create_index(table_name))
+            .execute(&self.pool)
+            .await?;
+
+        // Tables created by older versions use a 32 bit expires column which can not go past 2038.
+        let (schema_name, name) = match table_name.split_once('.') {
+            Some((schema_name, name)) => (Some(schema_name.to_lowercase()), name),
+            None => (None, table_name),
+        };
+
+        let data_type: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT data_type::TEXT FROM information_schema.columns
+            WHERE table_schema = COALESCE($1, current_schema()) AND table_name = $2 AND column_name = 'expires'
+        "#,
+        )
+        .bind(schema_name)
+        .bind(name.to_lowercase())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if data_type.is_some_and(|(data_type,)| data_type == "integer") {
+            sqlx::query(
+                &r#"ALTER TABLE %%TABLE_NAME%% ALTER COLUMN "expires" TYPE BIGINT"#
+                    .replace("%%TABLE_NAME%%", table_name),
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+
         Ok(())
     }
 
-    async fn delete_by_expiry(&self, table_name: &str) -> Result<(), SessionError> {
-        sqlx::query(
+    async fn check(&self, table_name: &str) -> Result<Vec<String>, SessionError> {
+        let (schema_name, name) = match table_name.split_once('.') {
+            Some((schema_name, name)) => (Some(schema_name.to_lowercase()), name),
+            None => (None, table_name),
+        };
+
+        // Unquoted names are stored in lower case by Postgres.
+        let columns: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT column_name::TEXT FROM information_schema.columns
+            WHERE table_schema = COALESCE($1, current_schema()) AND table_name = $2
+        "#,
+        )
+        .bind(schema_name)
+        .bind(name.to_lowercase())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(missing_columns(
+            table_name,
+            columns.into_iter().map(|(column,)| column),
+        ))
+    }
+
+    fn migration_sql(table_name: &str) -> Option<String> {
+        let schema = match table_name.split_once('.') {
+            Some((schema_name, _)) => format!("CREATE SCHEMA IF NOT EXISTS {};\n", schema_name),
+            None => String::new(),
+        };
+
+        Some(format!(
+            "{}{};\n{};\n",
+            schema,
+            CREATE_TABLE.replace("%%TABLE_NAME%%", table_name),
+            create_index(table_name)
+        ))
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        let result = sqlx::query(
             &r#"DELETE FROM %%TABLE_NAME%% WHERE expires < $1"#
                 .replace("%%TABLE_NAME%%", table_name),
         )
         .bind(Utc::now().timestamp())
         .execute(&self.pool)
         .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_expired_ids(&self, table_name: &str) -> Result<Vec<String>, SessionError> {
+        let ids: Vec<(String,)> = sqlx::query_as(
+            &r#"DELETE FROM %%TABLE_NAME%% WHERE expires < $1 RETURNING id"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn ping(&self, _table_name: &str) -> Result<(), SessionError> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
         Ok(())
     }
 
     async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
         let (count,) = sqlx::query_as(
-            &r#"SELECT COUNT(*) FROM %%TABLE_NAME%%"#.replace("%%TABLE_NAME%%", table_name),
+            &r#"SELECT COUNT(*) FROM %%TABLE_NAME%% WHERE expires IS NULL OR expires > $1"#
+                .replace("%%TABLE_NAME%%", table_name),
         )
+        .bind(Utc::now().timestamp())
         .fetch_one(&self.pool)
         .await?;
 
         return Ok(count);
     }
 
+    async fn count_expired(&self, table_name: &str) -> Result<i64, SessionError> {
+        let (count,) = sqlx::query_as(
+            &r#"SELECT COUNT(*) FROM %%TABLE_NAME%% WHERE expires < $1"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
     async fn store(
         &self,
         id: &str,
@@ -75,14 +197,73 @@ impl AxumDatabasePool for AxumPgPool {
     "#
             .replace("%%TABLE_NAME%%", table_name),
         )
-        .bind(&id)
-        .bind(&session)
-        .bind(&expires)
+        .bind(id)
+        .bind(session)
+        .bind(expires)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn store_with_user(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        user_id: Option<&str>,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        sqlx::query(
+            &r#"
+        INSERT INTO %%TABLE_NAME%%
+            (id, session, expires, user_id) SELECT $1, $2, $3, $4
+        ON CONFLICT(id) DO UPDATE SET
+            expires = EXCLUDED.expires,
+            session = EXCLUDED.session,
+            user_id = EXCLUDED.user_id
+    "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(id)
+        .bind(session)
+        .bind(expires)
+        .bind(user_id)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    async fn store_batch(
+        &self,
+        sessions: &[SessionRecord],
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        // Keeps each statement well under the bind parameter limit.
+        for chunk in sessions.chunks(1000) {
+            let mut query = QueryBuilder::<Postgres>::new(
+                "INSERT INTO %%TABLE_NAME%% (id, session, expires, user_id) "
+                    .replace("%%TABLE_NAME%%", table_name),
+            );
+
+            query.push_values(chunk, |mut row, record| {
+                row.push_bind(&record.id)
+                    .push_bind(&record.session)
+                    .push_bind(record.expires)
+                    .push_bind(&record.user_id);
+            });
+
+            query.push(
+                r#" ON CONFLICT(id) DO UPDATE SET
+                expires = EXCLUDED.expires,
+                session = EXCLUDED.session,
+                user_id = EXCLUDED.user_id"#,
+            );
+            query.build().execute(&self.pool).await?;
+        }
+
+        Ok(())
+    }
+
     async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
         let result: Option<(String,)> = sqlx::query_as(
             &r#"
@@ -91,7 +272,7 @@ impl AxumDatabasePool for AxumPgPool {
         "#
             .replace("%%TABLE_NAME%%", table_name),
         )
-        .bind(&id)
+        .bind(id)
         .bind(Utc::now().timestamp())
         .fetch_optional(&self.pool)
         .await?;
@@ -99,16 +280,81 @@ impl AxumDatabasePool for AxumPgPool {
         Ok(result.map(|(session,)| session))
     }
 
+    async fn load_by_user_id(
+        &self,
+        user_id: &str,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let result: Vec<(String,)> = sqlx::query_as(
+            &r#"
+            SELECT session FROM %%TABLE_NAME%%
+            WHERE user_id = $1 AND (expires IS NULL OR expires > $2)
+            ORDER BY id
+            LIMIT $3 OFFSET $4
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(user_id)
+        .bind(Utc::now().timestamp())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result.into_iter().map(|(session,)| session).collect())
+    }
+
+    async fn load_ids(
+        &self,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let result: Vec<(String,)> = sqlx::query_as(
+            &r#"
+            SELECT id FROM %%TABLE_NAME%%
+            WHERE expires IS NULL OR expires > $1
+            ORDER BY expires, id
+            LIMIT $2 OFFSET $3
+        "#
+            .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(Utc::now().timestamp())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result.into_iter().map(|(id,)| id).collect())
+    }
+
     async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
         sqlx::query(
             &r#"DELETE FROM %%TABLE_NAME%% WHERE id = $1"#.replace("%%TABLE_NAME%%", table_name),
         )
-        .bind(&id)
+        .bind(id)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    async fn delete_by_user_id(
+        &self,
+        user_id: &str,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let ids: Vec<(String,)> = sqlx::query_as(
+            &r#"DELETE FROM %%TABLE_NAME%% WHERE user_id = $1 RETURNING id"#
+                .replace("%%TABLE_NAME%%", table_name),
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+
     async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
         sqlx::query(&r#"TRUNCATE %%TABLE_NAME%%"#.replace("%%TABLE_NAME%%", table_name))
             .execute(&self.pool)
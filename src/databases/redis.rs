@@ -0,0 +1,100 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use redis::{AsyncCommands, Client};
+
+pub type AxumRedisSession = AxumSession<AxumRedisPool>;
+pub type AxumRedisSessionStore = AxumSessionStore<AxumRedisPool>;
+
+/// Redis's Pool type for AxumDatabasePool
+///
+/// Sessions are stored as `table_name:session_id` keys so the configured table name
+/// acts as a key prefix. This allows several Session stores to share one Redis database.
+#[derive(Debug, Clone)]
+pub struct AxumRedisPool {
+    client: Client,
+}
+
+impl From<Client> for AxumRedisPool {
+    fn from(client: Client) -> Self {
+        AxumRedisPool { client }
+    }
+}
+
+impl AxumRedisPool {
+    /// Gathers all the keys that belong to the table_name prefix using SCAN.
+    async fn keys(&self, table_name: &str) -> Result<Vec<String>, SessionError> {
+        let mut con = self.client.get_async_connection().await?;
+        let mut iter: redis::AsyncIter<String> =
+            con.scan_match(format!("{}:*", table_name)).await?;
+        let mut keys = Vec::new();
+
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+
+        Ok(keys)
+    }
+}
+
+fn redis_key(id: &str, table_name: &str) -> String {
+    format!("{}:{}", table_name, id)
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumRedisPool {
+    async fn initiate(&self, _table_name: &str) -> Result<(), SessionError> {
+        // Redis does not actually use Tables so there is no way we can make one.
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, _table_name: &str) -> Result<u64, SessionError> {
+        // Redis does this for use using the Expiry Options.
+        Ok(0)
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        Ok(self.keys(table_name).await?.len() as i64)
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let id = redis_key(id, table_name);
+        let mut con = self.client.get_async_connection().await?;
+        redis::pipe()
+            .set(&id, session)
+            .ignore()
+            .expire_at(&id, expires as usize)
+            .ignore()
+            .query_async::<_, ()>(&mut con)
+            .await?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let mut con = self.client.get_async_connection().await?;
+        let result: Option<String> = con.get(redis_key(id, table_name)).await?;
+        Ok(result)
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        let mut con = self.client.get_async_connection().await?;
+        con.del::<_, ()>(redis_key(id, table_name)).await?;
+        Ok(())
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        let keys = self.keys(table_name).await?;
+
+        if !keys.is_empty() {
+            let mut con = self.client.get_async_connection().await?;
+            con.del::<_, ()>(keys).await?;
+        }
+
+        Ok(())
+    }
+}
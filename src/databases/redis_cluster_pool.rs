@@ -0,0 +1,116 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use redis::{cluster::ClusterClient, AsyncCommands};
+use std::fmt;
+
+pub type AxumRedisClusterSession = AxumSession<AxumRedisClusterPool>;
+pub type AxumRedisClusterSessionStore = AxumSessionStore<AxumRedisClusterPool>;
+
+/// Redis Cluster's Pool type for AxumDatabasePool
+///
+/// Keys are hash tagged as `{table_name}:session_id` so every session of a table lives in
+/// the same slot. Alongside the sessions a sorted set `{table_name}:index` scored by expiry
+/// is kept so count, cleanup and delete_all work without scanning every node.
+/// Use `rediss://` node urls along with the redis-tls feature for TLS.
+#[derive(Clone)]
+pub struct AxumRedisClusterPool {
+    client: ClusterClient,
+}
+
+impl fmt::Debug for AxumRedisClusterPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AxumRedisClusterPool").finish()
+    }
+}
+
+impl From<ClusterClient> for AxumRedisClusterPool {
+    fn from(client: ClusterClient) -> Self {
+        AxumRedisClusterPool { client }
+    }
+}
+
+fn redis_key(id: &str, table_name: &str) -> String {
+    format!("{{{}}}:{}", table_name, id)
+}
+
+fn index_key(table_name: &str) -> String {
+    format!("{{{}}}:index", table_name)
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumRedisClusterPool {
+    async fn initiate(&self, _table_name: &str) -> Result<(), SessionError> {
+        // Redis does not actually use Tables so there is no way we can make one.
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        // Redis expires the sessions themselves, we only need to clean up the index.
+        let mut con = self.client.get_async_connection().await?;
+        let removed: u64 = con
+            .zrembyscore(index_key(table_name), "-inf", Utc::now().timestamp())
+            .await?;
+        Ok(removed)
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let mut con = self.client.get_async_connection().await?;
+        let count: i64 = con
+            .zcount(index_key(table_name), Utc::now().timestamp(), "+inf")
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let key = redis_key(id, table_name);
+        let mut con = self.client.get_async_connection().await?;
+        redis::pipe()
+            .atomic()
+            .set(&key, session)
+            .ignore()
+            .expire_at(&key, expires as usize)
+            .ignore()
+            .zadd(index_key(table_name), id, expires)
+            .ignore()
+            .query_async::<_, ()>(&mut con)
+            .await?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let mut con = self.client.get_async_connection().await?;
+        let result: Option<String> = con.get(redis_key(id, table_name)).await?;
+        Ok(result)
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        let mut con = self.client.get_async_connection().await?;
+        redis::pipe()
+            .atomic()
+            .del(redis_key(id, table_name))
+            .ignore()
+            .zrem(index_key(table_name), id)
+            .ignore()
+            .query_async::<_, ()>(&mut con)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        let mut con = self.client.get_async_connection().await?;
+        let ids: Vec<String> = con.zrange(index_key(table_name), 0, -1).await?;
+        let mut keys: Vec<String> = ids.iter().map(|id| redis_key(id, table_name)).collect();
+
+        keys.push(index_key(table_name));
+        con.del::<_, ()>(keys).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use scylla::{CachingSession, Session};
+use std::sync::Arc;
+
+pub type AxumScyllaSession = AxumSession<AxumScyllaPool>;
+pub type AxumScyllaSessionStore = AxumSessionStore<AxumScyllaPool>;
+
+/// Scylla's Pool type for AxumDatabasePool. Also works with Cassandra.
+///
+/// Statements are prepared once and reused through a CachingSession.
+/// Expiry is handled natively using `USING TTL` so delete_by_expiry does nothing.
+/// Use AxumSessionConfig::with_schema_name to set the keyspace.
+#[derive(Debug, Clone)]
+pub struct AxumScyllaPool {
+    session: Arc<CachingSession>,
+}
+
+impl From<CachingSession> for AxumScyllaPool {
+    fn from(session: CachingSession) -> Self {
+        AxumScyllaPool {
+            session: Arc::new(session),
+        }
+    }
+}
+
+impl From<Session> for AxumScyllaPool {
+    fn from(session: Session) -> Self {
+        // Only a handful of statements are used so we only need to cache a few.
+        CachingSession::from(session, 16).into()
+    }
+}
+
+fn select_error(err: impl std::fmt::Display) -> SessionError {
+    SessionError::GenericSelectError(err.to_string())
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumScyllaPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        self.session
+            .session
+            .query(
+                r#"
+                CREATE TABLE IF NOT EXISTS %%TABLE_NAME%% (
+                    id TEXT PRIMARY KEY,
+                    expires BIGINT,
+                    session TEXT
+                )
+            "#
+                .replace("%%TABLE_NAME%%", table_name),
+                &[],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, _table_name: &str) -> Result<u64, SessionError> {
+        // Scylla does this for us using the TTL set on insert.
+        Ok(0)
+    }
+
+    /// Counting requires a full table scan in Scylla and Cassandra so this is expensive
+    /// on large tables and should not be called often.
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let (count,) = self
+            .session
+            .execute(
+                r#"SELECT COUNT(*) FROM %%TABLE_NAME%%"#.replace("%%TABLE_NAME%%", table_name),
+                &[],
+            )
+            .await?
+            .single_row_typed::<(i64,)>()
+            .map_err(select_error)?;
+
+        Ok(count)
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let ttl = std::cmp::max(expires - Utc::now().timestamp(), 1) as i32;
+
+        self.session
+            .execute(
+                r#"INSERT INTO %%TABLE_NAME%% (id, session, expires) VALUES (?, ?, ?) USING TTL ?"#
+                    .replace("%%TABLE_NAME%%", table_name),
+                (id, session, expires, ttl),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let result = self
+            .session
+            .execute(
+                r#"SELECT session FROM %%TABLE_NAME%% WHERE id = ?"#
+                    .replace("%%TABLE_NAME%%", table_name),
+                (id,),
+            )
+            .await?
+            .maybe_first_row_typed::<(String,)>()
+            .map_err(select_error)?;
+
+        Ok(result.map(|(session,)| session))
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        self.session
+            .execute(
+                r#"DELETE FROM %%TABLE_NAME%% WHERE id = ?"#.replace("%%TABLE_NAME%%", table_name),
+                (id,),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        self.session
+            .session
+            .query(
+                r#"TRUNCATE %%TABLE_NAME%%"#.replace("%%TABLE_NAME%%", table_name),
+                &[],
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,439 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement, TransactionTrait, Value,
+};
+
+pub type AxumSeaOrmSession = AxumSession<AxumSeaOrmPool>;
+pub type AxumSeaOrmSessionStore = AxumSessionStore<AxumSeaOrmPool>;
+
+/// SeaORM's Pool type for AxumDatabasePool
+///
+/// Works with Postgres, MySQL and SQLite by detecting the backend of the DatabaseConnection.
+/// Enable the sea-orm sqlx backend and runtime features you need within your own Cargo.toml.
+/// The table layout is the same as the sqlx backends so data can be shared between them.
+#[derive(Debug, Clone)]
+pub struct AxumSeaOrmPool {
+    connection: DatabaseConnection,
+}
+
+impl From<DatabaseConnection> for AxumSeaOrmPool {
+    fn from(connection: DatabaseConnection) -> Self {
+        AxumSeaOrmPool { connection }
+    }
+}
+
+impl AxumSeaOrmPool {
+    /// Builds a statement for the connection's backend.
+    ///
+    /// Queries are written using `$1` style placeholders which are swapped to `?` for MySQL.
+    fn statement(
+        &self,
+        query: &str,
+        table_name: &str,
+        values: impl IntoIterator<Item = Value>,
+    ) -> Statement {
+        let backend = self.connection.get_database_backend();
+        let mut query = query.replace("%%TABLE_NAME%%", table_name);
+
+        if backend == DatabaseBackend::MySql {
+            for index in (1..=4).rev() {
+                query = query.replace(&format!("${}", index), "?");
+            }
+        }
+
+        Statement::from_sql_and_values(backend, &query, values)
+    }
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumSeaOrmPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        let backend = self.connection.get_database_backend();
+        let query = match backend {
+            DatabaseBackend::MySql => {
+                r#"
+                CREATE TABLE IF NOT EXISTS %%TABLE_NAME%% (
+                    `id` VARCHAR(128) NOT NULL PRIMARY KEY,
+                    `expires` BIGINT NULL,
+                    `session` TEXT NOT NULL,
+                    `user_id` VARCHAR(128) NULL,
+                    INDEX `user_id_idx` (`user_id`)
+                )
+            "#
+            }
+            _ => {
+                r#"
+                CREATE TABLE IF NOT EXISTS %%TABLE_NAME%% (
+                    "id" VARCHAR(128) NOT NULL PRIMARY KEY,
+                    "expires" BIGINT NULL,
+                    "session" TEXT NOT NULL,
+                    "user_id" VARCHAR(128) NULL
+                )
+            "#
+            }
+        };
+
+        self.connection
+            .execute(self.statement(query, table_name, []))
+            .await?;
+
+        // Tables created by older versions are missing the user_id column.
+        let (schema_name, name) = match table_name.split_once('.') {
+            Some((schema_name, name)) => (Some(schema_name), name),
+            None => (None, table_name),
+        };
+
+        let found = match backend {
+            DatabaseBackend::MySql => self.connection.query_one(self.statement(
+                r#"
+                SELECT COUNT(*) AS count FROM information_schema.COLUMNS
+                WHERE TABLE_SCHEMA = COALESCE($1, DATABASE()) AND TABLE_NAME = $2 AND COLUMN_NAME = 'user_id'
+            "#,
+                table_name,
+                [schema_name.map(str::to_owned).into(), name.into()],
+            )),
+            DatabaseBackend::Postgres => self.connection.query_one(self.statement(
+                r#"
+                SELECT COUNT(*) AS count FROM information_schema.columns
+                WHERE table_schema = COALESCE($1, current_schema()) AND table_name = $2 AND column_name = 'user_id'
+            "#,
+                table_name,
+                [
+                    schema_name.map(|schema_name| schema_name.to_lowercase()).into(),
+                    name.to_lowercase().into(),
+                ],
+            )),
+            DatabaseBackend::Sqlite => self.connection.query_one(self.statement(
+                r#"SELECT COUNT(*) AS count FROM pragma_table_info($1) WHERE name = 'user_id'"#,
+                table_name,
+                [table_name.into()],
+            )),
+        }
+        .await?;
+
+        let found: i64 = match found {
+            Some(row) => row.try_get("", "count")?,
+            None => 0,
+        };
+
+        let queries: &[&str] = match (backend, found) {
+            (DatabaseBackend::MySql, 0) => &[r#"
+                ALTER TABLE %%TABLE_NAME%%
+                    ADD COLUMN `user_id` VARCHAR(128) NULL,
+                    ADD INDEX `user_id_idx` (`user_id`)
+            "#],
+            (DatabaseBackend::MySql, _) => &[],
+            (_, 0) => &[
+                r#"ALTER TABLE %%TABLE_NAME%% ADD COLUMN "user_id" VARCHAR(128) NULL"#,
+                r#"CREATE INDEX IF NOT EXISTS %%INDEX_NAME%%_user_id_idx ON %%TABLE_NAME%% ("user_id")"#,
+            ],
+            _ => &[
+                r#"CREATE INDEX IF NOT EXISTS %%INDEX_NAME%%_user_id_idx ON %%TABLE_NAME%% ("user_id")"#,
+            ],
+        };
+
+        // Index names can not include the schema so only the table's own name is used.
+        for query in queries {
+            self.connection
+                .execute(self.statement(&query.replace("%%INDEX_NAME%%", name), table_name, []))
+                .await?;
+        }
+
+        // Tables created by older versions use a 32 bit expires column which can not go past 2038.
+        // SQLite's INTEGER already holds 64 bits.
+        let query = match backend {
+            DatabaseBackend::MySql => Some((
+                r#"
+                SELECT CAST(DATA_TYPE AS CHAR) AS data_type FROM information_schema.COLUMNS
+                WHERE TABLE_SCHEMA = COALESCE($1, DATABASE()) AND TABLE_NAME = $2 AND COLUMN_NAME = 'expires'
+            "#,
+                "int",
+                r#"ALTER TABLE %%TABLE_NAME%% MODIFY `expires` BIGINT NULL"#,
+                [schema_name.map(str::to_owned).into(), name.into()],
+            )),
+            DatabaseBackend::Postgres => Some((
+                r#"
+                SELECT data_type::TEXT AS data_type FROM information_schema.columns
+                WHERE table_schema = COALESCE($1, current_schema()) AND table_name = $2 AND column_name = 'expires'
+            "#,
+                "integer",
+                r#"ALTER TABLE %%TABLE_NAME%% ALTER COLUMN "expires" TYPE BIGINT"#,
+                [
+                    schema_name
+                        .map(|schema_name| schema_name.to_lowercase())
+                        .into(),
+                    name.to_lowercase().into(),
+                ],
+            )),
+            DatabaseBackend::Sqlite => None,
+        };
+
+        if let Some((query, old_type, alter, values)) = query {
+            let data_type: Option<String> = match self
+                .connection
+                .query_one(self.statement(query, table_name, values))
+                .await?
+            {
+                Some(row) => Some(row.try_get("", "data_type")?),
+                None => None,
+            };
+
+            if data_type.is_some_and(|data_type| data_type.eq_ignore_ascii_case(old_type)) {
+                self.connection
+                    .execute(self.statement(alter, table_name, []))
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        let result = self
+            .connection
+            .execute(self.statement(
+                r#"DELETE FROM %%TABLE_NAME%% WHERE expires < $1"#,
+                table_name,
+                [Utc::now().timestamp().into()],
+            ))
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let result = self
+            .connection
+            .query_one(self.statement(
+                r#"SELECT COUNT(*) AS count FROM %%TABLE_NAME%% WHERE expires IS NULL OR expires > $1"#,
+                table_name,
+                [Utc::now().timestamp().into()],
+            ))
+            .await?;
+
+        Ok(match result {
+            Some(row) => row.try_get("", "count")?,
+            None => 0,
+        })
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let query = match self.connection.get_database_backend() {
+            DatabaseBackend::MySql => {
+                r#"
+                INSERT INTO %%TABLE_NAME%%
+                    (id, session, expires) VALUES ($1, $2, $3)
+                ON DUPLICATE KEY UPDATE
+                    expires = VALUES(expires),
+                    session = VALUES(session)
+            "#
+            }
+            _ => {
+                r#"
+                INSERT INTO %%TABLE_NAME%%
+                    (id, session, expires) VALUES ($1, $2, $3)
+                ON CONFLICT(id) DO UPDATE SET
+                    expires = EXCLUDED.expires,
+                    session = EXCLUDED.session
+            "#
+            }
+        };
+
+        self.connection
+            .execute(self.statement(
+                query,
+                table_name,
+                [id.into(), session.into(), expires.into()],
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn store_with_user(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        user_id: Option<&str>,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        let query = match self.connection.get_database_backend() {
+            DatabaseBackend::MySql => {
+                r#"
+                INSERT INTO %%TABLE_NAME%%
+                    (id, session, expires, user_id) VALUES ($1, $2, $3, $4)
+                ON DUPLICATE KEY UPDATE
+                    expires = VALUES(expires),
+                    session = VALUES(session),
+                    user_id = VALUES(user_id)
+            "#
+            }
+            _ => {
+                r#"
+                INSERT INTO %%TABLE_NAME%%
+                    (id, session, expires, user_id) VALUES ($1, $2, $3, $4)
+                ON CONFLICT(id) DO UPDATE SET
+                    expires = EXCLUDED.expires,
+                    session = EXCLUDED.session,
+                    user_id = EXCLUDED.user_id
+            "#
+            }
+        };
+
+        self.connection
+            .execute(self.statement(
+                query,
+                table_name,
+                [
+                    id.into(),
+                    session.into(),
+                    expires.into(),
+                    user_id.map(str::to_owned).into(),
+                ],
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let result = self
+            .connection
+            .query_one(self.statement(
+                r#"
+                SELECT session FROM %%TABLE_NAME%%
+                WHERE id = $1 AND (expires IS NULL OR expires > $2)
+            "#,
+                table_name,
+                [id.into(), Utc::now().timestamp().into()],
+            ))
+            .await?;
+
+        Ok(match result {
+            Some(row) => Some(row.try_get("", "session")?),
+            None => None,
+        })
+    }
+
+    async fn load_by_user_id(
+        &self,
+        user_id: &str,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let rows = self
+            .connection
+            .query_all(self.statement(
+                r#"
+                SELECT session FROM %%TABLE_NAME%%
+                WHERE user_id = $1 AND (expires IS NULL OR expires > $2)
+                ORDER BY id
+                LIMIT $3 OFFSET $4
+            "#,
+                table_name,
+                [
+                    user_id.into(),
+                    Utc::now().timestamp().into(),
+                    limit.into(),
+                    offset.into(),
+                ],
+            ))
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.try_get("", "session"))
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn load_ids(
+        &self,
+        offset: i64,
+        limit: i64,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let rows = self
+            .connection
+            .query_all(self.statement(
+                r#"
+                SELECT id FROM %%TABLE_NAME%%
+                WHERE expires IS NULL OR expires > $1
+                ORDER BY expires, id
+                LIMIT $2 OFFSET $3
+            "#,
+                table_name,
+                [Utc::now().timestamp().into(), limit.into(), offset.into()],
+            ))
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.try_get("", "id"))
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        self.connection
+            .execute(self.statement(
+                r#"DELETE FROM %%TABLE_NAME%% WHERE id = $1"#,
+                table_name,
+                [id.into()],
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_by_user_id(
+        &self,
+        user_id: &str,
+        table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let rows = if self.connection.get_database_backend() == DatabaseBackend::MySql {
+            // MySQL has no DELETE ... RETURNING so the ids are locked and selected first.
+            let tx = self.connection.begin().await?;
+            let rows = tx
+                .query_all(self.statement(
+                    r#"SELECT id FROM %%TABLE_NAME%% WHERE user_id = $1 FOR UPDATE"#,
+                    table_name,
+                    [user_id.into()],
+                ))
+                .await?;
+            tx.execute(self.statement(
+                r#"DELETE FROM %%TABLE_NAME%% WHERE user_id = $1"#,
+                table_name,
+                [user_id.into()],
+            ))
+            .await?;
+            tx.commit().await?;
+            rows
+        } else {
+            self.connection
+                .query_all(self.statement(
+                    r#"DELETE FROM %%TABLE_NAME%% WHERE user_id = $1 RETURNING id"#,
+                    table_name,
+                    [user_id.into()],
+                ))
+                .await?
+        };
+
+        Ok(rows
+            .iter()
+            .map(|row| row.try_get("", "id"))
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        // SQLite does not support TRUNCATE so DELETE is used for every backend.
+        self.connection
+            .execute(self.statement(r#"DELETE FROM %%TABLE_NAME%%"#, table_name, []))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use sled::{Db, IVec, Tree};
+use std::path::Path;
+
+pub type AxumSledSession = AxumSession<AxumSledPool>;
+pub type AxumSledSessionStore = AxumSessionStore<AxumSledPool>;
+
+/// Sled's Pool type for AxumDatabasePool
+///
+/// Stores sessions on disk within an embedded sled database so they survive restarts
+/// without running a database server. Each table name is its own sled Tree.
+/// Call flush() before shutting down to make sure all sessions are written to disk.
+#[derive(Debug, Clone)]
+pub struct AxumSledPool {
+    db: Db,
+}
+
+impl From<Db> for AxumSledPool {
+    fn from(db: Db) -> Self {
+        AxumSledPool { db }
+    }
+}
+
+impl AxumSledPool {
+    /// Opens or creates the sled database at the given path.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// use axum_database_sessions::AxumSledPool;
+    ///
+    /// let pool = AxumSledPool::open("./sessions").unwrap();
+    /// ```
+    ///
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SessionError> {
+        Ok(sled::open(path)?.into())
+    }
+
+    /// Flushes all dirty sessions to disk. Useful on graceful shutdown.
+    pub async fn flush(&self) -> Result<usize, SessionError> {
+        Ok(self.db.flush_async().await?)
+    }
+
+    fn tree(&self, table_name: &str) -> Result<Tree, SessionError> {
+        Ok(self.db.open_tree(table_name)?)
+    }
+}
+
+/// Values are stored as the big endian expires timestamp followed by the session.
+fn encode(session: &str, expires: i64) -> Vec<u8> {
+    let mut value = expires.to_be_bytes().to_vec();
+    value.extend_from_slice(session.as_bytes());
+    value
+}
+
+fn expires(value: &IVec) -> i64 {
+    value
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map_or(0, i64::from_be_bytes)
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumSledPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        self.tree(table_name)?;
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        let tree = self.tree(table_name)?;
+        let now = Utc::now().timestamp();
+        let mut removed = 0;
+
+        for item in tree.iter() {
+            let (id, value) = item?;
+
+            if expires(&value) < now && tree.remove(id)?.is_some() {
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let now = Utc::now().timestamp();
+        let mut count = 0;
+
+        for value in self.tree(table_name)?.iter().values() {
+            if expires(&value?) > now {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        self.tree(table_name)?
+            .insert(id, encode(session, expires))?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let value = match self.tree(table_name)?.get(id)? {
+            Some(value) if expires(&value) > Utc::now().timestamp() => value,
+            _ => return Ok(None),
+        };
+
+        Ok(Some(String::from_utf8_lossy(&value[8..]).into_owned()))
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        self.tree(table_name)?.remove(id)?;
+        Ok(())
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        self.tree(table_name)?.clear()?;
+        Ok(())
+    }
+}
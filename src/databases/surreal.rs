@@ -0,0 +1,112 @@
+use crate::{AxumDatabasePool, AxumSession, AxumSessionStore, SessionError};
+use async_trait::async_trait;
+use chrono::Utc;
+use surrealdb::{engine::any::Any, Surreal};
+
+pub type AxumSurrealSession = AxumSession<AxumSurrealPool>;
+pub type AxumSurrealSessionStore = AxumSessionStore<AxumSurrealPool>;
+
+/// SurrealDB's Pool type for AxumDatabasePool
+///
+/// Works with any engine the `Surreal<Any>` handle was connected with, embedded or remote.
+/// The namespace and database must already be selected using `use_ns` and `use_db`.
+/// Sessions are stored as records `table_name:id` holding `{ session, expires }`.
+#[derive(Debug, Clone)]
+pub struct AxumSurrealPool {
+    connection: Surreal<Any>,
+}
+
+impl From<Surreal<Any>> for AxumSurrealPool {
+    fn from(connection: Surreal<Any>) -> Self {
+        AxumSurrealPool { connection }
+    }
+}
+
+#[async_trait]
+impl AxumDatabasePool for AxumSurrealPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        self.connection
+            .query("DEFINE TABLE type::table($table) SCHEMALESS")
+            .bind(("table", table_name))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        let removed: Vec<serde_json::Value> = self
+            .connection
+            .query("DELETE type::table($table) WHERE expires < $expires RETURN BEFORE")
+            .bind(("table", table_name))
+            .bind(("expires", Utc::now().timestamp()))
+            .await?
+            .take(0)?;
+        Ok(removed.len() as u64)
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        let count: Option<i64> = self
+            .connection
+            .query("SELECT count() FROM type::table($table) WHERE expires = NONE OR expires > $expires GROUP ALL")
+            .bind(("table", table_name))
+            .bind(("expires", Utc::now().timestamp()))
+            .await?
+            .take((0, "count"))?;
+
+        Ok(count.unwrap_or(0))
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        // UPDATE on a record id creates the record if it does not exist yet.
+        self.connection
+            .query(
+                "UPDATE type::thing($table, $id) CONTENT { session: $session, expires: $expires }",
+            )
+            .bind(("table", table_name))
+            .bind(("id", id))
+            .bind(("session", session))
+            .bind(("expires", expires))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        let session: Option<String> = self
+            .connection
+            .query("SELECT session FROM type::thing($table, $id) WHERE expires = NONE OR expires > $expires")
+            .bind(("table", table_name))
+            .bind(("id", id))
+            .bind(("expires", Utc::now().timestamp()))
+            .await?
+            .take((0, "session"))?;
+
+        Ok(session)
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        self.connection
+            .query("DELETE type::thing($table, $id)")
+            .bind(("table", table_name))
+            .bind(("id", id))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        self.connection
+            .query("DELETE type::table($table)")
+            .bind(("table", table_name))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{AxumDatabasePool, AxumSession, SessionError};
+use std::{fmt::Debug, marker::PhantomData, ops::Deref};
+
+/// A Session used after the response was returned, like within a WebSocket or spawned task.
+///
+/// Made by AxumSession::detach() while handling the request. Until it is dropped the Session
+/// is kept within memory, so eviction, the memory lifespan and database only mode do not
+/// replace it, and its changes are written to the database once it is dropped. Use flush()
+/// to write them sooner so other requests and servers see them.
+///
+/// It derefs to the AxumSession so every method can be used. Lazy Sessions need to be set
+/// before the response is returned as the client otherwise never gets their cookie.
+///
+/// # Examples
+/// ```rust
+/// # #[tokio::main]
+/// # async fn main() {
+/// use axum::{body::Body, routing::get, Router};
+/// use axum_database_sessions::{AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+/// use http::{header::{COOKIE, SET_COOKIE}, Request};
+/// use tower_service::Service;
+///
+/// let config = AxumSessionConfig::default();
+/// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+///
+/// let mut app = Router::new()
+///     .route("/start", get(|session: AxumSession<AxumNullPool>| async move {
+///         let detached = session.detach();
+///
+///         tokio::spawn(async move {
+///             detached.set("job", "done").await;
+///             detached.flush().await.unwrap();
+///         });
+///     }))
+///     .route("/job", get(|session: AxumSession<AxumNullPool>| async move {
+///         session.get::<String>("job").await.unwrap_or_default()
+///     }))
+///     .layer(AxumSessionLayer::new(session_store));
+///
+/// let request = Request::builder().uri("/start").body(Body::empty()).unwrap();
+/// let response = app.call(request).await.unwrap();
+/// let cookies: Vec<_> = response.headers().get_all(SET_COOKIE).iter()
+///     .map(|value| value.to_str().unwrap().split(';').next().unwrap().to_owned())
+///     .collect();
+///
+/// tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+///
+/// let request = Request::builder()
+///     .uri("/job")
+///     .header(COOKIE, cookies.join("; "))
+///     .body(Body::empty())
+///     .unwrap();
+/// let mut body = app.call(request).await.unwrap().into_body();
+/// let job = http_body::Body::data(&mut body).await.unwrap().unwrap();
+///
+/// assert_eq!(&job[..], b"done");
+/// # }
+/// ```
+///
+pub struct DetachedSession<T, M = ()>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    session: AxumSession<T, M>,
+}
+
+impl<T, M> Clone for DetachedSession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.session.clone())
+    }
+}
+
+impl<T, M> Debug for DetachedSession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DetachedSession")
+            .field("session", &self.session)
+            .finish()
+    }
+}
+
+impl<T, M> Deref for DetachedSession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    type Target = AxumSession<T, M>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.session
+    }
+}
+
+impl<T, M> DetachedSession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    pub(crate) fn new(session: AxumSession<T, M>) -> Self {
+        // Counted like a request so the Session stays within memory.
+        if let Some(mut sess) = session.store.inner.get_mut(&session.id.inner()) {
+            sess.requests += 1;
+        }
+
+        Self { session }
+    }
+
+    /// Writes the Session's changes to the database if anything changed since the last write.
+    ///
+    /// This does nothing for stores without a database or Sessions that have not been accepted as storable.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// detached.set("last_seen", Utc::now()).await;
+    /// detached.flush().await?;
+    /// ```
+    ///
+    pub async fn flush(&self) -> Result<(), SessionError> {
+        flush(&self.session).await
+    }
+}
+
+impl<T, M> Drop for DetachedSession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn drop(&mut self) {
+        // The marker is dropped so the flush can be spawned without M being 'static.
+        let session: AxumSession<T> = AxumSession {
+            store: self.session.store.clone(),
+            id: self.session.id.clone(),
+            access: self.session.access.clone(),
+            client_ip: self.session.client_ip,
+            user_agent: self.session.user_agent.clone(),
+            marker: PhantomData,
+        };
+
+        // The flush is spawned since Drop can not wait on the database.
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(err) = flush(&session).await {
+                        tracing::error!(session = %session.short_id(), "Failed to store detached session: {}", err);
+                    }
+
+                    release(&session);
+                });
+            }
+            Err(_) => {
+                tracing::warn!(session = %session.short_id(), "Detached session dropped outside of a runtime, changes were not stored");
+                release(&session);
+            }
+        }
+    }
+}
+
+/// Writes the Session if anything changed since the last write.
+async fn flush<T, M>(session: &AxumSession<T, M>) -> Result<(), SessionError>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    let changed = session
+        .store
+        .inner
+        .get(&session.id.inner())
+        .is_some_and(|sess| sess.update);
+
+    if changed {
+        session.force_save().await
+    } else {
+        Ok(())
+    }
+}
+
+/// Stops counting the DetachedSession as using the Session.
+fn release<T, M>(session: &AxumSession<T, M>)
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    let id = session.id.inner();
+
+    if let Some(mut sess) = session.store.inner.get_mut(&id) {
+        sess.requests = sess.requests.saturating_sub(1);
+    }
+
+    // Database only Sessions were only kept within memory for the DetachedSession.
+    if session.store.config.database_only && session.store.is_persistent() {
+        session
+            .store
+            .inner
+            .remove_if(&id, |_, sess| sess.requests == 0);
+    }
+}
@@ -1,3 +1,5 @@
+use axum_core::response::{IntoResponse, Response};
+use http::StatusCode;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -10,15 +12,53 @@ pub enum SessionError {
         feature = "sqlite-rustls",
         feature = "sqlite-native",
         feature = "mysql-rustls",
-        feature = "mysql-native"
+        feature = "mysql-native",
+        feature = "mssql-rustls",
+        feature = "mssql-native"
     ))]
     #[error(transparent)]
     Sqlx(#[from] sqlx::Error),
     #[cfg(feature = "redis")]
     #[error(transparent)]
     Redis(#[from] redis::RedisError),
+    #[cfg(feature = "mongodb")]
+    #[error(transparent)]
+    Mongo(#[from] mongodb::error::Error),
+    #[cfg(feature = "scylla")]
+    #[error(transparent)]
+    Scylla(#[from] scylla::transport::errors::QueryError),
+    /// Boxed since surrealdb::Error would make every Result returning a SessionError large.
+    #[cfg(feature = "surrealdb")]
+    #[error(transparent)]
+    Surreal(#[from] Box<surrealdb::Error>),
+    #[cfg(feature = "sled")]
+    #[error(transparent)]
+    Sled(#[from] sled::Error),
+    #[cfg(feature = "memcache")]
+    #[error(transparent)]
+    Memcached(#[from] memcache::MemcacheError),
+    #[cfg(feature = "sea-orm")]
+    #[error(transparent)]
+    SeaOrm(#[from] sea_orm::DbErr),
+    #[cfg(feature = "deadpool-postgres")]
+    #[error(transparent)]
+    DeadpoolPostgres(#[from] deadpool_postgres::PoolError),
+    #[cfg(feature = "tokio-postgres")]
+    #[error(transparent)]
+    TokioPostgres(#[from] tokio_postgres::Error),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::error::Error),
+    #[cfg(feature = "bincode")]
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
+    #[cfg(feature = "rmp-serde")]
+    #[error(transparent)]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    #[cfg(feature = "rmp-serde")]
+    #[error(transparent)]
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
     #[error(transparent)]
     HTTP(#[from] http::Error),
     #[error("unknown Session store error")]
@@ -33,4 +73,75 @@ pub enum SessionError {
     GenericDeleteError(String),
     #[error("{0}")]
     GenericNotSupportedError(String),
+    #[error("Invalid table name {0}, only [A-Za-z0-9_] are allowed")]
+    InvalidTableName(String),
+    #[error(transparent)]
+    InvalidConfig(#[from] SessionConfigError),
+    #[error("Session was stored as {0} but the configured serializer is {1}")]
+    SerializerMismatch(String, String),
+    #[error("Session data is missing from the Session Store")]
+    MissingSession,
+    #[error("Session table is not ready, missing {}", .0.join(", "))]
+    MissingSchema(Vec<String>),
+    #[error("Invalid Session export {0}")]
+    InvalidExport(String),
+    #[error("User already has the most Sessions allowed, {0}")]
+    TooManySessions(usize),
+}
+
+#[cfg(feature = "surrealdb")]
+impl From<surrealdb::Error> for SessionError {
+    fn from(err: surrealdb::Error) -> Self {
+        SessionError::Surreal(Box::new(err))
+    }
+}
+
+/// Every problem found by AxumSessionConfig::validate().
+///
+/// Each problem is a readable message explaining what to change.
+#[derive(Error, Debug, Clone)]
+#[error("Invalid AxumSessionConfig: {}", .problems.join(", "))]
+pub struct SessionConfigError {
+    pub problems: Vec<String>,
+}
+
+/// Why a request was rejected by the Session extractors or layers.
+///
+/// The response keeps the rejection within its extensions so an AxumSessionLayer it passes
+/// through can replace it using AxumSessionConfig::with_rejection_handler.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum SessionRejection {
+    /// AxumSession was extracted without its AxumSessionLayer. Responds with 500 Internal Server Error.
+    /// Extract `Option<AxumSession>` to get None instead.
+    #[error("Can't extract AxumSession<{pool}, {marker}>. Is `AxumSessionLayer` enabled for it?")]
+    MissingLayer {
+        pool: &'static str,
+        marker: &'static str,
+    },
+    /// The client created too many new Sessions. Responds with 429 Too Many Requests.
+    #[error("Too many new sessions")]
+    RateLimited,
+    /// CsrfLayer found no valid CSRF token. Responds with 403 Forbidden.
+    #[error("Invalid CSRF token")]
+    InvalidCsrfToken,
+}
+
+impl SessionRejection {
+    /// Returns the status code of the default response.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            SessionRejection::MissingLayer { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            SessionRejection::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            SessionRejection::InvalidCsrfToken => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+impl IntoResponse for SessionRejection {
+    fn into_response(self) -> Response {
+        let mut response = (self.status(), self.to_string()).into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
 }
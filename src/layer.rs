@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, marker::PhantomData};
 
 use crate::{AxumDatabasePool, AxumSessionService, AxumSessionStore};
 use tower_layer::Layer;
@@ -7,20 +7,83 @@ use tower_layer::Layer;
 ///
 /// # Examples
 /// ```
-/// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
-/// use uuid::Uuid;
+/// use axum_database_sessions::{AxumNullPool, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
 ///
 /// let config = AxumSessionConfig::default();
-/// let session_store = AxumSessionStore::new(None, &config);
+/// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
 /// let layer = AxumSessionLayer::new(session_store);
 /// ```
 ///
-#[derive(Clone)]
-pub struct AxumSessionLayer<T>
+/// # tower-cookies
+/// With the `tower-cookies` feature and a `tower_cookies::CookieManagerLayer` added after this
+/// layer the Session's cookies are read from and written to its `Cookies` instead, using the
+/// config's Key for signed or private cookies. Without it the cookies are handled as usual.
+///
+/// ```rust
+/// # #[cfg(feature = "tower-cookies")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// use axum::{body::Body, routing::get, Router};
+/// use axum_database_sessions::{AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, Key};
+/// use http::{header::{COOKIE, SET_COOKIE}, Request};
+/// use tower_cookies::{Cookie, CookieManagerLayer, Cookies};
+/// use tower_service::Service;
+///
+/// let config = AxumSessionConfig::default().with_key(Key::generate());
+/// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+///
+/// let mut app = Router::new()
+///     .route("/", get(|session: AxumSession<AxumNullPool>, cookies: Cookies| async move {
+///         cookies.add(Cookie::new("theme", "dark"));
+///         let count = session.get::<usize>("count").await.unwrap_or(0) + 1;
+///         session.set("count", count).await;
+///         count.to_string()
+///     }))
+///     .layer(AxumSessionLayer::new(session_store))
+///     .layer(CookieManagerLayer::new());
+///
+/// let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+/// let response = app.call(request).await.unwrap();
+/// let cookies: Vec<_> = response.headers().get_all(SET_COOKIE).iter()
+///     .map(|value| value.to_str().unwrap().split(';').next().unwrap().to_owned())
+///     .collect();
+///
+/// assert_eq!(cookies.iter().filter(|cookie| cookie.starts_with("sqlx_session=")).count(), 1);
+/// assert!(cookies.iter().any(|cookie| cookie == "theme=dark"));
+///
+/// let request = Request::builder()
+///     .uri("/")
+///     .header(COOKIE, cookies.join("; "))
+///     .body(Body::empty())
+///     .unwrap();
+/// let response = app.call(request).await.unwrap();
+/// let mut body = response.into_body();
+/// let count = http_body::Body::data(&mut body).await.unwrap().unwrap();
+///
+/// assert_eq!(&count[..], b"2");
+/// # }
+/// # #[cfg(not(feature = "tower-cookies"))]
+/// # fn main() {}
+/// ```
+///
+pub struct AxumSessionLayer<T, M = ()>
 where
     T: AxumDatabasePool + Clone + fmt::Debug + std::marker::Sync + std::marker::Send + 'static,
 {
     session_store: AxumSessionStore<T>,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<T, M> Clone for AxumSessionLayer<T, M>
+where
+    T: AxumDatabasePool + Clone + fmt::Debug + std::marker::Sync + std::marker::Send + 'static,
+{
+    fn clone(&self) -> Self {
+        AxumSessionLayer {
+            session_store: self.session_store.clone(),
+            marker: PhantomData,
+        }
+    }
 }
 
 impl<T> AxumSessionLayer<T>
@@ -31,29 +94,60 @@ where
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
-    /// use uuid::Uuid;
+    /// use axum_database_sessions::{AxumNullPool, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, &config);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     /// let layer = AxumSessionLayer::new(session_store);
     /// ```
     ///
     pub fn new(session_store: AxumSessionStore<T>) -> Self {
-        AxumSessionLayer { session_store }
+        AxumSessionLayer {
+            session_store,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, M> AxumSessionLayer<T, M>
+where
+    T: AxumDatabasePool + Clone + fmt::Debug + std::marker::Sync + std::marker::Send + 'static,
+{
+    /// Constructs a AxumSessionLayer whose Session is extracted as `AxumSession<T, M>`.
+    ///
+    /// Use this to run several Session layers within the same Router. Each layer needs its own
+    /// marker type and should use its own cookie name and table name.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumNullPool, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+    ///
+    /// struct Admin;
+    ///
+    /// let config = AxumSessionConfig::default().with_cookie_name("admin_session");
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    /// let layer = AxumSessionLayer::<AxumNullPool, Admin>::named(session_store);
+    /// ```
+    ///
+    pub fn named(session_store: AxumSessionStore<T>) -> Self {
+        AxumSessionLayer {
+            session_store,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<S, T> Layer<S> for AxumSessionLayer<T>
+impl<S, T, M> Layer<S> for AxumSessionLayer<T, M>
 where
     T: AxumDatabasePool + Clone + fmt::Debug + std::marker::Sync + std::marker::Send + 'static,
 {
-    type Service = AxumSessionService<S, T>;
+    type Service = AxumSessionService<S, T, M>;
 
     fn layer(&self, inner: S) -> Self::Service {
         AxumSessionService {
             session_store: self.session_store.clone(),
             inner,
+            marker: PhantomData,
         }
     }
 }
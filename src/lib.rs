@@ -2,24 +2,56 @@
 #![allow(dead_code)]
 
 mod config;
+#[cfg(feature = "csrf")]
+mod csrf;
 pub mod databases;
+mod detached_session;
 mod errors;
 mod layer;
+mod read_only_session;
+mod serializer;
 mod service;
 mod session;
+mod session_access;
+mod session_cleanup;
 mod session_data;
+mod session_events;
 mod session_id;
+mod session_metrics;
+mod session_namespace;
 mod session_store;
 mod session_timers;
+mod session_value;
+mod session_write_behind;
+mod typed_data;
 
-pub use config::{AxumSessionConfig, AxumSessionMode, Key, SameSite};
+pub use config::{
+    AxumSessionConfig, AxumSessionMode, CookieSecurity, ExpiryPolicy, IpBinding, Key, Migrations,
+    OverflowPolicy, PrivacySignals, RateLimitAction, SameSite,
+};
+#[cfg(feature = "csrf")]
+pub use csrf::{CsrfLayer, CsrfService, CSRF_FIELD, CSRF_HEADER};
 pub use databases::*;
-pub use errors::SessionError;
+pub use detached_session::DetachedSession;
+pub use errors::{SessionConfigError, SessionError, SessionRejection};
 pub use layer::AxumSessionLayer;
+pub use read_only_session::ReadOnlySession;
+pub use serializer::SessionSerializer;
 pub use session::AxumSession;
-pub use session_store::AxumSessionStore;
+pub use session_cleanup::SessionCleanupHandle;
+pub use session_data::{SessionSnapshot, SessionSummary};
+pub use session_events::SessionEventHandler;
+pub use session_id::{SessionIdGenerator, TokenGenerator, UuidGenerator};
+pub use session_namespace::SessionNamespace;
+pub use session_store::{AxumSessionStore, FlushReport, SessionCleanup, StoreHealth, StoreStats};
+pub use session_value::SessionValue;
+pub use session_write_behind::SessionWriteBehindHandle;
+pub use typed_data::SessionData;
 
-pub(crate) use service::{AxumSessionService, CookiesExt};
-pub(crate) use session_data::AxumSessionData;
-pub(crate) use session_id::AxumSessionID;
+pub(crate) use service::AxumSessionService;
+pub(crate) use session_access::AxumSessionAccess;
+pub(crate) use session_data::{clamp_lifespan, AxumSessionData, MAX_LIFESPAN_SECS};
+pub(crate) use session_events::catch_event_panic;
+pub(crate) use session_id::{short_id, AxumSessionID};
 pub(crate) use session_timers::AxumSessionTimers;
+pub(crate) use session_write_behind::WriteBehindQueue;
@@ -0,0 +1,173 @@
+use crate::{AxumDatabasePool, AxumSession, SessionData, SessionError, SessionRejection};
+use async_trait::async_trait;
+use axum_core::extract::FromRequestParts;
+use chrono::{DateTime, Duration, Utc};
+use http::request::Parts;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+/// A Session that can only be read.
+///
+/// When a request only extracts ReadOnlySession the layer does not update the Session's
+/// expiration or write it back to the database, and flash values are kept for the next request.
+/// It uses the same stored Session as AxumSession so no data is cloned.
+///
+/// # Examples
+/// ```rust ignore
+/// async fn profile(session: ReadOnlySession<AxumPgPool>) -> String {
+///     session.get::<String>("name").await.unwrap_or_default()
+/// }
+/// ```
+///
+pub struct ReadOnlySession<T, M = ()>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    session: AxumSession<T, M>,
+}
+
+impl<T, M> Clone for ReadOnlySession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            session: self.session.clone(),
+        }
+    }
+}
+
+impl<T, M> Debug for ReadOnlySession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadOnlySession")
+            .field("session", &self.session)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S, T, M> FromRequestParts<S> for ReadOnlySession<T, M>
+where
+    S: Send + Sync,
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+    M: Send + Sync + 'static,
+{
+    type Rejection = SessionRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let session = parts
+            .extensions
+            .get::<AxumSession<T, M>>()
+            .cloned()
+            .ok_or_else(|| SessionRejection::MissingLayer {
+                pool: std::any::type_name::<T>(),
+                marker: std::any::type_name::<M>(),
+            })?;
+
+        session.access.set_read_only();
+        Ok(ReadOnlySession { session })
+    }
+}
+
+impl<T, M> ReadOnlySession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    /// Returns the Current Session's ID. See AxumSession::id.
+    pub fn id(&self) -> String {
+        self.session.id()
+    }
+
+    /// Returns a log safe hash of the Current Session's ID. See AxumSession::short_id.
+    pub fn short_id(&self) -> String {
+        self.session.short_id()
+    }
+
+    /// Gets data from the Session's HashMap. See AxumSession::get.
+    pub async fn get<D: DeserializeOwned>(&self, key: &str) -> Option<D> {
+        self.session.get(key).await
+    }
+
+    /// Returns true if the Key exists within the Session. See AxumSession::contains_key.
+    pub async fn contains_key(&self, key: &str) -> bool {
+        self.session.contains_key(key).await
+    }
+
+    /// Gets data from the Session's HashMap returning any error. See AxumSession::try_get.
+    pub async fn try_get<D: DeserializeOwned>(&self, key: &str) -> Result<Option<D>, SessionError> {
+        self.session.try_get(key).await
+    }
+
+    /// Gets a binary value from the Session. See AxumSession::get_bytes.
+    pub async fn get_bytes(&self, key: &str) -> Option<Vec<u8>> {
+        self.session.get_bytes(key).await
+    }
+
+    /// Gets a SessionData Type from the Session's HashMap. See AxumSession::get_typed.
+    pub async fn get_typed<D: SessionData>(&self) -> Option<D> {
+        self.session.get_typed().await
+    }
+
+    /// Returns a snapshot of the Session's Keys. See AxumSession::keys.
+    pub async fn keys(&self) -> Vec<String> {
+        self.session.keys().await
+    }
+
+    /// Returns a snapshot of the Session's Keys and raw values. See AxumSession::entries_raw.
+    pub async fn entries_raw(&self) -> Vec<(String, String)> {
+        self.session.entries_raw().await
+    }
+
+    /// Returns how many Keys are stored within the Session. See AxumSession::len.
+    pub async fn len(&self) -> usize {
+        self.session.len().await
+    }
+
+    /// Returns true if no Keys are stored within the Session. See AxumSession::is_empty.
+    pub async fn is_empty(&self) -> bool {
+        self.session.is_empty().await
+    }
+
+    /// Returns true if the Session was accepted as storable. See AxumSession::is_accepted.
+    pub async fn is_accepted(&self) -> bool {
+        self.session.is_accepted().await
+    }
+
+    /// Returns true if the Session has a long term expiration. See AxumSession::is_longterm.
+    pub async fn is_longterm(&self) -> bool {
+        self.session.is_longterm().await
+    }
+
+    /// Returns true if the Session is set to be Destroyed. See AxumSession::is_destroyed.
+    pub async fn is_destroyed(&self) -> bool {
+        self.session.is_destroyed().await
+    }
+
+    /// Returns the time the Session was first created. See AxumSession::created_at.
+    pub async fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.session.created_at().await
+    }
+
+    /// Returns the time the Session was last used. See AxumSession::last_accessed.
+    pub async fn last_accessed(&self) -> Option<DateTime<Utc>> {
+        self.session.last_accessed().await
+    }
+
+    /// Returns the time the Session expires. See AxumSession::expires.
+    pub async fn expires(&self) -> Option<DateTime<Utc>> {
+        self.session.expires().await
+    }
+
+    /// Returns how long until the Session expires. See AxumSession::expires_in.
+    pub async fn expires_in(&self) -> Option<Duration> {
+        self.session.expires_in().await
+    }
+
+    /// Returns how many Sessions exist. See AxumSession::count.
+    pub async fn count(&self) -> i64 {
+        self.session.count().await
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{AxumSessionData, SessionError};
+
+/// Format used to encode the Session's data when stored within the database.
+///
+/// The binary formats are stored as base64 text with a `bincode:` or `msgpack:` prefix so every
+/// database backend keeps using the same text column. The prefix is used to detect when a Session
+/// was stored using a different format than the one configured.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, SessionSerializer};
+///
+/// let config = AxumSessionConfig::default().with_serializer(SessionSerializer::Json);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionSerializer {
+    /// Stores the Session as JSON. This is the Default.
+    Json,
+    /// Stores the Session using bincode.
+    #[cfg(feature = "bincode")]
+    Bincode,
+    /// Stores the Session using MessagePack.
+    #[cfg(feature = "rmp-serde")]
+    MessagePack,
+}
+
+impl SessionSerializer {
+    /// Returns the name of the format used within errors.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            SessionSerializer::Json => "json",
+            #[cfg(feature = "bincode")]
+            SessionSerializer::Bincode => "bincode",
+            #[cfg(feature = "rmp-serde")]
+            SessionSerializer::MessagePack => "msgpack",
+        }
+    }
+
+    /// Encodes the Session's data into a string to be stored within the database.
+    pub(crate) fn serialize(&self, session: &AxumSessionData) -> Result<String, SessionError> {
+        Ok(match self {
+            SessionSerializer::Json => serde_json::to_string(session)?,
+            #[cfg(feature = "bincode")]
+            SessionSerializer::Bincode => {
+                format!("bincode:{}", base64::encode(bincode::serialize(session)?))
+            }
+            #[cfg(feature = "rmp-serde")]
+            SessionSerializer::MessagePack => {
+                format!("msgpack:{}", base64::encode(rmp_serde::to_vec(session)?))
+            }
+        })
+    }
+
+    /// Decodes the Session's data loaded from the database.
+    ///
+    /// Returns ['SessionError::SerializerMismatch'] if it was stored with a different format.
+    pub(crate) fn deserialize(&self, session: &str) -> Result<AxumSessionData, SessionError> {
+        let found = match session.split_once(':') {
+            Some(("bincode", _)) => "bincode",
+            Some(("msgpack", _)) => "msgpack",
+            _ => "json",
+        };
+
+        if found != self.name() {
+            return Err(SessionError::SerializerMismatch(
+                found.to_owned(),
+                self.name().to_owned(),
+            ));
+        }
+
+        Ok(match self {
+            SessionSerializer::Json => serde_json::from_str(session)?,
+            #[cfg(feature = "bincode")]
+            SessionSerializer::Bincode => {
+                bincode::deserialize(&base64::decode(&session["bincode:".len()..])?)?
+            }
+            #[cfg(feature = "rmp-serde")]
+            SessionSerializer::MessagePack => {
+                rmp_serde::from_slice(&base64::decode(&session["msgpack:".len()..])?)?
+            }
+        })
+    }
+}
@@ -1,27 +1,34 @@
-use crate::{AxumDatabasePool, AxumSession, AxumSessionConfig, AxumSessionData, AxumSessionStore};
+use crate::{
+    session_metrics, short_id, AxumDatabasePool, AxumSession, AxumSessionConfig, AxumSessionData,
+    AxumSessionStore, CookieSecurity, IpBinding, PrivacySignals, RateLimitAction, SessionRejection,
+};
+use axum::extract::ConnectInfo;
 use axum_core::{
     body::{self, BoxBody},
-    response::Response,
+    response::{IntoResponse, Response},
     BoxError,
 };
 use bytes::Bytes;
 use chrono::Utc;
-use cookie::{Cookie, CookieJar, Key};
+use cookie::{Cookie, CookieJar, SameSite};
 use futures::future::BoxFuture;
 use http::{
     self,
-    header::{COOKIE, SET_COOKIE},
-    HeaderMap, Request,
+    header::{AUTHORIZATION, COOKIE, FORWARDED, SET_COOKIE, USER_AGENT},
+    HeaderMap, HeaderName, HeaderValue, Request,
 };
 use http_body::Body as HttpBody;
+use sha2::{Digest, Sha256};
 use std::{
     boxed::Box,
     convert::Infallible,
     fmt::{self, Debug, Formatter},
-    marker::{Send, Sync},
+    marker::{PhantomData, Send, Sync},
+    net::{IpAddr, SocketAddr},
     task::{Context, Poll},
 };
 use tower_service::Service;
+use tracing::Instrument;
 
 enum CookieType {
     Storable,
@@ -36,24 +43,64 @@ impl CookieType {
         }
     }
 
-    pub(crate) fn get_age(&self, config: &AxumSessionConfig) -> Option<chrono::Duration> {
+    pub(crate) fn get_age(
+        &self,
+        config: &AxumSessionConfig,
+        longterm: bool,
+        cookie_lifespan: Option<chrono::Duration>,
+    ) -> Option<chrono::Duration> {
+        // Long term sessions and sessions with set_expiry() still get a persistent cookie so Remember Me keeps working.
+        if config.browser_session_cookie {
+            return cookie_lifespan.or_else(|| longterm.then_some(config.max_lifespan));
+        }
+
         match self {
-            CookieType::Data => config.cookie_max_age,
+            CookieType::Data => cookie_lifespan.or(config.cookie_max_age),
             CookieType::Storable => config.storable_cookie_max_age,
         }
     }
 }
 
-#[derive(Clone)]
-pub struct AxumSessionService<S, T>
+/// Marks the Session as no longer used by the request once dropped.
+struct SessionInUse<T, M>(AxumSession<T, M>)
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static;
+
+impl<T, M> Drop for SessionInUse<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn drop(&mut self) {
+        if let Some(mut sess) = self.0.store.inner.get_mut(&self.0.id.inner()) {
+            sess.requests = sess.requests.saturating_sub(1);
+        }
+    }
+}
+
+pub struct AxumSessionService<S, T, M = ()>
 where
     T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
 {
     pub(crate) session_store: AxumSessionStore<T>,
     pub(crate) inner: S,
+    pub(crate) marker: PhantomData<fn() -> M>,
 }
 
-impl<S, T, ReqBody, ResBody> Service<Request<ReqBody>> for AxumSessionService<S, T>
+impl<S, T, M> Clone for AxumSessionService<S, T, M>
+where
+    S: Clone,
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            session_store: self.session_store.clone(),
+            inner: self.inner.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S, T, M, ReqBody, ResBody> Service<Request<ReqBody>> for AxumSessionService<S, T, M>
 where
     S: Service<Request<ReqBody>, Response = Response<ResBody>, Error = Infallible>
         + Clone
@@ -65,6 +112,7 @@ where
     ResBody: HttpBody<Data = Bytes> + Send + 'static,
     ResBody::Error: Into<BoxError>,
     T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+    M: Send + Sync + 'static,
 {
     type Response = Response<BoxBody>;
     type Error = Infallible;
@@ -80,30 +128,285 @@ where
         let mut ready_inner = std::mem::replace(&mut self.inner, not_ready_inner);
 
         Box::pin(async move {
+            // Excluded paths skip the Session before the store is used at all.
+            if store.config.is_excluded(req.uri().path()) {
+                return Ok(ready_inner.call(req).await?.map(body::boxed));
+            }
+
             let mut cookies = get_cookies(&req);
-            let session = AxumSession::new(&store, &cookies);
-            let accepted = cookies
-                .get_cookie(&store.config.storable_cookie_name, &store.config.key)
-                .map_or(false, |c| c.value().parse().unwrap_or(false));
-
-            // check if the session id exists if not lets check if it exists in the database or generate a new session.
-            if !store.service_session_data(&session) {
-                let mut sess = store
-                    .load_session(session.id.inner())
-                    .await
-                    .ok()
-                    .flatten()
-                    .unwrap_or_else(|| AxumSessionData::new(session.id.0, accepted, &store.config));
-
-                if !sess.validate() || sess.destroy {
-                    sess.destroy = false;
-                    sess.data.clear();
+            let session_header = get_session_header(&store.config);
+            let bearer_header = get_bearer_header(&store.config);
+            let bearer = bearer_header
+                .as_ref()
+                .and_then(|_| get_bearer_token(req.headers()));
+            let session_cookie = cookies.get_cookie(&store.config.cookie_name, &store.config);
+            let storable_cookie =
+                cookies.get_cookie(&store.config.storable_cookie_name, &store.config);
+            // Cookies only readable using a secondary key are sent again using the primary Key.
+            let rekey_cookies = [&session_cookie, &storable_cookie]
+                .into_iter()
+                .flatten()
+                .any(|(_, secondary)| *secondary);
+            let value = if let Some(name) = &session_header {
+                req.headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_owned)
+            } else if bearer.is_some() {
+                bearer.clone()
+            } else {
+                session_cookie.map(|(c, _)| c.value().to_owned())
+            };
+            let storable_cookie = storable_cookie.map(|(c, _)| c.value().to_owned());
+            // The IP is only read when Sessions are bound to it or new Sessions are rate limited.
+            let peer_ip = (store.config.ip_binding != IpBinding::Disabled
+                || store.config.session_rate_limit.is_some())
+            .then(|| get_client_ip(&req, &store.config))
+            .flatten();
+            let client_ip = peer_ip.filter(|_| store.config.ip_binding != IpBinding::Disabled);
+            let user_agent = store
+                .config
+                .user_agent_binding
+                .then(|| get_user_agent_hash(&req, &store.config))
+                .flatten();
+            let session = AxumSession::<T, M>::new(&store, value.clone(), client_ip, user_agent);
+            // Header clients have no cookie to accept so they are always treated as accepted.
+            let cookie_client = session_header.is_none() && bearer.is_none();
+            let declined = cookie_client
+                && store.config.privacy_signals != PrivacySignals::Ignore
+                && sends_privacy_signal(req.headers());
+            let accepted = !cookie_client
+                || match storable_cookie.as_deref() {
+                    Some(c) => c.parse().unwrap_or(false),
+                    // Without an acceptance cookie not sending the privacy headers can count as accepting.
+                    None => {
+                        store.config.privacy_signals == PrivacySignals::HonorOrAccept && !declined
+                    }
+                };
+
+            // Database only mode always loads the session so changes made by other instances are seen.
+            let database_only = store.config.database_only && store.is_persistent();
+
+            // Always mode has no acceptance gating so new sessions start as storable.
+            let storable = accepted || !store.config.session_mode.is_storable();
+
+            // Each phase has its own span so the time spent loading and saving the Session can be seen.
+            let rate_limited = async {
+                let in_memory = if database_only {
+                    None
+                } else {
+                    store.service_session_data(&session)
+                };
+
+                if in_memory.is_some() {
+                    tracing::debug!(session = %session.short_id(), "Session memory cache hit");
+                } else {
+                    tracing::debug!(session = %session.short_id(), "Session memory cache miss");
+                }
+
+                if in_memory == Some(true) {
+                    store.on_expired(&session.id.inner()).await;
+                    store.on_create(&session.id.inner()).await;
+                }
+
+                // check if the session id exists if not lets check if it exists in the database or generate a new session.
+                if in_memory.is_none() {
+                    let loaded = match store.load_session(session.id.inner()).await {
+                        Ok(sess) => sess,
+                        Err(err) => {
+                            tracing::error!(session = %session.short_id(), "Failed to load session: {}", err);
+                            None
+                        }
+                    };
+
+                    let expired = loaded
+                        .as_ref()
+                        .is_some_and(|sess| !sess.validate_policy(&store.config));
+                    let reloaded = loaded
+                        .as_ref()
+                        .is_some_and(|sess| !expired && !sess.destroy);
+
+                    // Lazy Sessions are only created once the request sets something within them.
+                    if loaded.is_none() && store.config.lazy_sessions {
+                        session.access.set_pending(storable);
+
+                        if declined {
+                            session.access.set_declined();
+                        }
+
+                        return false;
+                    }
+
+                    // Clients past the rate limit do not get a new Session.
+                    let limited = !reloaded && !store.allow_new_session(peer_ip).await;
+
+                    if limited {
+                        tracing::debug!(session = %session.short_id(), "New session was rate limited");
+
+                        if store.config.session_rate_limit.map(|(_, _, action)| action)
+                            == Some(RateLimitAction::TooManyRequests)
+                        {
+                            return true;
+                        }
+
+                        // The throwaway Session uses its own ID so it never replaces a stored Session.
+                        session.access.set_throwaway();
+                        session.id.set(store.generate_id());
+                    }
+
+                    let mut sess = loaded.filter(|_| !limited).unwrap_or_else(|| {
+                        AxumSessionData::new(session.id.inner(), storable, &store.config)
+                    });
+
+                    if !sess.validate_policy(&store.config) || sess.destroy {
+                        sess.reset();
+                        // Gives the new Session a current expiration so cleanup will not remove it mid request.
+                        sess.update_expiry(&store.config);
+                    }
+
                     sess.autoremove = Utc::now() + store.config.memory_lifespan;
+                    // Keeps the count of other requests using the Session.
+                    sess.requests = store
+                        .inner
+                        .get(&session.id.inner())
+                        .map_or(0, |sess| sess.requests);
+
+                    store.inner.insert(session.id.inner(), sess);
+                    store.evict_memory();
+                    session_metrics::gauge(
+                        &store.config,
+                        "memory_sessions",
+                        store.inner.len() as f64,
+                    );
+
+                    if reloaded {
+                        store.on_load(&session.id.inner()).await;
+                    } else if !limited {
+                        if expired {
+                            store.on_expired(&session.id.inner()).await;
+                        }
+
+                        store.on_create(&session.id.inner()).await;
+                    }
+                }
+
+                // Sessions are bound to the IP of their first request and checked on the ones after.
+                let mut rejected = false;
+
+                if let Some(ip) = session.client_ip {
+                    let session_ip = store
+                        .inner
+                        .get_mut(&session.id.inner())
+                        .and_then(|mut sess| {
+                            if sess.ip.is_none() {
+                                sess.ip = Some(ip);
+                                sess.update = true;
+                            }
+
+                            sess.ip
+                        })
+                        .filter(|session_ip| !store.config.ip_matches(*session_ip, ip));
+
+                    if let Some(session_ip) = session_ip {
+                        if store.config.ip_binding == IpBinding::Reject {
+                            tracing::warn!(session = %session.short_id(), "Session used from another IP was rejected");
+                            rejected = true;
+                        } else {
+                            store
+                                .on_ip_mismatch(&session.id.inner(), session_ip, ip)
+                                .await;
+                        }
+                    }
+                }
+
+                // Sessions created without a User-Agent are bound to the first one sent.
+                if store.config.user_agent_binding {
+                    let mismatch = store
+                        .inner
+                        .get_mut(&session.id.inner())
+                        .is_some_and(|mut sess| match &sess.user_agent {
+                            Some(user_agent) => session.user_agent.as_ref() != Some(user_agent),
+                            None => {
+                                sess.update |= session.user_agent.is_some();
+                                sess.user_agent = session.user_agent.clone();
+                                false
+                            }
+                        });
+
+                    if mismatch {
+                        tracing::warn!(session = %session.short_id(), "Session used from another User-Agent was rejected");
+                        store.on_user_agent_mismatch(&session.id.inner()).await;
+                        rejected = true;
+                    }
                 }
 
-                store.inner.insert(session.id.inner(), sess);
+                if rejected {
+                    // The original Session is left alone so it keeps working for its own client.
+                    session.id.set(store.generate_id());
+
+                    if store.config.lazy_sessions {
+                        session.access.set_pending(storable);
+
+                        if declined {
+                            session.access.set_declined();
+                        }
+
+                        return false;
+                    }
+
+                    let mut sess =
+                        AxumSessionData::new(session.id.inner(), storable, &store.config);
+                    sess.ip = session.client_ip;
+                    sess.user_agent = session.user_agent.clone();
+
+                    store.inner.insert(session.id.inner(), sess);
+                    store.evict_memory();
+                    store.on_create(&session.id.inner()).await;
+                }
+
+                // Requests declining cookies only get a Session for the request unless their Session
+                // was accepted using session.set_store(true). Their own Session is left alone.
+                let consented = store
+                    .inner
+                    .get(&session.id.inner())
+                    .is_some_and(|sess| sess.consented);
+
+                if declined && !consented {
+                    tracing::debug!(session = %session.short_id(), "Request declined cookies using a privacy header");
+                    session.access.set_declined();
+                    session.id.set(store.generate_id());
+                    store.inner.insert(
+                        session.id.inner(),
+                        AxumSessionData::new(session.id.inner(), false, &store.config),
+                    );
+                }
+
+                if let Some(mut sess) = store.inner.get_mut(&session.id.inner()) {
+                    sess.touch(&store.config);
+                    sess.start_flash();
+                    sess.memory_used = Utc::now();
+                    sess.requests += 1;
+                }
+
+                false
+            }
+            .instrument(tracing::debug_span!(
+                "session_layer",
+                phase = "load",
+                session = %session.short_id()
+            ))
+            .await;
+
+            if rate_limited {
+                return Ok(handle_rejection(
+                    SessionRejection::RateLimited.into_response(),
+                    &store.config,
+                ));
             }
 
+            // Keeps the Session from being evicted from memory until the request is done.
+            let _in_use = SessionInUse(session.clone());
+
             let (last_sweep, last_database_sweep) = {
                 let timers = store.timers.read().await;
                 (timers.last_expiry_sweep, timers.last_database_expiry_sweep)
@@ -114,56 +417,257 @@ where
             // forever by abandoned sessions (e.g. when a client lost their cookie)
             // Throttle by memory lifespan - e.g. sweep every hour
             if last_sweep <= Utc::now() {
-                store.inner.retain(|_k, v| v.autoremove > Utc::now());
+                let mut expired = Vec::new();
+
+                store.inner.retain(|k, v| {
+                    let keep = v.autoremove > Utc::now();
+
+                    // Sessions within the database are only dropped from memory, not expired.
+                    if !keep && store.is_memory_only(v) {
+                        expired.push(k.clone());
+                    }
+
+                    keep
+                });
+                store.destroyed.retain(|_k, until| *until > Utc::now());
                 store.timers.write().await.last_expiry_sweep =
                     Utc::now() + store.config.memory_lifespan;
+
+                for id in expired {
+                    store.on_expired(&id).await;
+                }
             }
 
             // Throttle by database lifespan - e.g. sweep every 6 hours
             if last_database_sweep <= Utc::now() && store.is_persistent() {
-                store.cleanup().await.unwrap();
+                if let Err(err) = store.cleanup().await {
+                    tracing::error!("Failed to clean up expired sessions: {}", err);
+                }
                 store.timers.write().await.last_database_expiry_sweep =
                     Utc::now() + store.config.lifespan;
             }
 
+            let lifespan_before = session
+                .store
+                .inner
+                .get(&session.id.inner())
+                .map(|sess| (sess.longterm, sess.cookie_lifespan()));
+            let storable_before = session
+                .store
+                .inner
+                .get(&session.id.inner())
+                .map(|sess| sess.storable);
+
             //Sets a clone of the Store in the Extensions for Direct usage and sets the Session for Direct usage
             req.extensions_mut().insert(store.clone());
             req.extensions_mut().insert(session.clone());
 
-            let mut response = ready_inner.call(req).await?.map(body::boxed);
+            let original_id = session.id.inner();
+            let mut response =
+                handle_rejection(ready_inner.call(req).await?.map(body::boxed), &store.config);
 
-            let storable = if let Some(session_data) = session.store.inner.get(&session.id.inner())
-            {
-                session_data.storable
-            } else {
-                false
-            };
+            async {
+                // Lazy Sessions that were never set are dropped without storing them or sending cookies.
+                if session.access.is_pending() {
+                    return;
+                }
+
+                // Requests declining cookies keep their Session once session.set_store(true) was called.
+                let promoted = session.access.is_declined()
+                    && store
+                        .inner
+                        .get(&session.id.inner())
+                        .is_some_and(|sess| sess.consented);
+
+                // Sessions given to rate limited clients are dropped without storing them or sending cookies.
+                if session.access.is_throwaway() && !promoted {
+                    store.inner.remove(&session.id.inner());
+                    return;
+                }
+
+                if promoted {
+                    store.on_create(&session.id.inner()).await;
+                }
+
+                let accepted = accepted || promoted;
 
-            // Add the Storable Cookie so we can keep track if they can store the session.
-            // Todo: Maybe add a way to store expiration times and such for accepted or not accept via json.
-            cookies.add_cookie(
-                create_cookie(&store.config, storable.to_string(), CookieType::Storable),
-                &store.config.key,
-            );
-
-            // Add the Session ID so it can link back to a Session if one exists.
-            cookies.add_cookie(
-                create_cookie(&store.config, session.id.inner(), CookieType::Data),
-                &store.config.key,
-            );
-
-            if !store.config.session_mode.is_storable() || accepted {
-                // run this After a response has returned so we save the most updated data to sql.
-                if store.is_persistent() {
+                // Sessions used again after destroy() continue as a new Session with a new ID.
+                let reused = session
+                    .store
+                    .inner
+                    .get_mut(&session.id.inner())
+                    .map(|mut sess| {
+                        let reused = sess.destroy
+                            && (!sess.data.is_empty()
+                                || !sess.bytes.is_empty()
+                                || !sess.flash.is_empty());
+
+                        if reused {
+                            sess.destroy = false;
+                        }
+
+                        reused
+                    })
+                    .unwrap_or(false);
+
+                if reused {
+                    session.renew().await;
+                }
+
+                // The ID used before the Session was accepted gets replaced once it is accepted.
+                let newly_accepted = store.config.renew_on_accept
+                    && storable_before == Some(false)
+                    && session
+                        .store
+                        .inner
+                        .get(&session.id.inner())
+                        .is_some_and(|sess| sess.storable && !sess.destroy);
+
+                if newly_accepted && !reused {
+                    session.change_id(false);
+                }
+
+                // The ID is checked after the request since session.renew() could have changed it.
+                let is_new = value.as_deref() != Some(session.id.inner().as_str());
+
+                if original_id != session.id.inner() && store.is_persistent() {
+                    if let Err(err) = session.store.delete_session(&original_id).await {
+                        tracing::error!(
+                            session = %short_id(&original_id),
+                            "Failed to destroy renewed session: {}",
+                            err
+                        );
+                    }
+                }
+
+                // Requests that only used ReadOnlySession do not update or store the Session.
+                let read_only = session.access.is_read_only();
+
+                // Flash values that were not taken during this request are dropped.
+                // ReadOnlySession can not take them so they are kept for the next request instead.
+                if let Some(mut sess) = session.store.inner.get_mut(&session.id.inner()) {
+                    let current = std::mem::take(&mut sess.flash_current);
+
+                    if read_only {
+                        for (key, value) in current {
+                            sess.flash.entry(key).or_insert(value);
+                        }
+                    }
+                }
+
+                let (storable, destroy, longterm, cookie_lifespan, refresh_due) =
+                    if let Some(session_data) = session.store.inner.get(&session.id.inner()) {
+                        (
+                            session_data.storable,
+                            session_data.destroy,
+                            session_data.longterm,
+                            session_data.cookie_lifespan(),
+                            session_data.cookie_refresh_due(&store.config),
+                        )
+                    } else {
+                        // Keep the acceptance the browser already has when the Session went missing.
+                        (accepted, false, false, None, true)
+                    };
+
+                // Sessions destroyed through the store while this request ran are destroyed here too.
+                let destroy = destroy || store.is_destroyed(&session.id.inner());
+
+                // Only send the cookies when something about them changed so responses stay cacheable.
+                let send_cookies = is_new
+                    || destroy
+                    || refresh_due
+                    || rekey_cookies
+                    || lifespan_before.unwrap_or_default() != (longterm, cookie_lifespan)
+                    || storable_cookie.as_deref() != Some(storable.to_string().as_str());
+
+                if destroy {
+                    // Remove the Session so the next request starts with a new Session ID.
+                    store.inner.remove(&session.id.inner());
+
+                    if store.is_persistent() {
+                        if let Err(err) = session.store.delete_session(&session.id.inner()).await {
+                            tracing::error!(session = %session.short_id(), "Failed to destroy session: {}", err);
+                        }
+                    }
+                }
+
+                if let Some(name) = &session_header {
+                    // Return the Session ID within the same header instead of using cookies.
+                    if !destroy {
+                        if let Ok(value) = HeaderValue::from_str(&session.id.inner()) {
+                            response.headers_mut().insert(name.clone(), value);
+                        }
+                    }
+                } else if bearer.is_some() {
+                    // Bearer clients never get cookies. New Session IDs are returned within the bearer header.
+                    if let Some(name) = bearer_header.filter(|_| is_new && !destroy) {
+                        if let Ok(value) = HeaderValue::from_str(&session.id.inner()) {
+                            response.headers_mut().insert(name, value);
+                        }
+                    }
+                } else {
+                    // New Sessions could be from a bearer client so also return the Session ID within the bearer header.
+                    if let Some(name) = bearer_header.filter(|_| is_new && !destroy) {
+                        if let Ok(value) = HeaderValue::from_str(&session.id.inner()) {
+                            response.headers_mut().insert(name, value);
+                        }
+                    }
+
+                    if send_cookies {
+                        // Add the Storable Cookie so we can keep track if they can store the session.
+                        // Todo: Maybe add a way to store expiration times and such for accepted or not accept via json.
+                        cookies.add_cookie(
+                            create_cookie(
+                                &store.config,
+                                storable.to_string(),
+                                CookieType::Storable,
+                                longterm,
+                                cookie_lifespan,
+                            ),
+                            &store.config,
+                        );
+
+                        if destroy {
+                            // Uses the same attributes as the created cookie or browsers will not clear it.
+                            cookies.remove_cookie(
+                                create_cookie(
+                                    &store.config,
+                                    String::new(),
+                                    CookieType::Data,
+                                    false,
+                                    None,
+                                ),
+                                &store.config,
+                            );
+                        } else {
+                            // Add the Session ID so it can link back to a Session if one exists.
+                            cookies.add_cookie(
+                                create_cookie(
+                                    &store.config,
+                                    session.id.inner(),
+                                    CookieType::Data,
+                                    longterm,
+                                    cookie_lifespan,
+                                ),
+                                &store.config,
+                            );
+
+                            if let Some(mut sess) = session.store.inner.get_mut(&session.id.inner())
+                            {
+                                sess.cookie_sent = Some(Utc::now());
+                            }
+                        }
+                    }
+                }
+
+                if (!store.config.session_mode.is_storable() || accepted) && !read_only {
+                    // run this After a response has returned so we save the most updated data to sql.
                     let sess =
                         if let Some(mut sess) = session.store.inner.get_mut(&session.id.inner()) {
-                            if sess.longterm {
-                                sess.expires = Utc::now() + store.config.max_lifespan;
-                            } else {
-                                sess.expires = Utc::now() + store.config.lifespan;
-                            }
+                            // Memory only sessions also keep their expiration current for session.expires().
+                            sess.update_expiry(&store.config);
 
-                            if sess.update {
+                            if sess.update && store.is_persistent() {
                                 sess.update = false;
                                 Some(sess.clone())
                             } else {
@@ -174,23 +678,37 @@ where
                         };
 
                     if let Some(sess) = sess {
-                        session.store.store_session(&sess).await.unwrap()
+                        if let Err(err) = session.store.save_session(&sess).await {
+                            tracing::error!(session = %session.short_id(), "Failed to store session: {}", err);
+                        }
                     }
                 }
-            }
 
-            if store.config.session_mode.is_storable() && !accepted {
-                store.inner.remove(&session.id.inner());
+                if store.config.session_mode.is_storable() && !accepted {
+                    store.inner.remove(&session.id.inner());
 
-                // Also run this just in case it was stored in the database and they rejected storability.
-                if store.is_persistent() {
-                    session
-                        .store
-                        .destroy_session(&session.id.inner())
-                        .await
-                        .unwrap();
+                    // Also run this just in case it was stored in the database and they rejected storability.
+                    if store.is_persistent() {
+                        if let Err(err) = session.store.delete_session(&session.id.inner()).await {
+                            tracing::error!(session = %session.short_id(), "Failed to destroy session: {}", err);
+                        }
+                    }
+                }
+
+                // The session is already written back so it only lived in memory for this request.
+                // It stays while other requests or a DetachedSession still use it.
+                if database_only {
+                    store
+                        .inner
+                        .remove_if(&session.id.inner(), |_, sess| sess.requests <= 1);
                 }
             }
+            .instrument(tracing::debug_span!(
+                "session_layer",
+                phase = "save",
+                session = %session.short_id()
+            ))
+            .await;
 
             set_cookies(cookies, response.headers_mut());
 
@@ -199,7 +717,7 @@ where
     }
 }
 
-impl<S, T> Debug for AxumSessionService<S, T>
+impl<S, T, M> Debug for AxumSessionService<S, T, M>
 where
     S: Debug,
     T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
@@ -213,24 +731,134 @@ where
 }
 
 pub(crate) trait CookiesExt {
-    fn get_cookie(&self, name: &str, key: &Option<Key>) -> Option<Cookie<'static>>;
-    fn add_cookie(&mut self, cookie: Cookie<'static>, key: &Option<Key>);
+    /// Returns the cookie along with true if it was only readable using a secondary key.
+    fn get_cookie(&self, name: &str, config: &AxumSessionConfig)
+        -> Option<(Cookie<'static>, bool)>;
+    fn add_cookie(&mut self, cookie: Cookie<'static>, config: &AxumSessionConfig);
+    fn remove_cookie(&mut self, cookie: Cookie<'static>, config: &AxumSessionConfig);
 }
 
 impl CookiesExt for CookieJar {
-    fn get_cookie(&self, name: &str, key: &Option<Key>) -> Option<Cookie<'static>> {
-        if let Some(key) = key {
-            self.private(key).get(name)
-        } else {
-            self.get(name).cloned()
+    fn get_cookie(
+        &self,
+        name: &str,
+        config: &AxumSessionConfig,
+    ) -> Option<(Cookie<'static>, bool)> {
+        let key = match (&config.key, config.cookie_security) {
+            (None, _) | (_, CookieSecurity::None) => {
+                return self.get(name).cloned().map(|c| (c, false))
+            }
+            (Some(key), _) => key,
+        };
+
+        // Try the primary key first then fall back to the secondary keys for key rotation.
+        std::iter::once(key)
+            .chain(config.secondary_keys.iter())
+            .enumerate()
+            .find_map(|(index, key)| {
+                match config.cookie_security {
+                    CookieSecurity::Signed => self.signed(key).get(name),
+                    _ => self.private(key).get(name),
+                }
+                .map(|cookie| (cookie, index > 0))
+            })
+    }
+
+    fn add_cookie(&mut self, cookie: Cookie<'static>, config: &AxumSessionConfig) {
+        match (&config.key, config.cookie_security) {
+            (Some(key), CookieSecurity::Signed) => self.signed_mut(key).add(cookie),
+            (Some(key), CookieSecurity::Private) => self.private_mut(key).add(cookie),
+            (None, _) | (_, CookieSecurity::None) => self.add(cookie),
         }
     }
 
-    fn add_cookie(&mut self, cookie: Cookie<'static>, key: &Option<Key>) {
-        if let Some(key) = key {
-            self.private_mut(key).add(cookie)
-        } else {
-            self.add(cookie)
+    fn remove_cookie(&mut self, cookie: Cookie<'static>, config: &AxumSessionConfig) {
+        match (&config.key, config.cookie_security) {
+            (Some(key), CookieSecurity::Signed) => self.signed_mut(key).remove(cookie),
+            (Some(key), CookieSecurity::Private) => self.private_mut(key).remove(cookie),
+            (None, _) | (_, CookieSecurity::None) => self.remove(cookie),
+        }
+    }
+}
+
+#[cfg(feature = "tower-cookies")]
+impl CookiesExt for tower_cookies::Cookies {
+    fn get_cookie(
+        &self,
+        name: &str,
+        config: &AxumSessionConfig,
+    ) -> Option<(Cookie<'static>, bool)> {
+        let key = match (&config.key, config.cookie_security) {
+            (None, _) | (_, CookieSecurity::None) => {
+                return self.get(name).map(|c| (c.into_owned(), false))
+            }
+            (Some(key), _) => key,
+        };
+
+        std::iter::once(key)
+            .chain(config.secondary_keys.iter())
+            .enumerate()
+            .find_map(|(index, key)| {
+                match config.cookie_security {
+                    CookieSecurity::Signed => self.signed(key).get(name),
+                    _ => self.private(key).get(name),
+                }
+                .map(|cookie| (cookie, index > 0))
+            })
+    }
+
+    fn add_cookie(&mut self, cookie: Cookie<'static>, config: &AxumSessionConfig) {
+        match (&config.key, config.cookie_security) {
+            (Some(key), CookieSecurity::Signed) => self.signed(key).add(cookie),
+            (Some(key), CookieSecurity::Private) => self.private(key).add(cookie),
+            (None, _) | (_, CookieSecurity::None) => self.add(cookie),
+        }
+    }
+
+    fn remove_cookie(&mut self, cookie: Cookie<'static>, config: &AxumSessionConfig) {
+        match (&config.key, config.cookie_security) {
+            (Some(key), CookieSecurity::Signed) => self.signed(key).remove(cookie),
+            (Some(key), CookieSecurity::Private) => self.private(key).remove(cookie),
+            (None, _) | (_, CookieSecurity::None) => self.remove(cookie),
+        }
+    }
+}
+
+/// The jar the Session's cookies are read from and written to.
+enum SessionCookies {
+    /// Parsed from the request by this layer which also writes its changes to the response.
+    Jar(CookieJar),
+    /// Shared with an outer tower_cookies::CookieManagerLayer which writes the changes itself.
+    #[cfg(feature = "tower-cookies")]
+    Shared(tower_cookies::Cookies),
+}
+
+impl CookiesExt for SessionCookies {
+    fn get_cookie(
+        &self,
+        name: &str,
+        config: &AxumSessionConfig,
+    ) -> Option<(Cookie<'static>, bool)> {
+        match self {
+            SessionCookies::Jar(jar) => jar.get_cookie(name, config),
+            #[cfg(feature = "tower-cookies")]
+            SessionCookies::Shared(cookies) => cookies.get_cookie(name, config),
+        }
+    }
+
+    fn add_cookie(&mut self, cookie: Cookie<'static>, config: &AxumSessionConfig) {
+        match self {
+            SessionCookies::Jar(jar) => jar.add_cookie(cookie, config),
+            #[cfg(feature = "tower-cookies")]
+            SessionCookies::Shared(cookies) => cookies.add_cookie(cookie, config),
+        }
+    }
+
+    fn remove_cookie(&mut self, cookie: Cookie<'static>, config: &AxumSessionConfig) {
+        match self {
+            SessionCookies::Jar(jar) => jar.remove_cookie(cookie, config),
+            #[cfg(feature = "tower-cookies")]
+            SessionCookies::Shared(cookies) => cookies.remove_cookie(cookie, config),
         }
     }
 }
@@ -239,28 +867,181 @@ fn create_cookie<'a>(
     config: &AxumSessionConfig,
     value: String,
     cookie_type: CookieType,
+    longterm: bool,
+    cookie_lifespan: Option<chrono::Duration>,
 ) -> Cookie<'a> {
+    // Browsers reject SameSite=None cookies that are not Secure so we force it on.
+    let secure = config.cookie_secure || config.cookie_same_site == SameSite::None;
+
     let mut cookie_builder = Cookie::build(cookie_type.get_name(config), value)
         .path(config.cookie_path.clone())
-        .secure(config.cookie_secure)
-        .http_only(config.cookie_http_only);
+        .secure(secure)
+        .http_only(config.cookie_http_only)
+        .same_site(config.cookie_same_site);
 
     if let Some(domain) = &config.cookie_domain {
-        cookie_builder = cookie_builder
-            .domain(domain.clone())
-            .same_site(config.cookie_same_site);
+        cookie_builder = cookie_builder.domain(domain.clone());
     }
 
-    if let Some(max_age) = cookie_type.get_age(config) {
-        let time_duration = max_age.to_std().expect("Max Age out of bounds");
-        cookie_builder =
-            cookie_builder.max_age(time_duration.try_into().expect("Max Age out of bounds"));
+    if let Some(max_age) = cookie_type.get_age(config, longterm, cookie_lifespan) {
+        // Negative ages can only come from an invalid config so they expire the cookie instead of failing.
+        cookie_builder = cookie_builder.max_age(cookie::time::Duration::seconds(
+            max_age.num_seconds().max(0),
+        ));
     }
 
     cookie_builder.finish()
 }
 
-fn get_cookies<ReqBody>(req: &Request<ReqBody>) -> CookieJar {
+/// Returns true if the request sends `DNT: 1` or `Sec-GPC: 1`.
+fn sends_privacy_signal(headers: &HeaderMap) -> bool {
+    ["dnt", "sec-gpc"].into_iter().any(|name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.trim() == "1")
+    })
+}
+
+/// Replaces the default response of a SessionRejection using the config's rejection handler.
+fn handle_rejection(response: Response<BoxBody>, config: &AxumSessionConfig) -> Response<BoxBody> {
+    match (
+        &config.rejection_handler,
+        response.extensions().get::<SessionRejection>(),
+    ) {
+        (Some(handler), Some(rejection)) => handler(rejection.clone()),
+        _ => response,
+    }
+}
+
+fn get_session_header(config: &AxumSessionConfig) -> Option<HeaderName> {
+    config
+        .session_header
+        .as_ref()
+        .and_then(|name| HeaderName::from_bytes(name.as_bytes()).ok())
+}
+
+fn get_bearer_header(config: &AxumSessionConfig) -> Option<HeaderName> {
+    config
+        .bearer_header
+        .as_ref()
+        .and_then(|name| HeaderName::from_bytes(name.as_bytes()).ok())
+}
+
+/// Gets the token from `Authorization: Bearer <token>` ignoring any other schemes.
+fn get_bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(AUTHORIZATION)
+        .into_iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.trim().split_once(' '))
+        .find(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+        .map(|(_, token)| token.trim().to_owned())
+}
+
+/// Gets the client's IP from axum's ConnectInfo.
+///
+/// Requests from trusted proxies use the address they forwarded for instead, walking the
+/// `Forwarded` or `X-Forwarded-For` header from the right until an untrusted address is found.
+fn get_client_ip<ReqBody>(req: &Request<ReqBody>, config: &AxumSessionConfig) -> Option<IpAddr> {
+    let mut ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()?
+        .0
+        .ip()
+        .to_canonical();
+    let headers = req.headers();
+    let values = |name| {
+        headers
+            .get_all(name)
+            .iter()
+            .flat_map(|value| value.to_str().unwrap_or_default().split(','))
+    };
+
+    // Forwarded replaces X-Forwarded-For so only one of them is used.
+    let hops: Vec<Option<IpAddr>> = if headers.contains_key(FORWARDED) {
+        values(FORWARDED)
+            .map(|element| {
+                element
+                    .split(';')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                    .and_then(|(_, node)| parse_forwarded_ip(node))
+            })
+            .collect()
+    } else {
+        values(HeaderName::from_static("x-forwarded-for"))
+            .map(parse_forwarded_ip)
+            .collect()
+    };
+
+    for hop in hops.into_iter().rev() {
+        match hop {
+            Some(hop)
+                if config
+                    .trusted_proxies
+                    .iter()
+                    .any(|p| p.to_canonical() == ip) =>
+            {
+                ip = hop
+            }
+            // Addresses that can not be read stop the walk since nothing after them can be trusted.
+            _ => break,
+        }
+    }
+
+    Some(ip)
+}
+
+/// Hashes the User-Agent header so the raw string is never stored.
+///
+/// Digits are removed first when AxumSessionConfig::with_user_agent_normalization is set.
+fn get_user_agent_hash<ReqBody>(
+    req: &Request<ReqBody>,
+    config: &AxumSessionConfig,
+) -> Option<String> {
+    let user_agent = req.headers().get(USER_AGENT)?.as_bytes();
+    let mut hasher = Sha256::new();
+
+    if config.user_agent_normalization {
+        hasher.update(
+            user_agent
+                .iter()
+                .copied()
+                .filter(|c| !c.is_ascii_digit())
+                .collect::<Vec<u8>>(),
+        );
+    } else {
+        hasher.update(user_agent);
+    }
+
+    Some(base64::encode_config(
+        hasher.finalize(),
+        base64::URL_SAFE_NO_PAD,
+    ))
+}
+
+/// Parses an address like `192.0.2.1`, `192.0.2.1:80` or `"[2001:db8::1]:80"` from a forwarded header.
+fn parse_forwarded_ip(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    let ip = match node.strip_prefix('[') {
+        Some(rest) => rest.split(']').next()?.parse().ok()?,
+        None => node
+            .parse::<IpAddr>()
+            .or_else(|_| node.parse::<SocketAddr>().map(|addr| addr.ip()))
+            .ok()?,
+    };
+
+    Some(ip.to_canonical())
+}
+
+fn get_cookies<ReqBody>(req: &Request<ReqBody>) -> SessionCookies {
+    // Share the jar of an outer tower-cookies layer so both agree on the response's cookies.
+    #[cfg(feature = "tower-cookies")]
+    if let Some(cookies) = req.extensions().get::<tower_cookies::Cookies>() {
+        return SessionCookies::Shared(cookies.clone());
+    }
+
     let mut jar = CookieJar::new();
 
     let cookie_iter = req
@@ -275,13 +1056,20 @@ fn get_cookies<ReqBody>(req: &Request<ReqBody>) -> CookieJar {
         jar.add_original(cookie);
     }
 
-    jar
+    SessionCookies::Jar(jar)
 }
 
-fn set_cookies(jar: CookieJar, headers: &mut HeaderMap) {
-    for cookie in jar.delta() {
-        if let Ok(header_value) = cookie.encoded().to_string().parse() {
-            headers.append(SET_COOKIE, header_value);
+fn set_cookies(cookies: SessionCookies, headers: &mut HeaderMap) {
+    match cookies {
+        SessionCookies::Jar(jar) => {
+            for cookie in jar.delta() {
+                if let Ok(header_value) = cookie.encoded().to_string().parse() {
+                    headers.append(SET_COOKIE, header_value);
+                }
+            }
         }
+        // The tower-cookies layer writes the shared jar's changes itself.
+        #[cfg(feature = "tower-cookies")]
+        SessionCookies::Shared(_) => {}
     }
 }
@@ -1,71 +1,142 @@
-use crate::{AxumDatabasePool, AxumSessionData, AxumSessionID, AxumSessionStore, CookiesExt};
+use crate::{
+    clamp_lifespan, short_id, AxumDatabasePool, AxumSessionAccess, AxumSessionData, AxumSessionID,
+    AxumSessionStore, DetachedSession, SessionData, SessionError, SessionIdGenerator,
+    SessionNamespace, SessionRejection, TokenGenerator,
+};
 use async_trait::async_trait;
-use axum_core::extract::{FromRequest, RequestParts};
-use cookie::CookieJar;
-use http::{self, StatusCode};
+use axum_core::extract::FromRequestParts;
+use chrono::{DateTime, Duration, Utc};
+use http::request::Parts;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
+    collections::HashMap,
     fmt::Debug,
-    marker::{Send, Sync},
+    marker::{PhantomData, Send, Sync},
+    net::IpAddr,
+    sync::Arc,
 };
-use uuid::Uuid;
 
 /// A Session Store.
 ///
-/// Provides a Storage Handler to AxumSessionStore and contains the AxumSessionID(UUID) of the current session.
+/// Provides a Storage Handler to AxumSessionStore and contains the AxumSessionID of the current session.
 ///
 /// This is Auto generated by the Session Layer Upon Service Execution.
-#[derive(Debug, Clone)]
-pub struct AxumSession<T>
+///
+/// The marker type `M` lets several [`crate::AxumSessionLayer`]s run within the same Router.
+/// Each layer made with AxumSessionLayer::named() is extracted using its own marker.
+///
+/// # Examples
+/// ```rust ignore
+/// struct Admin;
+///
+/// async fn handler(public: AxumSession<AxumPgPool>, admin: AxumSession<AxumPgPool, Admin>) {}
+/// ```
+///
+/// Extract `Option<AxumSession>` within handlers shared with routes that do not have the layer.
+/// It is None instead of rejecting the request with a [`crate::SessionRejection`].
+///
+/// ```rust ignore
+/// async fn shared(session: Option<AxumSession<AxumPgPool>>) {}
+/// ```
+///
+/// Clones share the same Session. Changes made once the response was returned, like from a
+/// WebSocket or spawned task, are only written by the Session's next request. Use
+/// AxumSession::detach() for those so they are kept and written once the handle is dropped.
+///
+pub struct AxumSession<T, M = ()>
 where
     T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
 {
     pub(crate) store: AxumSessionStore<T>,
     pub(crate) id: AxumSessionID,
+    pub(crate) access: Arc<AxumSessionAccess>,
+    /// The client's IP when AxumSessionConfig::with_ip_binding is set.
+    pub(crate) client_ip: Option<IpAddr>,
+    /// Hash of the client's User-Agent when AxumSessionConfig::with_user_agent_binding is set.
+    pub(crate) user_agent: Option<String>,
+    pub(crate) marker: PhantomData<fn() -> M>,
+}
+
+impl<T, M> Clone for AxumSession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            id: self.id.clone(),
+            access: self.access.clone(),
+            client_ip: self.client_ip,
+            user_agent: self.user_agent.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, M> Debug for AxumSession<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AxumSession")
+            .field("store", &self.store)
+            .field("id", &self.id)
+            .field("access", &self.access)
+            .field("client_ip", &self.client_ip)
+            .field("user_agent", &self.user_agent)
+            .field("marker", &std::any::type_name::<M>())
+            .finish()
+    }
 }
 
-/// Adds FromRequest<B> for AxumSession
+/// Adds FromRequestParts<S> for AxumSession
 ///
 /// Returns the AxumSession from Axums request extensions.
 #[async_trait]
-impl<B, T> FromRequest<B> for AxumSession<T>
+impl<S, T, M> FromRequestParts<S> for AxumSession<T, M>
 where
-    B: Send,
+    S: Send + Sync,
     T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+    M: Send + Sync + 'static,
 {
-    type Rejection = (http::StatusCode, &'static str);
+    type Rejection = SessionRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let session = parts
+            .extensions
+            .get::<AxumSession<T, M>>()
+            .cloned()
+            .ok_or_else(|| SessionRejection::MissingLayer {
+                pool: std::any::type_name::<T>(),
+                marker: std::any::type_name::<M>(),
+            })?;
 
-    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
-        req.extensions().get::<AxumSession<T>>().cloned().ok_or((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Can't extract AxumSession. Is `AxumSessionLayer` enabled?",
-        ))
+        session.access.set_writable();
+        Ok(session)
     }
 }
 
-impl<S> AxumSession<S>
+impl<S, M> AxumSession<S, M>
 where
     S: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
 {
-    pub(crate) fn new(store: &AxumSessionStore<S>, cookies: &CookieJar) -> AxumSession<S> {
-        let value = cookies
-            .get_cookie(&store.config.cookie_name, &store.config.key)
-            .and_then(|c| Uuid::parse_str(c.value()).ok());
-
-        let uuid = match value {
-            Some(v) => v,
-            None => loop {
-                let token = Uuid::new_v4();
-
-                if !store.inner.contains_key(&token.to_string()) {
-                    break token;
-                }
-            },
-        };
+    pub(crate) fn new(
+        store: &AxumSessionStore<S>,
+        value: Option<String>,
+        client_ip: Option<IpAddr>,
+        user_agent: Option<String>,
+    ) -> AxumSession<S, M> {
+        let id = value
+            .filter(|id| store.config.id_generator.validate(id))
+            .unwrap_or_else(|| store.generate_id());
 
         AxumSession {
-            id: AxumSessionID(uuid),
+            id: AxumSessionID::new(id),
+            access: Arc::new(AxumSessionAccess::default()),
+            client_ip,
+            user_agent,
             store: store.clone(),
+            marker: PhantomData,
         }
     }
     /// Runs a Closure upon the Current Sessions stored data to get or set session data.
@@ -73,155 +144,1239 @@ where
     /// Provides an Option<T> that returns the requested data from the Sessions store.
     ///
     /// # Examples
-    /// ```rust no_run
+    /// ```rust ignore
     /// session.tap(|sess| {
     ///   let string = sess.data.get(key)?;
     ///   serde_json::from_str(string).ok()
     /// }).await;
     /// ```
     ///
-    pub(crate) fn tap<T: DeserializeOwned>(
-        &self,
-        func: impl FnOnce(&mut AxumSessionData) -> Option<T>,
-    ) -> Option<T> {
-        if let Some(mut instance) = self.store.inner.get_mut(&self.id.0.to_string()) {
+    pub(crate) fn tap<T>(&self, func: impl FnOnce(&mut AxumSessionData) -> Option<T>) -> Option<T> {
+        if let Some(mut instance) = self.store.inner.get_mut(&self.id.inner()) {
             func(&mut instance)
         } else {
-            tracing::warn!("Session data unexpectedly missing");
+            // Lazy Sessions that were not created yet have nothing to read.
+            if !self.access.is_pending() {
+                tracing::warn!(session = %self.short_id(), "Session data unexpectedly missing");
+            }
+
             None
         }
     }
 
-    /// Sets the Current Session to be Destroyed on the next run.
+    /// Returns a handle to the Current Session for use after the response was returned.
+    ///
+    /// Call this while handling the request, like before a WebSocket upgrade, and move the
+    /// handle into the socket or task. See [`DetachedSession`] for how its changes are written.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// async fn handler(ws: WebSocketUpgrade, session: AxumSession<AxumPgPool>) -> Response {
+    ///     let session = session.detach();
+    ///     ws.on_upgrade(move |socket| handle_socket(socket, session))
+    /// }
+    /// ```
+    ///
+    pub fn detach(&self) -> DetachedSession<S, M> {
+        DetachedSession::new(self.clone())
+    }
+
+    /// Returns the Current Session's ID.
+    ///
+    /// The ID is what authenticates the Session so avoid writing it into logs.
+    /// Use short_id() for log correlation instead.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let id = session.id();
+    /// ```
+    ///
+    pub fn id(&self) -> String {
+        self.id.inner()
+    }
+
+    /// Returns a hash of the Current Session's ID that is safe to write into logs.
+    ///
+    /// The same Session always returns the same short ID but it can not be used to get the Session.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// tracing::info!(session = %session.short_id(), "user logged in");
+    /// ```
+    ///
+    pub fn short_id(&self) -> String {
+        short_id(&self.id.inner())
+    }
+
+    /// Runs func against the Session's data returning SessionError::MissingSession if it is missing.
+    pub(crate) fn try_tap<T>(
+        &self,
+        func: impl FnOnce(&mut AxumSessionData) -> T,
+    ) -> Result<T, SessionError> {
+        let mut instance = self
+            .store
+            .inner
+            .get_mut(&self.id.inner())
+            .ok_or(SessionError::MissingSession)?;
+
+        Ok(func(&mut instance))
+    }
+
+    /// Creates the lazy Session before the first value is set.
+    ///
+    /// Does nothing unless AxumSessionConfig::with_lazy_sessions is set and the Session was not created yet.
+    async fn create_pending(&self) {
+        if !self.access.take_pending() {
+            return;
+        }
+
+        let id = self.id.inner();
+        let mut sess = AxumSessionData::new(
+            id.clone(),
+            self.access.pending_storable(),
+            &self.store.config,
+        );
+        // Used by this request until the layer drops its guard.
+        sess.requests = 1;
+        sess.ip = self.client_ip;
+        sess.user_agent = self.user_agent.clone();
+
+        self.store.inner.insert(id.clone(), sess);
+        self.store.evict_memory();
+        self.store.on_create(&id).await;
+    }
+
+    /// Reads a flag from the Session's data returning false without a warning if it is missing.
+    fn flag(&self, func: impl FnOnce(&AxumSessionData) -> bool) -> bool {
+        self.store
+            .inner
+            .get(&self.id.inner())
+            .is_some_and(|sess| func(&sess))
+    }
+
+    /// Returns true if the Current Session was accepted as storable.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let show_banner = !session.is_accepted().await;
+    /// ```
+    ///
+    pub async fn is_accepted(&self) -> bool {
+        if self.access.is_pending() {
+            return self.access.pending_storable();
+        }
+
+        self.flag(|sess| sess.storable)
+    }
+
+    /// Returns true if the Current Session was set to a long term expiration.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let remembered = session.is_longterm().await;
+    /// ```
+    ///
+    pub async fn is_longterm(&self) -> bool {
+        self.flag(|sess| sess.longterm)
+    }
+
+    /// Returns true if the Current Session is set to be Destroyed.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let logged_out = session.is_destroyed().await;
+    /// ```
+    ///
+    pub async fn is_destroyed(&self) -> bool {
+        self.flag(|sess| sess.destroy)
+    }
+
+    /// Sets the Current Session to be Destroyed once the response is returned.
+    ///
+    /// The Session's data is cleared right away. Once the response is returned the Session is
+    /// removed from memory and the database and the session cookie is removed from the browser
+    /// so the next request will start a new Session.
+    ///
+    /// If data or flash values are set after destroy() they are kept within a new Session
+    /// and the new Session's cookie is sent instead.
     ///
     /// # Examples
-    /// ```rust no_run
+    /// ```rust ignore
     /// session.destroy().await;
+    /// session.set_flash("notice", "You have been logged out").await;
     /// ```
     ///
     pub async fn destroy(&self) {
-        self.tap(|sess| {
+        if let Err(err) = self.try_destroy().await {
+            tracing::warn!("Failed to destroy session: {}", err);
+        }
+    }
+
+    /// Sets the Current Session to be Destroyed returning an error if the Session is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.try_destroy().await?;
+    /// ```
+    ///
+    pub async fn try_destroy(&self) -> Result<(), SessionError> {
+        if self.access.is_pending() {
+            return Ok(());
+        }
+
+        // Keeps the data for the event handler unless it was already destroyed.
+        let data = self.try_tap(|sess| {
+            let data = (!sess.destroy).then(|| std::mem::take(&mut sess.data));
+            sess.reset();
             sess.destroy = true;
-            Some(1)
-        });
+            data
+        })?;
+
+        if let Some(data) = data {
+            self.store.on_destroy(&self.id.inner(), &data).await;
+        }
+
+        Ok(())
     }
 
-    /// Sets the Current Session to a long term expiration. Useful for Remember Me setups.
+    /// Regenerates the Current Session's ID while keeping its data.
+    ///
+    /// Use this after logging in to protect against session fixation. The old ID is removed
+    /// from the database once the response is returned and a new cookie is sent with the new ID.
+    /// This handle and any clones of it keep working using the new ID.
+    /// The user id set by set_store_user_id is cleared.
     ///
     /// # Examples
-    /// ```rust no_run
-    /// session.set_longterm(true).await;
+    /// ```rust ignore
+    /// session.renew().await;
     /// ```
     ///
-    pub async fn set_longterm(&self, longterm: bool) {
-        self.tap(|sess| {
-            sess.longterm = longterm;
+    pub async fn renew(&self) {
+        self.change_id(true);
+    }
+
+    /// Moves the Current Session's data to a new ID like renew() does.
+    ///
+    /// The user id and CSRF token are only cleared when renew is set.
+    pub(crate) fn change_id(&self, renew: bool) {
+        let old_id = self.id.inner();
+        let new_id = self.store.generate_id();
+
+        if let Some((_, mut sess)) = self.store.inner.remove(&old_id) {
+            sess.id = new_id.clone();
             sess.update = true;
-            Some(1)
+
+            if renew {
+                sess.user_id = None;
+                sess.csrf_token = None;
+            }
+
+            self.store.inner.insert(new_id.clone(), sess);
+        } else if !self.access.is_pending() {
+            tracing::warn!(session = %self.short_id(), "Session data unexpectedly missing");
+        }
+
+        self.id.set(new_id);
+    }
+
+    /// Recomputes the Current Session's expiration from now without changing its data.
+    ///
+    /// Use this within long polling or Server Sent Events requests so active users keep their
+    /// Session. The Session is only written to the database once the expiration moved by more
+    /// than AxumSessionConfig::with_touch_granularity, so it is cheap to call often.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.touch().await;
+    /// ```
+    ///
+    pub async fn touch(&self) {
+        let config = &self.store.config;
+        let storable_mode = config.session_mode.is_storable();
+
+        let sess = self.tap(|sess| {
+            let previous = sess.expires;
+            sess.last_accessed = Utc::now();
+            sess.update_expiry(config);
+
+            if sess.expires - previous < config.touch_granularity {
+                sess.expires = previous;
+                return None;
+            }
+
+            // Lets the layer refresh the cookie's Max-Age if the request has not returned yet.
+            sess.cookie_sent = None;
+
+            if storable_mode && !sess.storable {
+                return None;
+            }
+
+            sess.update = false;
+            Some(sess.clone())
+        });
+
+        if let Some(sess) = sess {
+            if let Err(err) = self.store.store_session(&sess).await {
+                tracing::error!(session = %self.short_id(), "Failed to store session: {}", err);
+            }
+        }
+    }
+
+    /// Replaces the Current Session's data with the copy stored within the database.
+    ///
+    /// Use this within long lived requests when another server could have changed the Session.
+    /// Any changes not yet written are lost. If the stored Session no longer exists, expired or
+    /// was destroyed the Current Session is cleared and continues as a new empty Session.
+    /// This does nothing for stores without a database.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.reload().await?;
+    /// ```
+    ///
+    pub async fn reload(&self) -> Result<(), SessionError> {
+        if !self.store.is_persistent() || self.access.is_pending() {
+            return Ok(());
+        }
+
+        let config = &self.store.config;
+        let loaded = self
+            .store
+            .load_session(self.id.inner())
+            .await?
+            .filter(|sess| sess.validate_policy(config) && !sess.destroy);
+
+        let id = self.id.inner();
+        let mut entry = self.store.inner.entry(id.clone()).or_insert_with(|| {
+            AxumSessionData::new(id, !config.session_mode.is_storable(), config)
         });
+
+        match loaded {
+            Some(mut sess) => {
+                // Values belonging to this request and the memory lifespan are kept.
+                sess.flash_current = std::mem::take(&mut entry.flash_current);
+                sess.autoremove = entry.autoremove;
+                sess.requests = entry.requests;
+                sess.update = false;
+                *entry = sess;
+            }
+            None => {
+                entry.reset();
+                entry.update = true;
+            }
+        }
+
+        Ok(())
     }
 
-    /// Sets the Current Session to be storable.
+    /// Writes the Current Session to the database right away instead of once the response is returned.
     ///
-    /// This will allow the Session to save its data for the lifetime if set to true.
-    /// If this is set to false it will unload the stored session.
+    /// The layer skips its own write if nothing changed afterwards. This does nothing for
+    /// stores without a database or Sessions that have not been accepted as storable.
     ///
     /// # Examples
-    /// ```rust no_run
-    /// session.set_store(true).await;
+    /// ```rust ignore
+    /// session.set("order", order).await;
+    /// session.force_save().await?;
     /// ```
     ///
-    pub async fn set_store(&self, storable: bool) {
-        self.tap(|sess| {
-            sess.storable = storable;
+    pub async fn force_save(&self) -> Result<(), SessionError> {
+        if !self.store.is_persistent() || self.access.is_pending() {
+            return Ok(());
+        }
+
+        let config = &self.store.config;
+        let storable_mode = config.session_mode.is_storable();
+
+        let sess = self.try_tap(|sess| {
+            if storable_mode && !sess.storable {
+                return None;
+            }
+
+            sess.update_expiry(config);
+            sess.update = false;
+            Some(sess.clone())
+        })?;
+
+        if let Some(sess) = sess {
+            if let Err(err) = self.store.store_session(&sess).await {
+                // Mark it again so the layer retries once the response is returned.
+                self.tap(|sess| {
+                    sess.update = true;
+                    Some(1)
+                });
+
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the application's user id the Current Session belongs to.
+    ///
+    /// This is stored in its own database column so the Session Store can find every
+    /// Session of a user. destroy and renew clear it, so set it again after renewing on login.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.renew().await;
+    /// session.set_store_user_id(user.id.to_string()).await;
+    /// ```
+    ///
+    pub async fn set_store_user_id(&self, id: impl Into<String>) {
+        if let Err(err) = self.try_set_store_user_id(id).await {
+            tracing::error!(session = %self.short_id(), "Failed to set session user id: {}", err);
+        }
+    }
+
+    /// Sets the application's user id the Current Session belongs to returning an error if the
+    /// user already has the most Sessions allowed by AxumSessionConfig::with_max_sessions_per_user.
+    ///
+    /// # Errors
+    /// - ['SessionError::TooManySessions'] is returned when using `OverflowPolicy::Reject` and the user has no room left.
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    /// - ['SessionError::GenericNotSupportedError'] is returned when a max is set and the database can not find Sessions by user id.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// if let Err(SessionError::TooManySessions(max)) = session.try_set_store_user_id(user.id.to_string()).await {
+    ///     return format!("Log out of another device first, only {} are allowed.", max);
+    /// }
+    /// ```
+    ///
+    pub async fn try_set_store_user_id(&self, id: impl Into<String>) -> Result<(), SessionError> {
+        let id = id.into();
+
+        if self.flag(|sess| sess.user_id.as_ref() == Some(&id)) {
+            return Ok(());
+        }
+
+        self.store.make_room_for_user(&id, &self.id.inner()).await?;
+
+        self.create_pending().await;
+        self.try_tap(|sess| {
+            sess.user_id = Some(id);
+            // Logging in replaces the CSRF token the same as renew() does.
+            sess.csrf_token = None;
             sess.update = true;
-            Some(1)
-        });
+        })
     }
 
-    /// Gets data from the Session's HashMap
+    /// Returns the Current Session's CSRF token, creating and storing a random one if it has none.
     ///
-    /// Provides an Option<T> that returns the requested data from the Sessions store.
-    /// Returns None if Key does not exist or if serdes_json failed to deserialize.
+    /// Send it back within forms or the `X-CSRF-Token` header and check it using verify_csrf,
+    /// or let the CsrfLayer check it. renew() and set_store_user_id replace the token.
     ///
     /// # Examples
-    /// ```rust no_run
-    /// let id = session.get("user-id").await.unwrap_or(0);
+    /// ```rust ignore
+    /// let token = session.csrf_token().await;
+    /// let form = format!(r#"<input type="hidden" name="csrf_token" value="{}">"#, token);
     /// ```
     ///
-    ///Used to get data stored within SessionDatas hashmap from a key value.
-    pub async fn get<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+    pub async fn csrf_token(&self) -> String {
+        self.create_pending().await;
         self.tap(|sess| {
-            let string = sess.data.get(key)?;
-            serde_json::from_str(string).ok()
+            let token = sess.csrf_token.get_or_insert_with(|| {
+                sess.update = true;
+                TokenGenerator.generate()
+            });
+
+            Some(token.clone())
         })
+        .unwrap_or_default()
     }
 
-    /// Sets data to the Current Session's HashMap.
+    /// Checks if token is the Current Session's CSRF token returned by csrf_token.
+    ///
+    /// The comparison takes the same time no matter how much of the token matches.
+    /// Returns false if the Session has no CSRF token.
     ///
     /// # Examples
-    /// ```rust no_run
-    /// session.set("user-id", 1).await;
+    /// ```rust ignore
+    /// if !session.verify_csrf(&form.csrf_token).await {
+    ///     return StatusCode::FORBIDDEN;
+    /// }
     /// ```
     ///
-    pub async fn set(&self, key: &str, value: impl Serialize) {
-        let value = serde_json::to_string(&value).unwrap_or_else(|_| "".to_string());
+    pub async fn verify_csrf(&self, token: &str) -> bool {
+        self.flag(|sess| {
+            sess.csrf_token
+                .as_deref()
+                .is_some_and(|expected| constant_time_eq(expected.as_bytes(), token.as_bytes()))
+        })
+    }
+
+    /// Gets the application's user id set by set_store_user_id.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let user_id = session.store_user_id().await;
+    /// ```
+    ///
+    pub async fn store_user_id(&self) -> Option<String> {
+        self.tap(|sess| sess.user_id.clone())
+    }
 
+    /// Sets the Current Session to a long term expiration. Useful for Remember Me setups.
+    ///
+    /// This uses the configured max lifespan. Use set_remember to choose the lifespan.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.set_longterm(true).await;
+    /// ```
+    ///
+    pub async fn set_longterm(&self, longterm: bool) {
+        self.create_pending().await;
         self.tap(|sess| {
-            if sess.data.get(key) != Some(&value) {
-                sess.data.insert(key.to_string(), value);
+            if sess.longterm != longterm || sess.remember_lifespan.is_some() {
+                sess.longterm = longterm;
+                sess.remember_lifespan = None;
                 sess.update = true;
             }
+
             Some(1)
         });
     }
 
-    /// Removes a Key from the Current Session's HashMap.
+    /// Sets the Current Session to be remembered for lifespan instead of the configured max lifespan.
+    ///
+    /// Both the cookie's Max-Age and the database's expiration use lifespan.
+    /// Setting None reverts to the standard lifespan once the response is returned.
+    /// Lifespans under a second are raised to one second and ones over 100 years are lowered to 100 years.
     ///
     /// # Examples
-    /// ```rust no_run
-    /// session.remove("user-id").await;
+    /// ```rust ignore
+    /// use chrono::Duration;
+    ///
+    /// session.set_remember(Some(Duration::days(7))).await;
     /// ```
     ///
-    pub async fn remove(&self, key: &str) {
+    pub async fn set_remember(&self, lifespan: Option<Duration>) {
+        self.create_pending().await;
         self.tap(|sess| {
-            sess.update = true;
-            sess.data.remove(key)
+            let remember_lifespan = lifespan.map(clamp_lifespan);
+
+            if sess.longterm != lifespan.is_some() || sess.remember_lifespan != remember_lifespan {
+                sess.longterm = lifespan.is_some();
+                sess.remember_lifespan = remember_lifespan;
+                sess.update = true;
+            }
+
+            Some(1)
         });
     }
 
-    /// Clears all data from the Current Session's HashMap.
+    /// Sets the Current Session's lifespan, replacing the configured lifespan and set_longterm().
+    ///
+    /// This is used for both the cookie's Max-Age and the database's expiration.
+    /// With ExpiryPolicy::Hybrid this replaces the idle time while the max time is still used.
+    /// Lifespans under a second are raised to one second and ones over 100 years are lowered to 100 years.
     ///
     /// # Examples
-    /// ```rust no_run
-    /// session.clear_all().await;
+    /// ```rust ignore
+    /// use chrono::Duration;
+    ///
+    /// session.set_expiry(Duration::hours(12)).await;
     /// ```
     ///
-    pub async fn clear_all(&self) {
-        if let Some(mut instance) = self.store.inner.get_mut(&self.id.0.to_string()) {
-            instance.data.clear();
-        }
+    pub async fn set_expiry(&self, lifespan: Duration) {
+        self.create_pending().await;
+        let lifespan = clamp_lifespan(lifespan);
 
-        if self.store.is_persistent() {
-            self.store.clear_store().await.unwrap();
-        }
+        self.tap(|sess| {
+            if sess.lifespan_override != Some(lifespan) {
+                sess.lifespan_override = Some(lifespan);
+                sess.update = true;
+            }
+
+            Some(1)
+        });
     }
 
-    /// Returns a i64 count of how many Sessions exist.
+    /// Clears the lifespan set by set_expiry() so the configured lifespan is used again.
     ///
-    /// If the Session is persistant it will return all sessions within the database.
-    /// If the Session is not persistant it will return a count within AxumSessionStore.
+    /// # Examples
+    /// ```rust ignore
+    /// session.clear_expiry().await;
+    /// ```
+    ///
+    pub async fn clear_expiry(&self) {
+        self.tap(|sess| {
+            if sess.lifespan_override.take().is_some() {
+                sess.update = true;
+            }
+
+            Some(1)
+        });
+    }
+
+    /// Returns the time the Current Session was first created.
     ///
     /// # Examples
-    /// ```rust no_run
-    /// let count = session.count().await;
+    /// ```rust ignore
+    /// let since = session.created_at().await;
     /// ```
     ///
-    pub async fn count(&self) -> i64 {
-        if self.store.is_persistent() {
-            self.store.count().await.unwrap_or(0i64)
-        } else {
-            self.store.inner.len() as i64
-        }
+    pub async fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.tap(|sess| Some(sess.created))
     }
+
+    /// Returns the time the Current Session was last used.
+    ///
+    /// This is only updated once it moved by more than a tenth of the idle timeout, or the
+    /// touch granularity when no idle timeout is set, to avoid a database write each request.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let last_seen = session.last_accessed().await;
+    /// ```
+    ///
+    pub async fn last_accessed(&self) -> Option<DateTime<Utc>> {
+        self.tap(|sess| Some(sess.last_accessed))
+    }
+
+    /// Returns the time the Current Session expires.
+    ///
+    /// This is the expiration the layer last computed, including any longterm adjustments.
+    /// Returns None if the Session has not been accepted as storable yet.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let expires = session.expires().await;
+    /// ```
+    ///
+    pub async fn expires(&self) -> Option<DateTime<Utc>> {
+        let storable_mode = self.store.config.session_mode.is_storable();
+
+        self.tap(|sess| {
+            if storable_mode && !sess.storable {
+                None
+            } else {
+                Some(sess.expires)
+            }
+        })
+    }
+
+    /// Returns how long until the Current Session expires.
+    ///
+    /// Returns None if the Session has not been accepted as storable yet.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let minutes = session.expires_in().await.map(|left| left.num_minutes());
+    /// ```
+    ///
+    pub async fn expires_in(&self) -> Option<Duration> {
+        self.expires()
+            .await
+            .map(|expires| std::cmp::max(expires - Utc::now(), Duration::zero()))
+    }
+
+    /// Sets the Current Session to be storable.
+    ///
+    /// This will allow the Session to save its data for the lifetime if set to true.
+    /// If this is set to false it will unload the stored session. Setting it to true also
+    /// accepts cookies for requests declining them using AxumSessionConfig::with_privacy_signals.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.set_store(true).await;
+    /// ```
+    ///
+    pub async fn set_store(&self, storable: bool) {
+        self.create_pending().await;
+        self.tap(|sess| {
+            if sess.storable != storable || sess.consented != storable {
+                sess.storable = storable;
+                sess.consented = storable;
+                sess.update = true;
+            }
+
+            Some(1)
+        });
+    }
+
+    /// Gets data from the Session's HashMap
+    ///
+    /// Provides an Option<T> that returns the requested data from the Sessions store.
+    /// Returns None if Key does not exist or if serdes_json failed to deserialize.
+    /// Use try_get to tell these apart, or contains_key to only check if the Key exists.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let id = session.get("user-id").await.unwrap_or(0);
+    /// ```
+    ///
+    ///Used to get data stored within SessionDatas hashmap from a key value.
+    pub async fn get<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.tap(|sess| {
+            let string = sess.data.get(key)?;
+            serde_json::from_str(string).ok()
+        })
+    }
+
+    /// Returns true if the Key exists within the Current Session without deserializing it.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let logged_in = session.contains_key("user-id").await;
+    /// ```
+    ///
+    pub async fn contains_key(&self, key: &str) -> bool {
+        self.tap(|sess| Some(sess.data.contains_key(key) || sess.bytes.contains_key(key)))
+            .unwrap_or(false)
+    }
+
+    /// Gets data from the Session's HashMap returning an error if serde_json failed to deserialize.
+    ///
+    /// Returns Ok(None) if the Key does not exist or SessionError::MissingSession if the Session is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let id: Option<i64> = session.try_get("user-id").await?;
+    /// ```
+    ///
+    pub async fn try_get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SessionError> {
+        if self.access.is_pending() {
+            return Ok(None);
+        }
+
+        let value = self.try_tap(|sess| {
+            sess.data
+                .get(key)
+                .map(|string| serde_json::from_str(string))
+                .transpose()
+        })??;
+
+        Ok(value)
+    }
+
+    /// Gets data from the Session's HashMap or inserts the value returned by default.
+    ///
+    /// The check and insert happen within a single lock so requests sharing the Session can not
+    /// race each other. If the existing value fails to deserialize it is replaced by the default.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let cart_id: u64 = session.get_or_insert_with("cart-id", || 1).await;
+    /// ```
+    ///
+    pub async fn get_or_insert_with<T: Serialize + DeserializeOwned>(
+        &self,
+        key: &str,
+        default: impl FnOnce() -> T,
+    ) -> T {
+        self.create_pending().await;
+        let mut default = Some(default);
+
+        let value = self.tap(|sess| {
+            if let Some(value) = sess
+                .data
+                .get(key)
+                .and_then(|string| serde_json::from_str(string).ok())
+            {
+                return Some(value);
+            }
+
+            let value = default.take()?();
+
+            if let Ok(string) = serde_json::to_string(&value) {
+                sess.insert_value(key, string);
+            }
+
+            Some(value)
+        });
+
+        match (value, default) {
+            (Some(value), _) => value,
+            (None, Some(default)) => default(),
+            (None, None) => unreachable!("default is only taken when a value is returned"),
+        }
+    }
+
+    /// Gets data from the Session's HashMap or returns default.
+    ///
+    /// Unlike get_or_insert_with this does not insert the default into the Session.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let id: u64 = session.get_or("user-id", 0).await;
+    /// ```
+    ///
+    pub async fn get_or<T: DeserializeOwned>(&self, key: &str, default: T) -> T {
+        self.get(key).await.unwrap_or(default)
+    }
+
+    /// Gets data from the Session's HashMap and removes it within a single lock.
+    ///
+    /// Useful for one time values like OAuth state or password reset confirmations.
+    /// Returns None if Key does not exist or if serdes_json failed to deserialize.
+    /// The Key is removed even if it failed to deserialize so it can never be read twice.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let state: Option<String> = session.get_remove("oauth-state").await;
+    /// ```
+    ///
+    pub async fn get_remove<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.tap(|sess| {
+            let string = sess.remove_value(key)?;
+            serde_json::from_str(&string).ok()
+        })
+    }
+
+    /// Returns a snapshot of the Keys within the Current Session's HashMap.
+    ///
+    /// Returns an empty Vec if the Session data is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let keys = session.keys().await;
+    /// ```
+    ///
+    pub async fn keys(&self) -> Vec<String> {
+        self.tap(|sess| Some(sess.data.keys().chain(sess.bytes.keys()).cloned().collect()))
+            .unwrap_or_default()
+    }
+
+    /// Returns a snapshot of the Keys and their raw JSON values within the Current Session's HashMap.
+    ///
+    /// Binary values set using set_bytes() are not included.
+    /// Returns an empty Vec if the Session data is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// for (key, value) in session.entries_raw().await {
+    ///     println!("{} = {}", key, value);
+    /// }
+    /// ```
+    ///
+    pub async fn entries_raw(&self) -> Vec<(String, String)> {
+        self.tap(|sess| {
+            Some(
+                sess.data
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            )
+        })
+        .unwrap_or_default()
+    }
+
+    /// Returns a snapshot of all the data within the Current Session's HashMap.
+    ///
+    /// Values which fail to parse are skipped with a warning. Binary values set using
+    /// set_bytes() are not included. Returns an empty HashMap if the Session data is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let snapshot = session.get_all().await;
+    /// ```
+    ///
+    pub async fn get_all(&self) -> HashMap<String, serde_json::Value> {
+        self.get_all_raw()
+            .await
+            .into_iter()
+            .filter_map(|(key, value)| match serde_json::from_str(&value) {
+                Ok(value) => Some((key, value)),
+                Err(err) => {
+                    tracing::warn!("Failed to parse session key {}: {}", key, err);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns a snapshot of all the raw JSON values within the Current Session's HashMap.
+    ///
+    /// Returns an empty HashMap if the Session data is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let snapshot = session.get_all_raw().await;
+    /// ```
+    ///
+    pub async fn get_all_raw(&self) -> HashMap<String, String> {
+        self.tap(|sess| Some(sess.data.clone())).unwrap_or_default()
+    }
+
+    /// Returns how many Keys are stored within the Current Session's HashMap.
+    ///
+    /// Only user data is counted. Returns 0 if the Session data is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let items = session.len().await;
+    /// ```
+    ///
+    pub async fn len(&self) -> usize {
+        self.tap(|sess| Some(sess.data.len() + sess.bytes.len()))
+            .unwrap_or(0)
+    }
+
+    /// Returns true if no Keys are stored within the Current Session's HashMap.
+    ///
+    /// Returns true if the Session data is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// if session.is_empty().await {
+    ///     session.set_store(false).await;
+    /// }
+    /// ```
+    ///
+    pub async fn is_empty(&self) -> bool {
+        self.tap(|sess| Some(sess.data.is_empty() && sess.bytes.is_empty()))
+            .unwrap_or(true)
+    }
+
+    /// Gets a SessionData Type from the Session's HashMap using its Key.
+    ///
+    /// Returns None if the Key does not exist or if serdes_json failed to deserialize.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let state: Option<UserState> = session.get_typed().await;
+    /// ```
+    ///
+    pub async fn get_typed<D: SessionData>(&self) -> Option<D> {
+        self.get(D::key()).await
+    }
+
+    /// Sets a SessionData Type to the Current Session's HashMap using its Key.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.set_typed(UserState { id: 1 }).await;
+    /// ```
+    ///
+    pub async fn set_typed<D: SessionData>(&self, value: D) {
+        self.set(D::key(), value).await;
+    }
+
+    /// Removes a SessionData Type from the Current Session's HashMap.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.remove_typed::<UserState>().await;
+    /// ```
+    ///
+    pub async fn remove_typed<D: SessionData>(&self) {
+        self.remove(D::key()).await;
+    }
+
+    /// Sets a flash value which can be read once by the next request.
+    ///
+    /// Flash values are dropped after the next request even if they are not taken.
+    /// Useful for messages shown after a redirect.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.set_flash("notice", "Your profile was saved").await;
+    /// ```
+    ///
+    pub async fn set_flash(&self, key: &str, value: impl Serialize) {
+        let value = match serde_json::to_string(&value) {
+            Ok(value) => value,
+            Err(err) => {
+                tracing::error!("Failed to set session flash key {}: {}", key, err);
+                return;
+            }
+        };
+
+        self.create_pending().await;
+        self.tap(|sess| {
+            sess.flash.insert(key.to_string(), value);
+            sess.update = true;
+            Some(1)
+        });
+    }
+
+    /// Takes a flash value set by the previous request or by this request.
+    ///
+    /// Returns None if Key does not exist or if serdes_json failed to deserialize.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let notice: Option<String> = session.take_flash("notice").await;
+    /// ```
+    ///
+    pub async fn take_flash<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.tap(|sess| {
+            let string = match sess.flash_current.remove(key) {
+                Some(string) => string,
+                None => {
+                    let string = sess.flash.remove(key)?;
+                    sess.update = true;
+                    string
+                }
+            };
+
+            serde_json::from_str(&string).ok()
+        })
+    }
+
+    /// Sets a binary value to the Current Session without encoding it as JSON.
+    ///
+    /// Any value set using set() with the same Key is replaced.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.set_bytes("challenge", vec![1, 2, 3]).await;
+    /// ```
+    ///
+    pub async fn set_bytes(&self, key: &str, value: Vec<u8>) {
+        self.create_pending().await;
+        self.tap(|sess| {
+            if sess.data.remove(key).is_some() {
+                sess.update = true;
+            }
+
+            if sess.bytes.get(key) != Some(&value) {
+                sess.bytes.insert(key.to_string(), value);
+                sess.update = true;
+            }
+
+            Some(1)
+        });
+    }
+
+    /// Gets a binary value set using set_bytes().
+    ///
+    /// Returns None if the Key does not exist or was set using set().
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let challenge = session.get_bytes("challenge").await;
+    /// ```
+    ///
+    pub async fn get_bytes(&self, key: &str) -> Option<Vec<u8>> {
+        self.tap(|sess| sess.bytes.get(key).cloned())
+    }
+
+    /// Returns a view of the Current Session where every Key is prefixed by namespace.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let cart = session.namespace("cart");
+    /// cart.set("items", vec![1, 2, 3]).await;
+    /// ```
+    ///
+    pub fn namespace(&self, namespace: &str) -> SessionNamespace<S, M> {
+        SessionNamespace::new(self.clone(), namespace)
+    }
+
+    /// Sets data to the Current Session's HashMap.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.set("user-id", 1).await;
+    /// ```
+    ///
+    pub async fn set(&self, key: &str, value: impl Serialize) {
+        if let Err(err) = self.try_set(key, value).await {
+            tracing::error!("Failed to set session key {}: {}", key, err);
+        }
+    }
+
+    /// Sets data to the Current Session's HashMap returning an error if serde_json failed to serialize
+    /// or SessionError::MissingSession if the Session is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.try_set("user-id", 1).await?;
+    /// ```
+    ///
+    pub async fn try_set(&self, key: &str, value: impl Serialize) -> Result<(), SessionError> {
+        let value = serde_json::to_string(&value)?;
+
+        self.create_pending().await;
+        self.try_tap(|sess| sess.insert_value(key, value))
+    }
+
+    /// Replaces all the data within the Current Session's HashMap within a single lock.
+    ///
+    /// The Session's flags like accepted and longterm are kept. Every value is serialized first,
+    /// so if any value fails to serialize the error is returned and the data is not changed.
+    /// Binary values set using set_bytes() are also removed.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// use std::collections::HashMap;
+    ///
+    /// let profile = HashMap::from([("user-id".to_owned(), 1), ("role".to_owned(), 2)]);
+    /// session.replace_data(profile).await?;
+    /// ```
+    ///
+    pub async fn replace_data<V: Serialize>(
+        &self,
+        data: impl IntoIterator<Item = (String, V)>,
+    ) -> Result<(), SessionError> {
+        let data = data
+            .into_iter()
+            .map(|(key, value)| Ok((key, serde_json::to_string(&value)?)))
+            .collect::<Result<HashMap<String, String>, SessionError>>()?;
+
+        self.create_pending().await;
+        self.try_tap(|sess| {
+            if sess.data != data || !sess.bytes.is_empty() {
+                sess.data = data;
+                sess.bytes.clear();
+                sess.update = true;
+            }
+        })
+    }
+
+    /// Updates a Key within the Current Session's HashMap using func and returns the new value.
+    ///
+    /// The value is read, passed to func and written back within a single lock so parallel
+    /// requests sharing the Session can not lose each other's changes. func is given None if the
+    /// Key does not exist or failed to deserialize. Returning None removes the Key.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let count = session.update("count", |count: Option<i64>| Some(count.unwrap_or(0) + 1)).await;
+    /// ```
+    ///
+    pub async fn update<T: Serialize + DeserializeOwned>(
+        &self,
+        key: &str,
+        func: impl FnOnce(Option<T>) -> Option<T>,
+    ) -> Option<T> {
+        self.create_pending().await;
+        self.tap(|sess| {
+            let current = sess
+                .data
+                .get(key)
+                .and_then(|string| serde_json::from_str(string).ok());
+
+            match func(current) {
+                Some(value) => {
+                    if let Ok(string) = serde_json::to_string(&value) {
+                        sess.insert_value(key, string);
+                    }
+
+                    Some(value)
+                }
+                None => {
+                    sess.remove_value(key);
+                    None
+                }
+            }
+        })
+    }
+
+    /// Removes a Key from the Current Session's HashMap.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.remove("user-id").await;
+    /// ```
+    ///
+    pub async fn remove(&self, key: &str) {
+        if let Err(err) = self.try_remove(key).await {
+            tracing::warn!("Failed to remove session key {}: {}", key, err);
+        }
+    }
+
+    /// Removes a Key from the Current Session's HashMap returning an error if the Session is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.try_remove("user-id").await?;
+    /// ```
+    ///
+    pub async fn try_remove(&self, key: &str) -> Result<(), SessionError> {
+        if self.access.is_pending() {
+            return Ok(());
+        }
+
+        self.try_tap(|sess| {
+            sess.remove_value(key);
+        })
+    }
+
+    /// Clears all data from the Current Session's HashMap.
+    ///
+    /// Only the Current Session is cleared and its row is updated once the request is returned.
+    /// Use AxumSessionStore::clear_store to remove every Session.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.clear_all().await;
+    /// ```
+    ///
+    pub async fn clear_all(&self) {
+        if let Err(err) = self.try_clear_all().await {
+            tracing::warn!("Failed to clear session: {}", err);
+        }
+    }
+
+    /// Clears all data from the Current Session's HashMap returning an error if the Session is missing.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// session.try_clear_all().await?;
+    /// ```
+    ///
+    pub async fn try_clear_all(&self) -> Result<(), SessionError> {
+        if self.access.is_pending() {
+            return Ok(());
+        }
+
+        self.try_tap(|sess| {
+            if !sess.data.is_empty() || !sess.bytes.is_empty() {
+                sess.data.clear();
+                sess.bytes.clear();
+                sess.update = true;
+            }
+        })
+    }
+
+    /// Returns a i64 count of how many unexpired Sessions exist.
+    ///
+    /// If the Session is persistant it will return all unexpired sessions within the database.
+    /// If the Session is not persistant it will return a count within AxumSessionStore.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let count = session.count().await;
+    /// ```
+    ///
+    pub async fn count(&self) -> i64 {
+        match self.try_count().await {
+            Ok(count) => count,
+            Err(err) => {
+                tracing::error!("Failed to count sessions: {}", err);
+                0
+            }
+        }
+    }
+
+    /// Returns a i64 count of how many Sessions exist returning any database error.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let count = session.try_count().await?;
+    /// ```
+    ///
+    pub async fn try_count(&self) -> Result<i64, SessionError> {
+        if self.store.is_persistent() {
+            self.store.count().await
+        } else {
+            Ok(self
+                .store
+                .inner
+                .iter()
+                .filter(|sess| sess.validate() && !sess.destroy)
+                .count() as i64)
+        }
+    }
+}
+
+/// Compares both without returning early so the time taken does not tell how much matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
 }
@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Tracks which Session extractors were used during a request.
+#[derive(Debug, Default)]
+pub(crate) struct AxumSessionAccess {
+    read_only: AtomicBool,
+    writable: AtomicBool,
+    /// Set while a lazy Session has not been created yet.
+    pending: AtomicBool,
+    /// If the lazy Session is created as accepted.
+    pending_storable: AtomicBool,
+    /// Set when the Session only exists for the request since the client was rate limited.
+    throwaway: AtomicBool,
+    /// Set when the throwaway Session is kept if session.set_store(true) is called.
+    declined: AtomicBool,
+}
+
+impl AxumSessionAccess {
+    pub(crate) fn set_read_only(&self) {
+        self.read_only.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_writable(&self) {
+        self.writable.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if only ReadOnlySession was extracted during the request.
+    pub(crate) fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed) && !self.writable.load(Ordering::Relaxed)
+    }
+
+    /// Marks the Session as lazy so it is only created once something is set.
+    pub(crate) fn set_pending(&self, storable: bool) {
+        self.pending_storable.store(storable, Ordering::Relaxed);
+        self.pending.store(true, Ordering::Release);
+    }
+
+    /// Returns true while the lazy Session has not been created.
+    pub(crate) fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Acquire)
+    }
+
+    /// Returns if the lazy Session is accepted.
+    pub(crate) fn pending_storable(&self) -> bool {
+        self.pending_storable.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_throwaway(&self) {
+        self.throwaway.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the Session is dropped once the request is done.
+    pub(crate) fn is_throwaway(&self) -> bool {
+        self.throwaway.load(Ordering::Relaxed)
+    }
+
+    /// Marks the Session as a throwaway for a request declining cookies.
+    pub(crate) fn set_declined(&self) {
+        self.declined.store(true, Ordering::Relaxed);
+        self.set_throwaway();
+    }
+
+    /// Returns true if the request declined cookies using a privacy header.
+    pub(crate) fn is_declined(&self) -> bool {
+        self.declined.load(Ordering::Relaxed)
+    }
+
+    /// Clears the lazy mark returning if the Session still needed to be created.
+    pub(crate) fn take_pending(&self) -> bool {
+        self.pending.swap(false, Ordering::AcqRel)
+    }
+}
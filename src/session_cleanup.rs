@@ -0,0 +1,33 @@
+use std::sync::Arc;
+use tokio::{sync::Notify, task::JoinHandle};
+
+/// Handle to the background cleanup task started by AxumSessionStore::spawn_cleanup.
+///
+/// Dropping the handle leaves the task running. Call shutdown to stop it.
+///
+/// # Examples
+/// ```rust ignore
+/// let cleanup = session_store.spawn_cleanup(Duration::minutes(10));
+///
+/// // During graceful shutdown.
+/// cleanup.shutdown().await;
+/// ```
+///
+#[derive(Debug)]
+pub struct SessionCleanupHandle {
+    pub(crate) shutdown: Arc<Notify>,
+    pub(crate) task: JoinHandle<()>,
+}
+
+impl SessionCleanupHandle {
+    /// Stops the cleanup task and waits for it to finish.
+    ///
+    /// A cleanup that is already running is finished first.
+    pub async fn shutdown(self) {
+        self.shutdown.notify_one();
+
+        if let Err(err) = self.task.await {
+            tracing::error!("Session cleanup task failed: {}", err);
+        }
+    }
+}
@@ -1,30 +1,79 @@
-use crate::AxumSessionConfig;
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use uuid::Uuid;
+use crate::{AxumSessionConfig, ExpiryPolicy};
+use chrono::{DateTime, Duration, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, net::IpAddr};
+
+/// The longest lifespan in seconds a Session can be given, which is 100 years.
+pub(crate) const MAX_LIFESPAN_SECS: i64 = 100 * 365 * 24 * 60 * 60;
+
+/// Keeps a lifespan given to a Session between 1 second and MAX_LIFESPAN_SECS.
+pub(crate) fn clamp_lifespan(lifespan: Duration) -> i64 {
+    lifespan.num_seconds().clamp(1, MAX_LIFESPAN_SECS)
+}
 
 /// The Store and Configured Data for a Session.
 ///
 /// # Examples
-/// ```
+/// ```rust ignore
 /// use axum_database_sessions::{AxumSessionConfig, AxumSessionData};
 /// use uuid::Uuid;
 ///
 /// let config = AxumSessionConfig::default();
 /// let token = Uuid::new_v4();
-/// let session_data = AxumSessionData::new(token, true, &config);
+/// let session_data = AxumSessionData::new(token.to_string(), true, &config);
 /// ```
 ///
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct AxumSessionData {
-    pub(crate) id: Uuid,
+    pub(crate) id: String,
     pub(crate) data: HashMap<String, String>,
+    /// Binary values set by session.set_bytes(). A Key is only ever within data or bytes.
+    #[serde(default, with = "bytes_map")]
+    pub(crate) bytes: HashMap<String, Vec<u8>>,
+    /// Flash values set during the last request which are readable during the next request.
+    #[serde(default)]
+    pub(crate) flash: HashMap<String, String>,
+    /// Flash values readable during the current request. These are dropped once it is returned.
+    #[serde(skip)]
+    pub(crate) flash_current: HashMap<String, String>,
     pub(crate) expires: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub(crate) created: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub(crate) last_accessed: DateTime<Utc>,
+    #[serde(default)]
+    pub(crate) cookie_sent: Option<DateTime<Utc>>,
     pub(crate) autoremove: DateTime<Utc>,
+    /// When the Session was last used from memory. The least recently used are evicted first.
+    #[serde(skip, default = "Utc::now")]
+    pub(crate) memory_used: DateTime<Utc>,
+    /// How many requests are using the Session. These are never evicted from memory.
+    #[serde(skip)]
+    pub(crate) requests: usize,
     pub(crate) destroy: bool,
     pub(crate) longterm: bool,
+    /// Lifespan in seconds set by session.set_expiry() which replaces the configured lifespan.
+    #[serde(default)]
+    pub(crate) lifespan_override: Option<i64>,
+    /// Lifespan in seconds set by session.set_remember() which replaces max_lifespan while longterm.
+    #[serde(default)]
+    pub(crate) remember_lifespan: Option<i64>,
+    /// The application's user id set by session.set_store_user_id(). Stored in its own database column.
+    #[serde(default)]
+    pub(crate) user_id: Option<String>,
+    /// The client's IP the Session was created from when AxumSessionConfig::with_ip_binding is set.
+    #[serde(default)]
+    pub(crate) ip: Option<IpAddr>,
+    /// Hash of the client's User-Agent when AxumSessionConfig::with_user_agent_binding is set.
+    #[serde(default)]
+    pub(crate) user_agent: Option<String>,
+    /// The token returned by session.csrf_token(). Replaced by renew() and logins.
+    #[serde(default)]
+    pub(crate) csrf_token: Option<String>,
     pub(crate) storable: bool,
+    /// Set once session.set_store(true) was called so privacy headers no longer decline the Session.
+    #[serde(default)]
+    pub(crate) consented: bool,
     pub(crate) update: bool,
 }
 
@@ -32,42 +81,325 @@ impl AxumSessionData {
     /// Constructs a new AxumSessionData.
     ///
     /// # Examples
-    /// ```rust
+    /// ```rust ignore
     /// use axum_database_sessions::{AxumSessionConfig, AxumSessionData};
     /// use uuid::Uuid;
     ///
     /// let config = AxumSessionConfig::default();
     /// let token = Uuid::new_v4();
-    /// let session_data = AxumSessionData::new(token, true, &config);
+    /// let session_data = AxumSessionData::new(token.to_string(), true, &config);
     /// ```
     ///
-    pub(crate) fn new(id: Uuid, storable: bool, config: &AxumSessionConfig) -> Self {
-        Self {
+    pub(crate) fn new(id: String, storable: bool, config: &AxumSessionConfig) -> Self {
+        let mut session = Self {
             id,
             data: HashMap::new(),
-            expires: Utc::now() + config.lifespan,
+            bytes: HashMap::new(),
+            flash: HashMap::new(),
+            flash_current: HashMap::new(),
+            expires: Utc::now(),
+            created: Utc::now(),
+            last_accessed: Utc::now(),
+            cookie_sent: None,
             destroy: false,
             autoremove: Utc::now() + config.memory_lifespan,
+            memory_used: Utc::now(),
+            requests: 0,
             longterm: false,
+            lifespan_override: None,
+            remember_lifespan: None,
+            user_id: None,
+            ip: None,
+            user_agent: None,
+            csrf_token: None,
+            consented: false,
             storable,
             update: true,
-        }
+        };
+
+        session.update_expiry(config);
+        session
     }
 
     /// Validates if the Session is to expire.
     ///
     /// # Examples
-    /// ```rust
+    /// ```rust ignore
     /// use axum_database_sessions::{AxumSessionConfig, AxumSessionData};
     /// use uuid::Uuid;
     ///
     /// let config = AxumSessionConfig::default();
     /// let token = Uuid::new_v4();
-    /// let session_data = AxumSessionData::new(token, true, &config);
+    /// let session_data = AxumSessionData::new(token.to_string(), true, &config);
     /// let expired = session_data.validate();
     /// ```
     ///
     pub(crate) fn validate(&self) -> bool {
         self.expires >= Utc::now()
     }
+
+    /// Returns the lifespan set by session.set_expiry() if one exists.
+    pub(crate) fn lifespan_override(&self) -> Option<Duration> {
+        self.lifespan_override
+            .map(|secs| Duration::seconds(secs.clamp(1, MAX_LIFESPAN_SECS)))
+    }
+
+    /// Returns the lifespan set by session.set_remember() if the Session is long term.
+    pub(crate) fn remember_lifespan(&self) -> Option<Duration> {
+        self.remember_lifespan
+            .filter(|_| self.longterm)
+            .map(|secs| Duration::seconds(secs.clamp(1, MAX_LIFESPAN_SECS)))
+    }
+
+    /// Returns the lifespan chosen for this Session which is also used for the cookie's Max-Age.
+    pub(crate) fn cookie_lifespan(&self) -> Option<Duration> {
+        self.lifespan_override()
+            .or_else(|| self.remember_lifespan())
+    }
+
+    /// Returns the lifespan of the Session using the override, remember, longterm or configured lifespan.
+    pub(crate) fn lifespan(&self, config: &AxumSessionConfig) -> Duration {
+        if let Some(lifespan) = self.cookie_lifespan() {
+            lifespan
+        } else if self.longterm {
+            config.max_lifespan
+        } else {
+            config.lifespan
+        }
+    }
+
+    /// Clears the Session's data and state so it can be reused as a new Session.
+    pub(crate) fn reset(&mut self) {
+        self.destroy = false;
+        self.longterm = false;
+        self.lifespan_override = None;
+        self.remember_lifespan = None;
+        self.user_id = None;
+        self.csrf_token = None;
+        self.created = Utc::now();
+        self.last_accessed = Utc::now();
+        self.data.clear();
+        self.bytes.clear();
+        self.flash.clear();
+    }
+
+    /// Returns the time the Session expires no matter how often it is used if the policy has one.
+    pub(crate) fn absolute_expiry(&self, config: &AxumSessionConfig) -> Option<DateTime<Utc>> {
+        match config.expiry_policy {
+            ExpiryPolicy::Sliding => None,
+            ExpiryPolicy::Absolute => Some(self.created + self.lifespan(config)),
+            ExpiryPolicy::Hybrid { max, .. } => Some(self.created + max),
+        }
+    }
+
+    /// Validates the Session against both its expiration and the policy's absolute expiration.
+    pub(crate) fn validate_policy(&self, config: &AxumSessionConfig) -> bool {
+        self.validate()
+            && self
+                .absolute_expiry(config)
+                .is_none_or(|expiry| expiry >= Utc::now())
+            && config
+                .idle_timeout
+                .is_none_or(|idle| self.last_accessed + idle >= Utc::now())
+    }
+
+    /// Checks if the cookies have not been sent within the cookie refresh interval.
+    pub(crate) fn cookie_refresh_due(&self, config: &AxumSessionConfig) -> bool {
+        self.cookie_sent
+            .is_none_or(|sent| sent + config.cookie_refresh_interval <= Utc::now())
+    }
+
+    /// Updates the Session's last access time.
+    ///
+    /// To avoid a database write each request this only happens once it has moved by more
+    /// than a tenth of the idle timeout, or the touch granularity when no idle timeout is set.
+    pub(crate) fn touch(&mut self, config: &AxumSessionConfig) {
+        let threshold = config
+            .idle_timeout
+            .map_or(config.touch_granularity, |idle| idle / 10);
+
+        if Utc::now() - self.last_accessed > threshold {
+            self.last_accessed = Utc::now();
+            self.update = true;
+        }
+    }
+
+    /// Sets a serialized value removing any binary value using the same Key.
+    pub(crate) fn insert_value(&mut self, key: &str, value: String) {
+        if self.bytes.remove(key).is_some() {
+            self.update = true;
+        }
+
+        if self.data.get(key) != Some(&value) {
+            self.data.insert(key.to_string(), value);
+            self.update = true;
+        }
+    }
+
+    /// Removes a Key from both the serialized and binary values.
+    pub(crate) fn remove_value(&mut self, key: &str) -> Option<String> {
+        let value = self.data.remove(key);
+
+        if value.is_some() || self.bytes.remove(key).is_some() {
+            self.update = true;
+        }
+
+        value
+    }
+
+    /// Moves the flash values set by the last request so they can only be read by this request.
+    pub(crate) fn start_flash(&mut self) {
+        self.flash_current = std::mem::take(&mut self.flash);
+
+        if !self.flash_current.is_empty() {
+            self.update = true;
+        }
+    }
+
+    /// Updates the Session's expiration time based on the configured ExpiryPolicy.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionData};
+    /// use uuid::Uuid;
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let token = Uuid::new_v4();
+    /// let mut session_data = AxumSessionData::new(token.to_string(), true, &config);
+    /// session_data.update_expiry(&config);
+    /// ```
+    ///
+    pub(crate) fn update_expiry(&mut self, config: &AxumSessionConfig) {
+        let lifespan = self.lifespan(config);
+
+        self.expires = match config.expiry_policy {
+            ExpiryPolicy::Sliding => Utc::now() + lifespan,
+            ExpiryPolicy::Absolute => self.created + lifespan,
+            ExpiryPolicy::Hybrid { idle, max } => {
+                let idle = self.cookie_lifespan().unwrap_or(idle);
+                std::cmp::min(Utc::now() + idle, self.created + max)
+            }
+        };
+    }
+}
+
+/// A read only copy of a Session returned by AxumSessionStore::get_session.
+///
+/// # Examples
+/// ```rust ignore
+/// if let Some(snapshot) = session_store.get_session(&id).await? {
+///     let user_id: Option<i64> = snapshot.get("user-id");
+/// }
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct SessionSnapshot {
+    /// The Session's ID.
+    pub id: String,
+    /// The Session's data as raw JSON values.
+    pub data: HashMap<String, String>,
+    /// The Session's binary values set using set_bytes().
+    pub bytes: HashMap<String, Vec<u8>>,
+    /// When the Session expires.
+    pub expires: DateTime<Utc>,
+    /// When the Session was first created.
+    pub created: DateTime<Utc>,
+    /// When the Session was last used.
+    pub last_accessed: DateTime<Utc>,
+    /// If the Session was accepted as storable.
+    pub accepted: bool,
+    /// If the Session has a long term expiration.
+    pub longterm: bool,
+}
+
+impl SessionSnapshot {
+    /// Gets data from the snapshot. Returns None if Key does not exist or failed to deserialize.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        serde_json::from_str(self.data.get(key)?).ok()
+    }
+}
+
+impl From<&AxumSessionData> for SessionSnapshot {
+    fn from(sess: &AxumSessionData) -> Self {
+        Self {
+            id: sess.id.clone(),
+            data: sess.data.clone(),
+            bytes: sess.bytes.clone(),
+            expires: sess.expires,
+            created: sess.created,
+            last_accessed: sess.last_accessed,
+            accepted: sess.storable,
+            longterm: sess.longterm,
+        }
+    }
+}
+
+/// A short summary of a Session returned by AxumSessionStore::sessions_for_user.
+///
+/// # Examples
+/// ```rust ignore
+/// for summary in session_store.sessions_for_user("42", 0, 20).await? {
+///     println!("{} last used {}", summary.id, summary.last_accessed);
+/// }
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    /// The Session's ID.
+    pub id: String,
+    /// When the Session was first created.
+    pub created: DateTime<Utc>,
+    /// When the Session was last used.
+    pub last_accessed: DateTime<Utc>,
+    /// When the Session expires.
+    pub expires: DateTime<Utc>,
+    /// If the Session has a long term expiration.
+    pub longterm: bool,
+}
+
+impl From<&AxumSessionData> for SessionSummary {
+    fn from(sess: &AxumSessionData) -> Self {
+        Self {
+            id: sess.id.clone(),
+            created: sess.created,
+            last_accessed: sess.last_accessed,
+            expires: sess.expires,
+            longterm: sess.longterm,
+        }
+    }
+}
+
+/// Stores binary values as base64 within human readable formats like JSON
+/// and as raw bytes within binary formats.
+mod bytes_map {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use std::collections::HashMap;
+
+    pub(crate) fn serialize<S: Serializer>(
+        map: &HashMap<String, Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_map(map.iter().map(|(key, value)| (key, base64::encode(value))))
+        } else {
+            serializer.collect_map(map)
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<String, Vec<u8>>, D::Error> {
+        if deserializer.is_human_readable() {
+            HashMap::<String, String>::deserialize(deserializer)?
+                .into_iter()
+                .map(|(key, value)| {
+                    base64::decode(value)
+                        .map(|value| (key, value))
+                        .map_err(D::Error::custom)
+                })
+                .collect()
+        } else {
+            HashMap::deserialize(deserializer)
+        }
+    }
 }
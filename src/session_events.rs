@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use futures::FutureExt;
+use std::{collections::HashMap, fmt::Debug, future::Future, net::IpAddr, panic::AssertUnwindSafe};
+
+/// Receives the Session lifecycle events, like for audit logs or to remove files kept per Session.
+///
+/// Every method defaults to doing nothing so only the needed events have to be implemented.
+/// Handlers are called after the store's locks are released so they can use the store.
+/// Panics within a handler are caught and logged. Handlers run within the request or cleanup
+/// that caused the event so slow handlers should spawn their work.
+///
+/// # Examples
+/// ```rust
+/// use async_trait::async_trait;
+/// use axum_database_sessions::{AxumSessionConfig, SessionEventHandler};
+/// use std::collections::HashMap;
+///
+/// #[derive(Debug)]
+/// struct AuditLog;
+///
+/// #[async_trait]
+/// impl SessionEventHandler for AuditLog {
+///     async fn on_create(&self, id: &str) {
+///         tracing::info!("session {} created", id);
+///     }
+///
+///     async fn on_destroy(&self, id: &str, _data: &HashMap<String, String>) {
+///         tracing::info!("session {} destroyed", id);
+///     }
+/// }
+///
+/// let config = AxumSessionConfig::default().with_event_handler(AuditLog);
+/// ```
+///
+#[async_trait]
+pub trait SessionEventHandler: Debug + Send + Sync {
+    /// Called when a request starts a new Session.
+    async fn on_create(&self, _id: &str) {}
+
+    /// Called when an existing Session is loaded from the database into memory.
+    async fn on_load(&self, _id: &str) {}
+
+    /// Called when a Session is destroyed by session.destroy(), destroy_session or
+    /// destroy_all_for_user with the Session's data from right before it was destroyed.
+    async fn on_destroy(&self, _id: &str, _data: &HashMap<String, String>) {}
+
+    /// Called when an expired Session is removed by cleanup or replaced by a new Session.
+    ///
+    /// Databases whose AxumDatabasePool::delete_expired_ids can not return the IDs, like
+    /// ones that expire sessions themselves, never call this for their expired Sessions.
+    async fn on_expired(&self, _id: &str) {}
+
+    /// Called when a Session is used from an IP outside of the one it was created from
+    /// while AxumSessionConfig::with_ip_binding is set to IpBinding::Notify.
+    async fn on_ip_mismatch(&self, _id: &str, _session_ip: IpAddr, _client_ip: IpAddr) {}
+
+    /// Called when a Session is used with another User-Agent than the one it was created with
+    /// while AxumSessionConfig::with_user_agent_binding is set. The request gets a new Session.
+    async fn on_user_agent_mismatch(&self, _id: &str) {}
+}
+
+/// Runs a handler's event logging instead of unwinding if it panicked.
+pub(crate) async fn catch_event_panic(event: &str, handler: impl Future<Output = ()>) {
+    if AssertUnwindSafe(handler).catch_unwind().await.is_err() {
+        tracing::error!("Session event handler panicked within {}", event);
+    }
+}
@@ -1,62 +1,179 @@
-use serde::{Deserialize, Serialize};
-use std::fmt::{self, Display, Formatter};
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    sync::{Arc, RwLock},
+};
 use uuid::Uuid;
 
-/// Contains the UUID the Session.
+/// Generates and validates the Session IDs used within the cookies and the database.
 ///
-/// This is used to store and find the Session.
-/// Used to pass the UUID between Cookies, the Database, and AxumSession.
+/// The default is [`UuidGenerator`]. Implement this to use your own ID format like ULID.
 ///
 /// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, SessionIdGenerator};
+///
+/// #[derive(Debug)]
+/// struct SimpleUuidGenerator;
+///
+/// impl SessionIdGenerator for SimpleUuidGenerator {
+///     fn generate(&self) -> String {
+///         uuid::Uuid::new_v4().simple().to_string()
+///     }
+///
+///     fn validate(&self, id: &str) -> bool {
+///         id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit())
+///     }
+/// }
+///
+/// let config = AxumSessionConfig::default().with_id_generator(SimpleUuidGenerator);
+/// ```
+///
+pub trait SessionIdGenerator: Debug + Send + Sync {
+    /// Generates a new random Session ID.
+    fn generate(&self) -> String;
+    /// Checks if the Session ID read from a cookie is in a valid format.
+    fn validate(&self, id: &str) -> bool;
+}
+
+/// Default [`SessionIdGenerator`] which uses UUID v4.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{SessionIdGenerator, UuidGenerator};
+///
+/// let id = UuidGenerator.generate();
+/// assert!(UuidGenerator.validate(&id));
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidGenerator;
+
+impl SessionIdGenerator for UuidGenerator {
+    fn generate(&self) -> String {
+        Uuid::new_v4().to_string()
+    }
+
+    fn validate(&self, id: &str) -> bool {
+        Uuid::parse_str(id).is_ok()
+    }
+}
+
+/// [`SessionIdGenerator`] which creates 256 bit random tokens encoded as URL safe base64.
+///
+/// Tokens are made from 32 bytes of the operating system's random number generator.
+/// UUID Session IDs are still accepted so existing Sessions keep working while migrating.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::{AxumSessionConfig, SessionIdGenerator, TokenGenerator, UuidGenerator};
+///
+/// let id = TokenGenerator.generate();
+/// assert!(TokenGenerator.validate(&id));
+/// assert!(TokenGenerator.validate(&UuidGenerator.generate()));
+///
+/// let config = AxumSessionConfig::default().with_id_generator(TokenGenerator);
 /// ```
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenGenerator;
+
+impl SessionIdGenerator for TokenGenerator {
+    fn generate(&self) -> String {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+    }
+
+    fn validate(&self, id: &str) -> bool {
+        base64::decode_config(id, base64::URL_SAFE_NO_PAD).is_ok_and(|bytes| bytes.len() == 32)
+            || UuidGenerator.validate(id)
+    }
+}
+
+/// Contains the ID of the Session.
+///
+/// This is used to store and find the Session.
+/// Used to pass the ID between Cookies, the Database, and AxumSession.
+/// Clones share the same ID so it can be changed by AxumSession::renew() while a handler holds it.
+///
+/// # Examples
+/// ```rust ignore
 /// use axum_database_sessions::AxumSessionID;
 /// use uuid::Uuid;
 ///
 ///
 /// let token = Uuid::new_v4();
-/// let id = AxumSessionID::new(token);
+/// let id = AxumSessionID::new(token.to_string());
 /// ```
 ///
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-pub(crate) struct AxumSessionID(pub(crate) Uuid);
+#[derive(Debug, Clone)]
+pub(crate) struct AxumSessionID(Arc<RwLock<String>>);
 
 impl AxumSessionID {
-    /// Constructs a new AxumSessionID hold a UUID.
+    /// Constructs a new AxumSessionID holding a Session ID.
     ///
     /// # Examples
-    /// ```rust
+    /// ```rust ignore
     /// use axum_database_sessions::AxumSessionID;
     /// use uuid::Uuid;
     ///
     ///
     /// let token = Uuid::new_v4();
-    /// let id = AxumSessionID::new(token);
+    /// let id = AxumSessionID::new(token.to_string());
     /// ```
     ///
-    pub(crate) fn new(uuid: Uuid) -> AxumSessionID {
-        AxumSessionID(uuid)
+    pub(crate) fn new(id: String) -> AxumSessionID {
+        AxumSessionID(Arc::new(RwLock::new(id)))
     }
 
-    /// Returns the inner UUID as a string.
+    /// Returns the inner Session ID as a string.
     ///
     /// # Examples
-    /// ```rust
+    /// ```rust ignore
     /// use axum_database_sessions::AxumSessionID;
     /// use uuid::Uuid;
     ///
     ///
     /// let token = Uuid::new_v4();
-    /// let id = AxumSessionID::new(token);
+    /// let id = AxumSessionID::new(token.to_string());
     /// let str_id = id.inner();
     /// ```
     ///
     pub(crate) fn inner(&self) -> String {
-        self.0.to_string()
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replaces the Session ID for every clone.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// use axum_database_sessions::AxumSessionID;
+    /// use uuid::Uuid;
+    ///
+    ///
+    /// let id = AxumSessionID::new(Uuid::new_v4().to_string());
+    /// id.set(Uuid::new_v4().to_string());
+    /// ```
+    ///
+    pub(crate) fn set(&self, id: String) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = id;
     }
 }
 
 impl Display for AxumSessionID {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.0.to_string())
+        f.write_str(&self.inner())
     }
 }
+
+/// Returns a hash of the Session ID that is safe to write into logs.
+///
+/// This is the first 8 bytes of the ID's SHA-256 as hex so it stays the same across Rust releases.
+pub(crate) fn short_id(id: &str) -> String {
+    Sha256::digest(id.as_bytes())[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
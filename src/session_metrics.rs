@@ -0,0 +1,53 @@
+use crate::AxumSessionConfig;
+
+/// Adds value to the counter named `{prefix}_{name}`.
+#[cfg(feature = "metrics")]
+pub(crate) fn count(config: &AxumSessionConfig, name: &str, value: u64) {
+    metrics::counter!(format!("{}_{}", config.metrics_prefix, name), value);
+}
+
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn count(_config: &AxumSessionConfig, _name: &str, _value: u64) {}
+
+/// Sets the gauge named `{prefix}_{name}`.
+#[cfg(feature = "metrics")]
+pub(crate) fn gauge(config: &AxumSessionConfig, name: &str, value: f64) {
+    metrics::gauge!(format!("{}_{}", config.metrics_prefix, name), value);
+}
+
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn gauge(_config: &AxumSessionConfig, _name: &str, _value: f64) {}
+
+/// Times a database call for the histogram named `{prefix}_{name}`.
+///
+/// Without the `metrics` feature this holds nothing and never reads the clock.
+pub(crate) struct LatencyTimer {
+    #[cfg(feature = "metrics")]
+    start: std::time::Instant,
+}
+
+impl LatencyTimer {
+    #[inline(always)]
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Records the time since start labeled with the database pool's type name.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn record<T>(self, config: &AxumSessionConfig, name: &str) {
+        metrics::histogram!(
+            format!("{}_{}", config.metrics_prefix, name),
+            self.start.elapsed(),
+            "backend" => crate::backend_name::<T>()
+        );
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    #[inline(always)]
+    pub(crate) fn record<T>(self, _config: &AxumSessionConfig, _name: &str) {}
+}
@@ -0,0 +1,163 @@
+use crate::{AxumDatabasePool, AxumSession};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+
+/// A view of the Session where every Key is prefixed by a namespace.
+///
+/// Lets separate parts of an application share one Session without their Keys colliding.
+/// Keys are stored as `namespace:key` so a namespace can be nested within another namespace.
+///
+/// # Examples
+/// ```rust ignore
+/// let cart = session.namespace("cart");
+/// cart.set("items", vec![1, 2, 3]).await;
+/// cart.clear().await;
+/// ```
+///
+pub struct SessionNamespace<T, M = ()>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    session: AxumSession<T, M>,
+    prefix: String,
+}
+
+impl<T, M> Clone for SessionNamespace<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            session: self.session.clone(),
+            prefix: self.prefix.clone(),
+        }
+    }
+}
+
+impl<T, M> Debug for SessionNamespace<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionNamespace")
+            .field("session", &self.session)
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl<T, M> SessionNamespace<T, M>
+where
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+{
+    pub(crate) fn new(session: AxumSession<T, M>, namespace: &str) -> Self {
+        Self {
+            session,
+            prefix: format!("{}:", namespace),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    /// Returns a namespace nested within this namespace.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let saved = session.namespace("cart").namespace("saved");
+    /// ```
+    ///
+    pub fn namespace(&self, namespace: &str) -> SessionNamespace<T, M> {
+        SessionNamespace {
+            session: self.session.clone(),
+            prefix: format!("{}{}:", self.prefix, namespace),
+        }
+    }
+
+    /// Gets data from the namespace. See AxumSession::get.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let items: Option<Vec<i64>> = cart.get("items").await;
+    /// ```
+    ///
+    pub async fn get<D: DeserializeOwned>(&self, key: &str) -> Option<D> {
+        self.session.get(&self.key(key)).await
+    }
+
+    /// Returns true if the Key exists within the namespace. See AxumSession::contains_key.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let has_items = cart.contains_key("items").await;
+    /// ```
+    ///
+    pub async fn contains_key(&self, key: &str) -> bool {
+        self.session.contains_key(&self.key(key)).await
+    }
+
+    /// Sets data within the namespace. See AxumSession::set.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// cart.set("items", vec![1, 2, 3]).await;
+    /// ```
+    ///
+    pub async fn set(&self, key: &str, value: impl Serialize) {
+        self.session.set(&self.key(key), value).await;
+    }
+
+    /// Removes a Key from the namespace. See AxumSession::remove.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// cart.remove("items").await;
+    /// ```
+    ///
+    pub async fn remove(&self, key: &str) {
+        self.session.remove(&self.key(key)).await;
+    }
+
+    /// Returns the Keys within the namespace without the namespace's prefix.
+    ///
+    /// Keys of nested namespaces are included with their nested prefix.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let keys = cart.keys().await;
+    /// ```
+    ///
+    pub async fn keys(&self) -> Vec<String> {
+        self.session
+            .keys()
+            .await
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(&self.prefix).map(str::to_owned))
+            .collect()
+    }
+
+    /// Removes every Key within the namespace and its nested namespaces.
+    ///
+    /// Keys outside of the namespace are not changed.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// cart.clear().await;
+    /// ```
+    ///
+    pub async fn clear(&self) {
+        self.session.tap(|sess| {
+            let before = sess.data.len() + sess.bytes.len();
+
+            sess.data.retain(|key, _| !key.starts_with(&self.prefix));
+            sess.bytes.retain(|key, _| !key.starts_with(&self.prefix));
+
+            if sess.data.len() + sess.bytes.len() != before {
+                sess.update = true;
+            }
+
+            Some(1)
+        });
+    }
+}
@@ -1,23 +1,37 @@
 use crate::{
+    backend_name, catch_event_panic, session_metrics, session_metrics::LatencyTimer, short_id,
     AxumDatabasePool, AxumSession, AxumSessionConfig, AxumSessionData, AxumSessionTimers,
-    SessionError,
+    Migrations, OverflowPolicy, SessionCleanupHandle, SessionConfigError, SessionError,
+    SessionRecord, SessionSnapshot, SessionSummary, SessionWriteBehindHandle, WriteBehindQueue,
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::{HashMap, HashSet},
     fmt::Debug,
+    future::Future,
     marker::{Send, Sync},
-    sync::Arc,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
 };
-use tokio::sync::RwLock;
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt},
+    sync::{Notify, RwLock},
+};
+use tracing::{field::Empty, Instrument, Span};
 /// Contains the main Services storage for all session's and database access for persistant Sessions.
 ///
 /// # Examples
 /// ```rust
-/// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
+/// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
 ///
 /// let config = AxumSessionConfig::default();
-/// let session_store = AxumSessionStore::new(None, config);
+/// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
 /// ```
 ///
 #[derive(Clone, Debug)]
@@ -27,14 +41,113 @@ where
 {
     // Client for the database
     pub client: Option<T>,
+    // Optional read replica client used for loading and counting sessions.
+    pub reader: Option<T>,
     /// locked Hashmap containing UserID and their session data
     pub(crate) inner: Arc<DashMap<String, AxumSessionData>>,
+    /// Sessions removed by destroy_session mapped to when their tombstone expires.
+    /// Stops requests that were still running from storing them again.
+    pub(crate) destroyed: Arc<DashMap<String, DateTime<Utc>>>,
+    /// Sessions waiting to be written by the task started with spawn_write_behind.
+    pub(crate) write_behind: Arc<WriteBehindQueue>,
+    /// New Sessions created per client IP mapped to when its rate limit window started.
+    pub(crate) rate_limits: Arc<DashMap<IpAddr, (DateTime<Utc>, u32)>>,
+    /// Set by shutdown to stop the background tasks.
+    pub(crate) stopping: Arc<StoreShutdown>,
     //move this to creation upon layer
     pub config: AxumSessionConfig,
     //move this to creation on layer.
     pub(crate) timers: Arc<RwLock<AxumSessionTimers>>,
 }
 
+/// How many expired Sessions were removed by AxumSessionStore::cleanup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionCleanup {
+    /// Sessions deleted from the database. Databases that expire sessions themselves report 0.
+    pub database: u64,
+    /// Sessions removed from memory.
+    pub memory: u64,
+}
+
+/// How many changed Sessions AxumSessionStore::shutdown wrote to the database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlushReport {
+    /// Sessions written to the database.
+    pub saved: u64,
+    /// Sessions that failed to be written or were not written before the shutdown timeout.
+    pub failed: u64,
+}
+
+/// Tells the background tasks the store is shutting down.
+#[derive(Debug, Default)]
+pub(crate) struct StoreShutdown {
+    started: AtomicBool,
+    notify: Notify,
+}
+
+impl StoreShutdown {
+    fn start(&self) {
+        self.started.store(true, Ordering::Release);
+        self.notify.notify_waiters();
+    }
+
+    pub(crate) fn is_started(&self) -> bool {
+        self.started.load(Ordering::Acquire)
+    }
+
+    /// Resolves once the shutdown started.
+    async fn wait(&self) {
+        // Created before the check so a shutdown starting in between still wakes it.
+        let notified = self.notify.notified();
+
+        if !self.is_started() {
+            notified.await;
+        }
+    }
+}
+
+/// The store's health returned by AxumSessionStore::health.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreHealth {
+    /// How long the database took to answer. None when the store has no database.
+    pub latency: Option<std::time::Duration>,
+    /// Sessions within memory.
+    pub memory_sessions: usize,
+}
+
+/// Session counts returned by AxumSessionStore::stats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StoreStats {
+    /// Sessions within memory.
+    pub memory_sessions: usize,
+    /// Sessions within the database including the expired ones.
+    pub database_sessions: i64,
+    /// Expired Sessions within the database waiting to be removed by cleanup.
+    pub database_expired: i64,
+    /// When cleanup last finished. None if it has not run yet.
+    pub last_cleanup: Option<DateTime<Utc>>,
+}
+
+/// Format name written within the first line of an export.
+const EXPORT_FORMAT: &str = "axum_database_sessions";
+/// Bumped whenever the exported Session lines change in a way older versions can not read.
+const EXPORT_VERSION: u32 = 1;
+
+/// The first line of an export made by AxumSessionStore::export.
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportHeader {
+    format: String,
+    version: u32,
+}
+
+/// Runs a database call within the span recording how long it took as elapsed_ms.
+async fn traced<F: Future>(span: Span, call: F) -> F::Output {
+    let start = Instant::now();
+    let output = call.instrument(span.clone()).await;
+    span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+    output
+}
+
 impl<T> AxumSessionStore<T>
 where
     T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
@@ -43,24 +156,132 @@ where
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     /// ```
     ///
+    /// # Panics
+    /// Panics if the config is invalid, listing every problem found. Use try_new to handle them instead.
+    ///
     pub fn new(client: Option<T>, config: AxumSessionConfig) -> Self {
-        Self {
+        Self::new_with_reader(client, None, config)
+    }
+
+    /// Constructs a New AxumSessionStore returning an error if the config is invalid.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    ///
+    /// let config = AxumSessionConfig::default().with_cookie_path("no_slash");
+    /// let error = AxumSessionStore::<AxumNullPool>::try_new(None, config).unwrap_err();
+    /// assert_eq!(error.problems.len(), 1);
+    /// ```
+    ///
+    pub fn try_new(
+        client: Option<T>,
+        config: AxumSessionConfig,
+    ) -> Result<Self, SessionConfigError> {
+        Self::try_new_with_reader(client, None, config)
+    }
+
+    /// Constructs a New AxumSessionStore using a writer client and a read replica client.
+    ///
+    /// load_session and count use the reader while everything else uses the writer client.
+    /// Since replicas can lag behind, a session not found on the reader is loaded from the writer.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::new_with_reader(Some(AxumNullPool), Some(AxumNullPool), config);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the config is invalid, listing every problem found. Use try_new_with_reader to handle them instead.
+    ///
+    pub fn new_with_reader(
+        client: Option<T>,
+        reader: Option<T>,
+        config: AxumSessionConfig,
+    ) -> Self {
+        Self::try_new_with_reader(client, reader, config).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Constructs a New AxumSessionStore using a writer client and a read replica client,
+    /// returning an error if the config is invalid.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::try_new_with_reader(Some(AxumNullPool), Some(AxumNullPool), config);
+    /// assert!(session_store.is_ok());
+    /// ```
+    ///
+    pub fn try_new_with_reader(
+        client: Option<T>,
+        reader: Option<T>,
+        config: AxumSessionConfig,
+    ) -> Result<Self, SessionConfigError> {
+        let config = config.validate()?;
+
+        Ok(Self {
             client,
+            reader,
             inner: Default::default(),
+            destroyed: Default::default(),
+            write_behind: Default::default(),
+            rate_limits: Default::default(),
+            stopping: Default::default(),
             config,
             timers: Arc::new(RwLock::new(AxumSessionTimers {
                 // the first expiry sweep is scheduled one lifetime from start-up
                 last_expiry_sweep: Utc::now() + Duration::hours(1),
                 // the first expiry sweep is scheduled one lifetime from start-up
                 last_database_expiry_sweep: Utc::now() + Duration::hours(6),
+                last_cleanup: None,
+                last_rate_limit_sweep: Utc::now(),
             })),
-        }
+        })
+    }
+
+    /// Constructs a New AxumSessionStore from an existing database pool.
+    ///
+    /// The pool is shared with the caller so its connection limits and settings are kept.
+    /// Any pool type that converts Into the AxumDatabasePool type can be used, like sqlx's PgPool.
+    ///
+    /// # Examples
+    /// ```rust no_run
+    /// use axum_database_sessions::{AxumPgPool, AxumSessionConfig, AxumSessionStore};
+    /// use sqlx::postgres::PgPoolOptions;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let pool = PgPoolOptions::new()
+    ///     .max_connections(20)
+    ///     .connect("postgres://localhost/my_app")
+    ///     .await?;
+    ///
+    /// let session_store = AxumSessionStore::<AxumPgPool>::from_pool(pool.clone(), AxumSessionConfig::default());
+    /// session_store.initiate().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the config is invalid, listing every problem found.
+    ///
+    pub fn from_pool(pool: impl Into<T>, config: AxumSessionConfig) -> Self {
+        Self::new(Some(pool.into()), config)
+    }
+
+    /// Returns the reader client if one is set otherwise the writer client.
+    pub(crate) fn reader(&self) -> Option<&T> {
+        self.reader.as_ref().or(self.client.as_ref())
     }
 
     /// Checks if the database is in persistent mode.
@@ -69,10 +290,10 @@ where
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     /// let is_persistent = session_store.is_persistent();
     /// ```
     ///
@@ -82,57 +303,454 @@ where
 
     /// Creates the Database Table needed for the Session if it does not exist.
     ///
+    /// AxumSessionConfig::with_migrations can check the table instead or skip it.
     /// If client is None it will return Ok(()).
     ///
     /// # Errors
     /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    /// - ['SessionError::InvalidTableName'] is returned if the table name contains anything other than `[A-Za-z0-9_]`.
+    /// - ['SessionError::InvalidConfig'] is returned if AxumSessionConfig::validate() finds any problems.
+    /// - ['SessionError::MissingSchema'] is returned if Migrations::CheckOnly finds the table or a column missing.
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     /// async {
     ///     let _ = session_store.initiate().await.unwrap();
     /// };
     /// ```
     ///
     pub async fn initiate(&self) -> Result<(), SessionError> {
+        if !self.config.is_valid_table_name() {
+            return Err(SessionError::InvalidTableName(
+                self.config.database_table_name().to_string(),
+            ));
+        }
+
+        self.config.clone().validate()?;
+
         if let Some(client) = &self.client {
-            client.initiate(&self.config.table_name).await?
+            let table_name = self.config.database_table_name();
+
+            match self.config.migrations {
+                Migrations::Run => client.initiate(&table_name).await?,
+                Migrations::CheckOnly => {
+                    let missing = client.check(&table_name).await?;
+
+                    if !missing.is_empty() {
+                        return Err(SessionError::MissingSchema(missing));
+                    }
+                }
+                Migrations::Skip => {}
+            }
         }
 
         Ok(())
     }
 
-    /// Cleans Expired sessions from the Database based on Utc::now().
+    /// Returns the statements initiate runs to create the Session table.
     ///
-    /// If client is None it will return Ok(()).
+    /// Use this to create the table using other migration tools like sqlx migrate or flyway
+    /// along with AxumSessionConfig::with_migrations. Returns None for databases without SQL.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// if let Some(sql) = session_store.migration_sql() {
+    ///     std::fs::write("migrations/0001_sessions.sql", sql).unwrap();
+    /// }
+    /// ```
+    ///
+    pub fn migration_sql(&self) -> Option<String> {
+        T::migration_sql(&self.config.database_table_name())
+    }
+
+    /// Cleans Expired sessions from the Database and memory based on Utc::now().
+    ///
+    /// This can be called on demand, like from a scheduler, and is safe to run alongside requests.
+    /// Returns how many Sessions were removed from each. If client is None only the memory is cleaned.
     ///
     /// # Errors
     /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     /// async {
-    ///     let _ = session_store.cleanup().await.unwrap();
+    ///     let removed = session_store.cleanup().await.unwrap();
+    ///     println!("{} removed from the database", removed.database);
     /// };
     /// ```
     ///
-    pub async fn cleanup(&self) -> Result<(), SessionError> {
+    pub async fn cleanup(&self) -> Result<SessionCleanup, SessionError> {
+        let mut removed = SessionCleanup::default();
+        let mut expired = Vec::new();
+
+        self.inner.retain(|id, sess| {
+            let keep = sess.validate_policy(&self.config);
+
+            if !keep {
+                removed.memory += 1;
+
+                // Sessions also within the database are reported once the database removes them.
+                if self.is_memory_only(sess) {
+                    expired.push(id.clone());
+                }
+            }
+
+            keep
+        });
+        self.destroyed.retain(|_, until| *until > Utc::now());
+
+        for id in expired {
+            self.on_expired(&id).await;
+        }
+
         if let Some(client) = &self.client {
-            client.delete_by_expiry(&self.config.table_name).await?;
+            let table_name = self.config.database_table_name();
+            let span = tracing::debug_span!(
+                "session_delete_by_expiry",
+                backend = backend_name::<T>(),
+                elapsed_ms = Empty
+            );
+
+            if self.config.event_handler.is_some() {
+                let ids = traced(span, client.delete_expired_ids(&table_name)).await?;
+                removed.database = ids.len() as u64;
+
+                for id in ids {
+                    self.on_expired(&id).await;
+                }
+            } else {
+                removed.database = traced(span, client.delete_by_expiry(&table_name)).await?;
+                session_metrics::count(&self.config, "expired_total", removed.database);
+            }
+
+            // Sampled here since counting the rows is too costly for every request.
+            #[cfg(feature = "metrics")]
+            match client.count(&table_name).await {
+                Ok(count) => {
+                    session_metrics::gauge(&self.config, "database_sessions", count as f64)
+                }
+                Err(err) => tracing::error!("Failed to count sessions for metrics: {}", err),
+            }
         }
 
-        Ok(())
+        session_metrics::gauge(&self.config, "memory_sessions", self.inner.len() as f64);
+        self.timers.write().await.last_cleanup = Some(Utc::now());
+        Ok(removed)
+    }
+
+    /// Starts a background task that runs cleanup every interval until it is shut down.
+    ///
+    /// Each run waits up to an extra tenth of the interval at random so multiple servers
+    /// do not clean up the same database at once. Errors are logged and the cleanup is
+    /// tried again on the next run. Intervals under a second are raised to one second.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    /// use chrono::Duration;
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// async {
+    ///     let cleanup = session_store.spawn_cleanup(Duration::minutes(10));
+    ///     // ...
+    ///     cleanup.shutdown().await;
+    /// };
+    /// ```
+    ///
+    pub fn spawn_cleanup(&self, interval: Duration) -> SessionCleanupHandle {
+        let interval = interval
+            .to_std()
+            .unwrap_or_default()
+            .max(std::time::Duration::from_secs(1));
+        let shutdown = Arc::new(Notify::new());
+        let notified = shutdown.clone();
+        let store = self.clone();
+
+        let task = tokio::spawn(async move {
+            loop {
+                let jitter = interval.mul_f64(rand::random::<f64>() / 10.0);
+
+                tokio::select! {
+                    _ = notified.notified() => break,
+                    _ = store.stopping.wait() => break,
+                    _ = tokio::time::sleep(interval + jitter) => {}
+                }
+
+                match store.cleanup().await {
+                    Ok(removed) => tracing::debug!(
+                        "Cleaned up expired sessions, {} from the database and {} from memory",
+                        removed.database,
+                        removed.memory
+                    ),
+                    Err(err) => tracing::error!("Failed to clean up expired sessions: {}", err),
+                }
+            }
+        });
+
+        SessionCleanupHandle { shutdown, task }
+    }
+
+    /// Starts a background task that writes changed Sessions to the database within batches.
+    ///
+    /// Instead of each request writing its Session once the response is returned, the Sessions
+    /// are queued and written every interval or once max_batch Sessions are queued, whichever
+    /// comes first. Queued Sessions are lost if the server stops before they are written, so call
+    /// shutdown on the returned handle or AxumSessionStore::shutdown during graceful shutdown.
+    /// session.force_save() still writes right away. Errors are logged and the Sessions are tried
+    /// again on the next write.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    /// use chrono::Duration;
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// async {
+    ///     let write_behind = session_store.spawn_write_behind(Duration::milliseconds(250), 500);
+    ///     // ...
+    ///     write_behind.shutdown().await;
+    /// };
+    /// ```
+    ///
+    pub fn spawn_write_behind(
+        &self,
+        interval: Duration,
+        max_batch: usize,
+    ) -> SessionWriteBehindHandle {
+        let interval = interval
+            .to_std()
+            .unwrap_or_default()
+            .max(std::time::Duration::from_millis(1));
+        let shutdown = Arc::new(Notify::new());
+        let notified = shutdown.clone();
+        let store = self.clone();
+
+        store
+            .write_behind
+            .max_batch
+            .store(max_batch.max(1), Ordering::Relaxed);
+        store.write_behind.enabled.store(true, Ordering::Release);
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = notified.notified() => break,
+                    _ = store.stopping.wait() => break,
+                    _ = store.write_behind.full.notified() => {}
+                    _ = tokio::time::sleep(interval) => {}
+                }
+
+                if let Err(err) = store.flush_write_behind().await {
+                    tracing::error!("Failed to write queued sessions: {}", err);
+                }
+            }
+
+            store.write_behind.enabled.store(false, Ordering::Release);
+
+            if let Err(err) = store.flush_write_behind().await {
+                tracing::error!("Failed to write queued sessions during shutdown: {}", err);
+            }
+        });
+
+        SessionWriteBehindHandle { shutdown, task }
+    }
+
+    /// Writes every changed Session to the database and stops the background tasks.
+    ///
+    /// Call this during graceful shutdown so Sessions queued by the write behind task or changed
+    /// after their response, like by a DetachedSession, are not lost. The cleanup and write behind
+    /// tasks stop and requests still running afterwards write their Sessions right away. Sessions
+    /// not written within AxumSessionConfig::with_shutdown_timeout are counted as failed.
+    /// Stores without a database have nothing to write and return right away.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool, FlushReport};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// let report = session_store.shutdown().await.unwrap();
+    /// assert_eq!(report, FlushReport::default());
+    /// # }
+    /// ```
+    ///
+    /// Within axum's graceful shutdown:
+    ///
+    /// ```rust
+    /// use axum::{routing::get, Router};
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionLayer, AxumSessionStore, AxumNullPool};
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    /// let app: Router = Router::new()
+    ///     .route("/", get(|| async { "Hello" }))
+    ///     .layer(AxumSessionLayer::new(session_store.clone()));
+    ///
+    /// async {
+    ///     axum::Server::bind(&"0.0.0.0:3000".parse().unwrap())
+    ///         .serve(app.into_make_service())
+    ///         .with_graceful_shutdown(async {
+    ///             tokio::signal::ctrl_c().await.unwrap();
+    ///             let report = session_store.shutdown().await.unwrap();
+    ///             tracing::info!("{} sessions saved, {} failed", report.saved, report.failed);
+    ///         })
+    ///         .await
+    ///         .unwrap();
+    /// };
+    /// ```
+    ///
+    pub async fn shutdown(&self) -> Result<FlushReport, SessionError> {
+        self.stopping.start();
+        self.write_behind.enabled.store(false, Ordering::Release);
+
+        let mut report = FlushReport::default();
+
+        if !self.is_persistent() {
+            return Ok(report);
+        }
+
+        let storable_mode = self.config.session_mode.is_storable();
+        let mut sessions: HashMap<String, AxumSessionData> = self
+            .write_behind
+            .take()
+            .into_iter()
+            .map(|sess| (sess.id.clone(), sess))
+            .collect();
+
+        // Changes within memory are newer than the queued copies.
+        for mut sess in self.inner.iter_mut() {
+            if sess.update && !sess.destroy && (!storable_mode || sess.storable) {
+                sess.update_expiry(&self.config);
+                sess.update = false;
+                sessions.insert(sess.id.clone(), sess.clone());
+            }
+        }
+
+        sessions.retain(|id, _| !self.is_destroyed(id));
+
+        let deadline =
+            tokio::time::Instant::now() + self.config.shutdown_timeout.to_std().unwrap_or_default();
+
+        for sess in sessions.into_values() {
+            match tokio::time::timeout_at(deadline, self.store_session(&sess)).await {
+                Ok(Ok(())) => report.saved += 1,
+                Ok(Err(err)) => {
+                    tracing::error!(
+                        "Failed to store session {} during shutdown: {}",
+                        short_id(&sess.id),
+                        err
+                    );
+                    report.failed += 1;
+                }
+                Err(_) => report.failed += 1,
+            }
+        }
+
+        if report.failed > 0 {
+            tracing::warn!(
+                "Session shutdown stored {} sessions and failed to store {}",
+                report.saved,
+                report.failed
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Checks the database can be reached, like for a readiness probe.
+    ///
+    /// Runs a trivial query against the database and reports how long it took along with the
+    /// Sessions within memory. This does not lock the memory store so it can be called often.
+    ///
+    /// # Errors
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    /// async {
+    ///     let health = session_store.health().await.unwrap();
+    ///     println!("{} sessions in memory", health.memory_sessions);
+    /// };
+    /// ```
+    ///
+    pub async fn health(&self) -> Result<StoreHealth, SessionError> {
+        let mut latency = None;
+
+        if let Some(client) = &self.client {
+            let start = std::time::Instant::now();
+            client.ping(&self.config.database_table_name()).await?;
+            latency = Some(start.elapsed());
+        }
+
+        Ok(StoreHealth {
+            latency,
+            memory_sessions: self.inner.len(),
+        })
+    }
+
+    /// Returns how many Sessions are within memory and the database, like for a debug endpoint.
+    ///
+    /// The database is counted twice, once for the unexpired and once for the expired Sessions.
+    /// If client is None the database counts are 0.
+    ///
+    /// # Errors
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    /// async {
+    ///     let stats = session_store.stats().await.unwrap();
+    ///     println!("{} expired sessions waiting for cleanup", stats.database_expired);
+    /// };
+    /// ```
+    ///
+    pub async fn stats(&self) -> Result<StoreStats, SessionError> {
+        let mut stats = StoreStats {
+            memory_sessions: self.inner.len(),
+            last_cleanup: self.timers.read().await.last_cleanup,
+            ..Default::default()
+        };
+
+        if let Some(client) = self.reader() {
+            let table_name = self.config.database_table_name();
+            let unexpired = client.count(&table_name).await?;
+
+            stats.database_expired = client.count_expired(&table_name).await?;
+            stats.database_sessions = unexpired + stats.database_expired;
+        }
+
+        Ok(stats)
     }
 
-    /// Returns count of existing sessions within database.
+    /// Returns count of unexpired sessions within database.
     ///
     /// If client is None it will return Ok(0).
     ///
@@ -141,18 +759,23 @@ where
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     /// async {
     ///     let count = session_store.count().await.unwrap();
     /// };
     /// ```
     ///
     pub async fn count(&self) -> Result<i64, SessionError> {
-        if let Some(client) = &self.client {
-            let count = client.count(&self.config.table_name).await?;
+        if let Some(client) = self.reader() {
+            let span = tracing::debug_span!(
+                "session_count",
+                backend = backend_name::<T>(),
+                elapsed_ms = Empty
+            );
+            let count = traced(span, client.count(&self.config.database_table_name())).await?;
             return Ok(count);
         }
 
@@ -166,14 +789,15 @@ where
     /// # Errors
     /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
     /// - ['SessionError::SerdeJson'] is returned if it failed to deserialize the sessions data.
+    /// - ['SessionError::SerializerMismatch'] is returned if the session was stored using a different serializer.
     ///
     /// # Examples
-    /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore};
+    /// ```rust ignore
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     /// use uuid::Uuid;
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config);
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     /// let token = Uuid::new_v4();
     /// async {
     ///     let session_data = session_store.load_session(token.to_string()).await.unwrap();
@@ -184,131 +808,978 @@ where
         &self,
         cookie_value: String,
     ) -> Result<Option<AxumSessionData>, SessionError> {
+        // Queued Sessions are newer than the copy within the database.
+        if let Some(sess) = self.write_behind.sessions.get(&cookie_value) {
+            return Ok(Some(sess.clone()));
+        }
+
         if let Some(client) = &self.client {
-            let result: Option<String> =
-                client.load(&cookie_value, &self.config.table_name).await?;
+            let table_name = self.config.database_table_name();
+            let timer = LatencyTimer::start();
+            let span = tracing::debug_span!(
+                "session_load",
+                backend = backend_name::<T>(),
+                session = %short_id(&cookie_value),
+                elapsed_ms = Empty
+            );
+
+            let result = traced(span, async {
+                let mut result: Option<String> = None;
+
+                if let Some(reader) = &self.reader {
+                    result = reader.load(&cookie_value, &table_name).await?;
+                }
+
+                // The replica might not have the session yet so fall back to the writer.
+                if result.is_none() {
+                    result = client.load(&cookie_value, &table_name).await?;
+                }
+
+                Ok::<_, SessionError>(result)
+            })
+            .await?;
+
+            timer.record::<T>(&self.config, "load_seconds");
 
             Ok(result
-                .map(|session| serde_json::from_str(&session))
+                .map(|session| self.config.serializer.deserialize(&session))
                 .transpose()?)
         } else {
             Ok(None)
         }
     }
 
-    /// private internal function that stores a session's data to the database.
+    /// Gets a read only copy of any Session by its ID.
     ///
-    /// If client is None it will return Ok(()).
+    /// Checks the memory first and then the database without adding the Session to memory.
+    /// Returns None if the Session does not exist, expired or was destroyed.
     ///
     /// # Errors
     /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
-    /// - ['SessionError::SerdeJson'] is returned if it failed to serialize the sessions data.
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumSessionData};
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     /// use uuid::Uuid;
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config.clone());
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config.clone());
     /// let token = Uuid::new_v4();
-    /// let session_data = AxumSessionData::new(token, true, &config);
     ///
     /// async {
-    ///     let _ = session_store.store_session(&session_data).await.unwrap();
+    ///     let snapshot = session_store.get_session(&token.to_string()).await.unwrap();
     /// };
     /// ```
     ///
-    pub(crate) async fn store_session(
-        &self,
-        session: &AxumSessionData,
-    ) -> Result<(), SessionError> {
-        if let Some(client) = &self.client {
-            client
-                .store(
-                    &session.id.to_string(),
-                    &serde_json::to_string(session)?,
-                    session.expires.timestamp(),
-                    &self.config.table_name,
-                )
-                .await?;
-        }
+    pub async fn get_session(&self, id: &str) -> Result<Option<SessionSnapshot>, SessionError> {
+        let memory = self.inner.get(id).map(|sess| sess.clone());
 
-        Ok(())
+        let loaded = match memory {
+            Some(sess) => Some(sess),
+            None => self.load_session(id.to_owned()).await?,
+        };
+
+        Ok(loaded
+            .filter(|sess| sess.validate_policy(&self.config) && !sess.destroy)
+            .map(|sess| SessionSnapshot::from(&sess)))
     }
 
-    /// Deletes a session's data from the database by its UUID.
+    /// Lists the Sessions stored with the given user id, like for a "Your active sessions" page.
     ///
-    /// If client is None it will return Ok(()).
+    /// Only the database is checked and expired Sessions are skipped. The Sessions are ordered
+    /// by ID and at most limit are returned after skipping offset, so a page can be smaller than
+    /// limit if some of its Sessions expired by the ExpiryPolicy. If client is None it will return Ok(Vec::new()).
     ///
     /// # Errors
     /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    /// - ['SessionError::SerdeJson'] is returned if it failed to deserialize the sessions data.
+    /// - ['SessionError::GenericNotSupportedError'] is returned if the database can not find Sessions by user id.
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumSessionData};
-    /// use uuid::Uuid;
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config.clone());
-    /// let token = Uuid::new_v4();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     ///
     /// async {
-    ///     let _ = session_store.destroy_session(&token.to_string()).await.unwrap();
+    ///     let sessions = session_store.sessions_for_user("42", 0, 20).await.unwrap();
     /// };
     /// ```
     ///
-    pub async fn destroy_session(&self, id: &str) -> Result<(), SessionError> {
+    pub async fn sessions_for_user(
+        &self,
+        user_id: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<SessionSummary>, SessionError> {
+        let mut summaries = Vec::new();
+
         if let Some(client) = &self.client {
-            client.delete_one_by_id(id, &self.config.table_name).await?;
+            let sessions = client
+                .load_by_user_id(
+                    user_id,
+                    i64::try_from(offset).unwrap_or(i64::MAX),
+                    i64::try_from(limit).unwrap_or(i64::MAX),
+                    &self.config.database_table_name(),
+                )
+                .await?;
+
+            for session in sessions {
+                let sess: AxumSessionData = self.config.serializer.deserialize(&session)?;
+
+                if sess.validate_policy(&self.config) && !self.is_destroyed(&sess.id) {
+                    summaries.push(SessionSummary::from(&sess));
+                }
+            }
         }
 
-        Ok(())
+        Ok(summaries)
     }
 
-    /// Deletes all sessions in the database.
+    /// Lists a page of Session IDs without loading the Sessions, like for bulk inspection using get_session.
     ///
-    /// If client is None it will return Ok(()).
+    /// When persistent the IDs of unexpired Sessions come from the database ordered by their
+    /// expiration and then ID. Otherwise the Sessions in memory are used with the same ordering.
+    /// At most limit IDs are returned after skipping offset.
+    ///
+    /// Since Sessions change their expiration when used and new Sessions are added while paging,
+    /// an ID can move between pages. Walking every page may skip or repeat some IDs.
     ///
     /// # Errors
     /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    /// - ['SessionError::GenericNotSupportedError'] is returned if the database can not list Session IDs.
     ///
     /// # Examples
     /// ```rust
-    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumSessionData};
-    /// use uuid::Uuid;
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
     ///
     /// let config = AxumSessionConfig::default();
-    /// let session_store = AxumSessionStore::new(None, config.clone());
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
     ///
     /// async {
-    ///     let _ = session_store.clear_store().await.unwrap();
+    ///     let ids = session_store.ids(0, 100).await.unwrap();
     /// };
     /// ```
     ///
-    pub async fn clear_store(&self) -> Result<(), SessionError> {
+    pub async fn ids(&self, offset: u64, limit: u64) -> Result<Vec<String>, SessionError> {
+        let offset = i64::try_from(offset).unwrap_or(i64::MAX);
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+
+        if let Some(client) = self.reader() {
+            return client
+                .load_ids(offset, limit, &self.config.database_table_name())
+                .await;
+        }
+
+        let mut sessions: Vec<(DateTime<Utc>, String)> = self
+            .inner
+            .iter()
+            .filter(|sess| sess.validate() && !sess.destroy)
+            .map(|sess| (sess.expires, sess.key().clone()))
+            .collect();
+
+        sessions.sort();
+
+        Ok(sessions
+            .into_iter()
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(usize::try_from(limit).unwrap_or(usize::MAX))
+            .map(|(_, id)| id)
+            .collect())
+    }
+
+    /// Writes every unexpired Session as newline delimited JSON, like for a backup or to move to another database.
+    ///
+    /// The first line names the format and its version and each following line is one Session
+    /// including its data, expiration and flags. The JSON does not depend on the configured
+    /// serializer so it can be imported by a store using any database or serializer.
+    /// When persistent the Sessions come from the database, otherwise from memory.
+    /// Returns how many Sessions were written.
+    ///
+    /// # Errors
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    /// - ['SessionError::SerdeJson'] is returned if it failed to serialize the sessions data.
+    /// - ['SessionError::IO'] is returned if it failed to write to the writer.
+    /// - ['SessionError::GenericNotSupportedError'] is returned if the database can not list Session IDs.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let backup = concat!(
+    ///     r#"{"format":"axum_database_sessions","version":1}"#, "\n",
+    ///     r#"{"id":"4c1d8a4e-7d07-4e4b-9c57-1b5b2f1a0c3e","data":{"count":"1"},"expires":"2999-01-01T00:00:00Z","#,
+    ///     r#""autoremove":"2999-01-01T00:00:00Z","destroy":false,"longterm":false,"storable":true,"update":false}"#, "\n",
+    /// );
+    ///
+    /// let old_store = AxumSessionStore::<AxumNullPool>::new(None, AxumSessionConfig::default());
+    /// assert_eq!(old_store.import(backup.as_bytes()).await.unwrap(), 1);
+    ///
+    /// let mut exported = Vec::new();
+    /// assert_eq!(old_store.export(&mut exported).await.unwrap(), 1);
+    ///
+    /// let new_store = AxumSessionStore::<AxumNullPool>::new(None, AxumSessionConfig::default());
+    /// assert_eq!(new_store.import(exported.as_slice()).await.unwrap(), 1);
+    /// assert!(new_store
+    ///     .get_session("4c1d8a4e-7d07-4e4b-9c57-1b5b2f1a0c3e")
+    ///     .await
+    ///     .unwrap()
+    ///     .is_some());
+    /// # }
+    /// ```
+    ///
+    pub async fn export<W>(&self, mut writer: W) -> Result<u64, SessionError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let table_name = self.config.database_table_name();
+        // Loaded before anything is written so databases unable to list IDs fail without a partial backup.
+        let mut first_page = match self.reader() {
+            Some(client) => Some(client.load_ids(0, 1000, &table_name).await?),
+            None => None,
+        };
+
+        let header = ExportHeader {
+            format: EXPORT_FORMAT.to_owned(),
+            version: EXPORT_VERSION,
+        };
+        let mut line = serde_json::to_vec(&header)?;
+        line.push(b'\n');
+        writer.write_all(&line).await?;
+
+        let mut count = 0;
+
+        if let Some(client) = self.reader() {
+            // Sessions used while paging move to a later page so they could be seen twice.
+            let mut exported = HashSet::new();
+            let mut offset = 0;
+
+            loop {
+                let ids = match first_page.take() {
+                    Some(ids) => ids,
+                    None => client.load_ids(offset, 1000, &table_name).await?,
+                };
+
+                if ids.is_empty() {
+                    break;
+                }
+
+                offset += ids.len() as i64;
+
+                for id in ids {
+                    if exported.contains(&id) {
+                        continue;
+                    }
+
+                    if let Some(sess) = self.load_session(id.clone()).await? {
+                        if self.write_export_line(&mut writer, &sess).await? {
+                            count += 1;
+                        }
+                    }
+
+                    exported.insert(id);
+                }
+            }
+        } else {
+            let sessions: Vec<AxumSessionData> =
+                self.inner.iter().map(|sess| sess.clone()).collect();
+
+            for sess in sessions {
+                if self.write_export_line(&mut writer, &sess).await? {
+                    count += 1;
+                }
+            }
+        }
+
+        writer.flush().await?;
+        Ok(count)
+    }
+
+    /// Writes the Session as one line of an export if it has not expired or been destroyed.
+    async fn write_export_line<W>(
+        &self,
+        writer: &mut W,
+        sess: &AxumSessionData,
+    ) -> Result<bool, SessionError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        if !sess.validate_policy(&self.config) || sess.destroy || self.is_destroyed(&sess.id) {
+            return Ok(false);
+        }
+
+        let mut line = serde_json::to_vec(sess)?;
+        line.push(b'\n');
+        writer.write_all(&line).await?;
+        Ok(true)
+    }
+
+    /// Reads Sessions written by export and stores them, replacing any Sessions with the same IDs.
+    ///
+    /// Sessions that expired since they were exported are skipped. When persistent the Sessions
+    /// are written to the database within batches and any copies within memory are removed
+    /// so the imported Sessions are loaded by the next request. Otherwise they are added to memory.
+    /// Returns how many Sessions were imported.
+    ///
+    /// # Errors
+    /// - ['SessionError::InvalidExport'] is returned if the first line is not an export header or its version is unsupported.
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    /// - ['SessionError::SerdeJson'] is returned if a line is not a valid Session.
+    /// - ['SessionError::IO'] is returned if it failed to read from the reader.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// let file = tokio::fs::File::open("sessions.ndjson").await?;
+    /// let imported = session_store.import(tokio::io::BufReader::new(file)).await?;
+    /// ```
+    ///
+    pub async fn import<R>(&self, reader: R) -> Result<u64, SessionError>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        let mut lines = reader.lines();
+
+        let header: ExportHeader = match lines.next_line().await? {
+            Some(line) => serde_json::from_str(&line)
+                .map_err(|_| SessionError::InvalidExport("header is missing".to_owned()))?,
+            None => return Err(SessionError::InvalidExport("header is missing".to_owned())),
+        };
+
+        if header.format != EXPORT_FORMAT || header.version != EXPORT_VERSION {
+            return Err(SessionError::InvalidExport(format!(
+                "{} version {} is not supported",
+                header.format, header.version
+            )));
+        }
+
+        let table_name = self.config.database_table_name();
+        let mut batch = Vec::new();
+        let mut count = 0;
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let sess: AxumSessionData = serde_json::from_str(&line)?;
+
+            if !sess.validate_policy(&self.config) || sess.destroy {
+                continue;
+            }
+
+            count += 1;
+
+            if let Some(client) = &self.client {
+                self.inner.remove(&sess.id);
+                batch.push(SessionRecord {
+                    id: sess.id.clone(),
+                    session: self.config.serializer.serialize(&sess)?,
+                    expires: sess.expires.timestamp(),
+                    user_id: sess.user_id.clone(),
+                });
+
+                if batch.len() >= 1000 {
+                    client.store_batch(&batch, &table_name).await?;
+                    batch.clear();
+                }
+            } else {
+                self.inner.insert(sess.id.clone(), sess);
+            }
+        }
+
         if let Some(client) = &self.client {
-            client.delete_all(&self.config.table_name).await?;
+            if !batch.is_empty() {
+                client.store_batch(&batch, &table_name).await?;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// private internal function that stores a session's data to the database.
+    ///
+    /// If client is None it will return Ok(()).
+    ///
+    /// # Errors
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    /// - ['SessionError::SerdeJson'] is returned if it failed to serialize the sessions data.
+    ///
+    /// # Examples
+    /// ```rust ignore
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    /// use uuid::Uuid;
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config.clone());
+    /// let token = Uuid::new_v4();
+    /// let session_data = AxumSessionData::new(token.to_string(), true, &config);
+    ///
+    /// async {
+    ///     let _ = session_store.store_session(&session_data).await.unwrap();
+    /// };
+    /// ```
+    ///
+    pub(crate) async fn store_session(
+        &self,
+        session: &AxumSessionData,
+    ) -> Result<(), SessionError> {
+        if self.is_destroyed(&session.id) {
+            return Ok(());
+        }
+
+        if let Some(client) = &self.client {
+            let timer = LatencyTimer::start();
+            let span = tracing::debug_span!(
+                "session_store",
+                backend = backend_name::<T>(),
+                session = %short_id(&session.id),
+                elapsed_ms = Empty
+            );
+
+            traced(
+                span,
+                client.store_with_user(
+                    &session.id.to_string(),
+                    &self.config.serializer.serialize(session)?,
+                    session.expires.timestamp(),
+                    session.user_id.as_deref(),
+                    &self.config.database_table_name(),
+                ),
+            )
+            .await?;
+
+            timer.record::<T>(&self.config, "save_seconds");
         }
 
         Ok(())
     }
 
-    /// Attempts to load check and clear Data.
+    /// Stores the Session once the response is returned.
+    ///
+    /// While the write behind task is running the Session is queued instead of written right away.
+    pub(crate) async fn save_session(&self, session: &AxumSessionData) -> Result<(), SessionError> {
+        if self.is_persistent() && self.write_behind.is_enabled() {
+            if !self.is_destroyed(&session.id) {
+                self.write_behind.push(session.clone());
+            }
+
+            return Ok(());
+        }
+
+        self.store_session(session).await
+    }
+
+    /// Writes every queued Session to the database within batches.
+    ///
+    /// If the write fails the Sessions are queued again unless a newer copy was queued meanwhile.
+    pub(crate) async fn flush_write_behind(&self) -> Result<(), SessionError> {
+        let sessions: Vec<AxumSessionData> = self
+            .write_behind
+            .take()
+            .into_iter()
+            .filter(|sess| !self.is_destroyed(&sess.id))
+            .collect();
+
+        let client = match &self.client {
+            Some(client) if !sessions.is_empty() => client,
+            _ => return Ok(()),
+        };
+
+        let mut records = Vec::with_capacity(sessions.len());
+
+        for sess in &sessions {
+            match self.config.serializer.serialize(sess) {
+                Ok(session) => records.push(SessionRecord {
+                    id: sess.id.clone(),
+                    session,
+                    expires: sess.expires.timestamp(),
+                    user_id: sess.user_id.clone(),
+                }),
+                Err(err) => {
+                    tracing::error!(session = %short_id(&sess.id), "Failed to serialize session: {}", err)
+                }
+            }
+        }
+
+        let timer = LatencyTimer::start();
+        let span = tracing::debug_span!(
+            "session_store_batch",
+            backend = backend_name::<T>(),
+            sessions = records.len(),
+            elapsed_ms = Empty
+        );
+
+        if let Err(err) = traced(
+            span,
+            client.store_batch(&records, &self.config.database_table_name()),
+        )
+        .await
+        {
+            for sess in sessions {
+                self.write_behind
+                    .sessions
+                    .entry(sess.id.clone())
+                    .or_insert(sess);
+            }
+
+            return Err(err);
+        }
+
+        timer.record::<T>(&self.config, "save_seconds");
+        Ok(())
+    }
+
+    /// Deletes a session's data from the database by its ID.
+    ///
+    /// If client is None it will return Ok(()).
+    pub(crate) async fn delete_session(&self, id: &str) -> Result<(), SessionError> {
+        self.write_behind.sessions.remove(id);
+
+        if let Some(client) = &self.client {
+            client
+                .delete_one_by_id(id, &self.config.database_table_name())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Destroys any Session by its ID, removing it from memory and the database.
+    ///
+    /// Returns true if the Session existed. Requests still using the Session when it is
+    /// destroyed will not store it again and will remove its cookie.
+    ///
+    /// # Errors
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    /// use uuid::Uuid;
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config.clone());
+    /// let token = Uuid::new_v4();
+    ///
+    /// async {
+    ///     let destroyed = session_store.destroy_session(&token.to_string()).await.unwrap();
+    /// };
+    /// ```
+    ///
+    pub async fn destroy_session(&self, id: &str) -> Result<bool, SessionError> {
+        self.destroyed
+            .insert(id.to_owned(), Utc::now() + self.config.memory_lifespan);
+
+        // The queued copy is newer than the one within memory.
+        let queued = self.write_behind.sessions.remove(id).map(|(_, sess)| sess);
+        let in_memory = self.inner.remove(id).map(|(_, sess)| sess);
+        let mut data = queued.or(in_memory).map(|sess| sess.data);
+        let mut found = data.is_some();
+
+        if let Some(client) = &self.client {
+            let table_name = self.config.database_table_name();
+
+            if let Some(session) = client.load(id, &table_name).await? {
+                found = true;
+
+                if data.is_none() {
+                    data = self
+                        .config
+                        .serializer
+                        .deserialize(&session)
+                        .ok()
+                        .map(|sess| sess.data);
+                }
+            }
+
+            client.delete_one_by_id(id, &table_name).await?;
+        }
+
+        if found {
+            self.on_destroy(id, &data.unwrap_or_default()).await;
+        }
+
+        Ok(found)
+    }
+
+    /// Destroys every Session stored with the given user id, removing them from memory and the database.
+    ///
+    /// Returns the number of Sessions destroyed. Requests still using any of the
+    /// Sessions will not store them again and will remove their cookies.
+    /// Only sessions with a user id set by session.set_store_user_id() are found.
+    ///
+    /// # Errors
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    /// - ['SessionError::GenericNotSupportedError'] is returned if the database can not find Sessions by user id.
+    ///   The Sessions found within memory are still destroyed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config);
+    ///
+    /// async {
+    ///     let removed = session_store.destroy_all_for_user("42").await.unwrap();
+    /// };
+    /// ```
+    ///
+    pub async fn destroy_all_for_user(&self, user_id: &str) -> Result<u64, SessionError> {
+        let until = Utc::now() + self.config.memory_lifespan;
+
+        // The final data of each Session given to the event handler. Queued copies are the newest.
+        let mut destroyed: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+        self.write_behind.sessions.retain(|id, sess| {
+            let keep = sess.user_id.as_deref() != Some(user_id);
+
+            if !keep {
+                self.destroyed.insert(id.clone(), until);
+                destroyed.insert(id.clone(), sess.data.clone());
+            }
+
+            keep
+        });
+
+        let in_memory: Vec<String> = self
+            .inner
+            .iter()
+            .filter(|sess| sess.user_id.as_deref() == Some(user_id))
+            .map(|sess| sess.key().clone())
+            .collect();
+
+        for id in in_memory {
+            self.destroyed.insert(id.clone(), until);
+
+            if let Some((id, sess)) = self.inner.remove(&id) {
+                destroyed.entry(id).or_insert(sess.data);
+            }
+        }
+
+        if let Some(client) = &self.client {
+            let table_name = self.config.database_table_name();
+
+            // Removed by ID as well so they stay removed even if the database can not find them by user id.
+            for id in destroyed.keys() {
+                client.delete_one_by_id(id, &table_name).await?;
+            }
+
+            // Only needed so the event handler gets the data of Sessions that were not within memory.
+            if self.config.event_handler.is_some() {
+                let sessions = client
+                    .load_by_user_id(user_id, 0, i64::MAX, &table_name)
+                    .await?;
+
+                for session in sessions {
+                    if let Ok(sess) = self.config.serializer.deserialize(&session) {
+                        destroyed.entry(sess.id).or_insert(sess.data);
+                    }
+                }
+            }
+
+            let ids = client.delete_by_user_id(user_id, &table_name).await?;
+
+            for id in ids {
+                self.inner.remove(&id);
+                self.destroyed.insert(id.clone(), until);
+                destroyed.entry(id).or_default();
+            }
+        }
+
+        let removed = destroyed.len() as u64;
+
+        for (id, data) in destroyed {
+            self.on_destroy(&id, &data).await;
+        }
+
+        Ok(removed)
+    }
+
+    /// Makes sure the user has room for one more Session using the configured OverflowPolicy.
     ///
-    /// If no session is found returns false.
-    pub(crate) fn service_session_data(&self, session: &AxumSession<T>) -> bool {
-        if let Some(mut inner) = self.inner.get_mut(&session.id.inner()) {
-            if inner.expires < Utc::now() || inner.destroy {
-                inner.destroy = false;
-                inner.longterm = false;
-                inner.data.clear();
+    /// The user's unexpired Sessions are gathered from the database, memory and the write behind
+    /// queue, skipping the Session the user id is being set on.
+    pub(crate) async fn make_room_for_user(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<(), SessionError> {
+        let Some((max, policy)) = self.config.max_sessions_per_user else {
+            return Ok(());
+        };
+
+        let mut sessions: HashMap<String, AxumSessionData> = HashMap::new();
+
+        if let Some(client) = &self.client {
+            let loaded = client
+                .load_by_user_id(user_id, 0, i64::MAX, &self.config.database_table_name())
+                .await?;
+
+            for session in loaded {
+                let sess: AxumSessionData = self.config.serializer.deserialize(&session)?;
+                sessions.insert(sess.id.clone(), sess);
+            }
+        }
+
+        // Memory and queued copies are newer than the database's.
+        for sess in self.inner.iter().chain(self.write_behind.sessions.iter()) {
+            if sess.user_id.as_deref() == Some(user_id) {
+                sessions.insert(sess.id.clone(), sess.clone());
             }
+        }
+
+        let mut sessions: Vec<AxumSessionData> = sessions
+            .into_values()
+            .filter(|sess| {
+                sess.id != session_id
+                    && sess.user_id.as_deref() == Some(user_id)
+                    && sess.validate_policy(&self.config)
+                    && !sess.destroy
+                    && !self.is_destroyed(&sess.id)
+            })
+            .collect();
+
+        if sessions.len() < max {
+            return Ok(());
+        }
 
-            inner.autoremove = Utc::now() + self.config.memory_lifespan;
+        if policy == OverflowPolicy::Reject {
+            return Err(SessionError::TooManySessions(max));
+        }
+
+        sessions.sort_by_key(|sess| sess.last_accessed);
+
+        // Leaves max - 1 of the other Sessions so the new one fits within the limit.
+        let excess = sessions.len() + 1 - max.max(1);
+
+        for sess in sessions.iter().take(excess) {
+            self.destroy_session(&sess.id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Counts a new Session for the client's IP returning false once it is past the rate limit.
+    ///
+    /// Always returns true without an IP or AxumSessionConfig::with_session_rate_limit.
+    pub(crate) async fn allow_new_session(&self, ip: Option<IpAddr>) -> bool {
+        let (Some(ip), Some((max, period, _))) = (ip, self.config.session_rate_limit) else {
             return true;
+        };
+
+        let now = Utc::now();
+
+        // Windows that ended are dropped once per period so clients that left are not kept forever.
+        if self.timers.read().await.last_rate_limit_sweep + period <= now {
+            self.timers.write().await.last_rate_limit_sweep = now;
+            self.rate_limits
+                .retain(|_, (started, _)| *started + period > now);
+        }
+
+        let mut window = self.rate_limits.entry(ip).or_insert((now, 0));
+
+        if window.0 + period <= now {
+            *window = (now, 0);
+        }
+
+        if window.1 >= max {
+            return false;
+        }
+
+        window.1 += 1;
+        true
+    }
+
+    /// Checks if the Session is not also stored within the database.
+    pub(crate) fn is_memory_only(&self, sess: &AxumSessionData) -> bool {
+        !self.is_persistent() || !sess.storable
+    }
+
+    /// Generates a new Session ID that is not used by a Session within memory.
+    pub(crate) fn generate_id(&self) -> String {
+        loop {
+            let token = self.config.id_generator.generate();
+
+            if !self.inner.contains_key(&token) {
+                break token;
+            }
+        }
+    }
+
+    /// Counts the created Session and calls the event handler's on_create if one is set.
+    pub(crate) async fn on_create(&self, id: &str) {
+        session_metrics::count(&self.config, "created_total", 1);
+
+        if let Some(handler) = &self.config.event_handler {
+            catch_event_panic("on_create", handler.on_create(id)).await;
+        }
+    }
+
+    /// Counts the loaded Session and calls the event handler's on_load if one is set.
+    pub(crate) async fn on_load(&self, id: &str) {
+        session_metrics::count(&self.config, "loaded_total", 1);
+
+        if let Some(handler) = &self.config.event_handler {
+            catch_event_panic("on_load", handler.on_load(id)).await;
+        }
+    }
+
+    /// Counts the destroyed Session and calls the event handler's on_destroy if one is set.
+    pub(crate) async fn on_destroy(&self, id: &str, data: &HashMap<String, String>) {
+        session_metrics::count(&self.config, "destroyed_total", 1);
+
+        if let Some(handler) = &self.config.event_handler {
+            catch_event_panic("on_destroy", handler.on_destroy(id, data)).await;
+        }
+    }
+
+    /// Counts the expired Session and calls the event handler's on_expired if one is set.
+    pub(crate) async fn on_expired(&self, id: &str) {
+        session_metrics::count(&self.config, "expired_total", 1);
+
+        if let Some(handler) = &self.config.event_handler {
+            catch_event_panic("on_expired", handler.on_expired(id)).await;
+        }
+    }
+
+    /// Calls the event handler's on_ip_mismatch if one is set.
+    pub(crate) async fn on_ip_mismatch(&self, id: &str, session_ip: IpAddr, client_ip: IpAddr) {
+        if let Some(handler) = &self.config.event_handler {
+            catch_event_panic(
+                "on_ip_mismatch",
+                handler.on_ip_mismatch(id, session_ip, client_ip),
+            )
+            .await;
+        }
+    }
+
+    /// Calls the event handler's on_user_agent_mismatch if one is set.
+    pub(crate) async fn on_user_agent_mismatch(&self, id: &str) {
+        if let Some(handler) = &self.config.event_handler {
+            catch_event_panic("on_user_agent_mismatch", handler.on_user_agent_mismatch(id)).await;
+        }
+    }
+
+    /// Checks if the Session was destroyed by destroy_session and its tombstone has not expired.
+    pub(crate) fn is_destroyed(&self, id: &str) -> bool {
+        self.destroyed
+            .get(id)
+            .is_some_and(|until| *until > Utc::now())
+    }
+
+    /// Deletes every Session in the database, logging out every user.
+    ///
+    /// This is meant for admin tooling. Use session.clear_all() to clear only the Current Session.
+    /// If client is None it will return Ok(()).
+    ///
+    /// # Errors
+    /// - ['SessionError::Sqlx'] is returned if database connection has failed or user does not have permissions.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, AxumNullPool};
+    /// use uuid::Uuid;
+    ///
+    /// let config = AxumSessionConfig::default();
+    /// let session_store = AxumSessionStore::<AxumNullPool>::new(None, config.clone());
+    ///
+    /// async {
+    ///     let _ = session_store.clear_store().await.unwrap();
+    /// };
+    /// ```
+    ///
+    pub async fn clear_store(&self) -> Result<(), SessionError> {
+        self.write_behind.sessions.clear();
+
+        if let Some(client) = &self.client {
+            client
+                .delete_all(&self.config.database_table_name())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Evicts the least recently used Sessions once memory holds more than memory_max_sessions.
+    ///
+    /// A tenth of the limit is evicted beyond what is needed so this does not run every request.
+    /// Sessions used by a request are skipped.
+    pub(crate) fn evict_memory(&self) {
+        let max = match self.config.memory_max_sessions {
+            Some(max) if self.inner.len() > max => max,
+            _ => return,
+        };
+
+        let mut unused: Vec<(DateTime<Utc>, String)> = self
+            .inner
+            .iter()
+            .filter(|sess| sess.requests == 0)
+            .map(|sess| (sess.memory_used, sess.key().clone()))
+            .collect();
+
+        unused.sort();
+
+        let excess = self.inner.len().saturating_sub(max - max / 10);
+        let mut evicted = 0;
+
+        for (_, id) in unused.into_iter().take(excess) {
+            if self
+                .inner
+                .remove_if(&id, |_, sess| sess.requests == 0)
+                .is_some()
+            {
+                evicted += 1;
+            }
+        }
+
+        tracing::info!(
+            "Evicted {} least recently used sessions from memory, the limit is {}",
+            evicted,
+            max
+        );
+    }
+
+    /// Attempts to load check and clear Data.
+    ///
+    /// If no session is found returns None, otherwise returns if the Session expired and was reset.
+    pub(crate) fn service_session_data<M>(&self, session: &AxumSession<T, M>) -> Option<bool> {
+        let id = session.id.inner();
+
+        // When persistent the memory copy is dropped once the memory lifespan has passed
+        // so it gets reloaded from the database in case another instance changed it.
+        // Copies still used by a request or DetachedSession are kept so their changes are not lost.
+        if self.is_persistent()
+            && self
+                .inner
+                .remove_if(&id, |_, inner| {
+                    inner.autoremove < Utc::now() && inner.requests == 0
+                })
+                .is_some()
+        {
+            return None;
+        }
+
+        if let Some(mut inner) = self.inner.get_mut(&id) {
+            let expired = !inner.validate_policy(&self.config);
+
+            if expired || inner.destroy {
+                inner.reset();
+                // Gives the new Session a current expiration so cleanup will not remove it mid request.
+                inner.update_expiry(&self.config);
+            }
+
+            // Only extend the memory lifespan when the memory is the only copy of the session.
+            if !self.is_persistent() {
+                inner.autoremove = Utc::now() + self.config.memory_lifespan;
+            }
+
+            return Some(expired);
         }
 
-        false
+        None
     }
 }
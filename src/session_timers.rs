@@ -8,4 +8,8 @@ use chrono::{DateTime, Utc};
 pub(crate) struct AxumSessionTimers {
     pub(crate) last_expiry_sweep: DateTime<Utc>,
     pub(crate) last_database_expiry_sweep: DateTime<Utc>,
+    /// When AxumSessionStore::cleanup last finished.
+    pub(crate) last_cleanup: Option<DateTime<Utc>>,
+    /// When the ended rate limit windows were last dropped.
+    pub(crate) last_rate_limit_sweep: DateTime<Utc>,
 }
@@ -0,0 +1,82 @@
+use crate::{AxumDatabasePool, ReadOnlySession, SessionData};
+use async_trait::async_trait;
+use axum_core::{
+    extract::FromRequestParts,
+    response::{IntoResponse, Response},
+};
+use http::request::Parts;
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+/// Extracts a SessionData Type from the Session within the handler's arguments.
+///
+/// Rejects the request using SessionData::rejection() when the Type is missing or fails
+/// to deserialize, which makes handlers that require a logged in user declarative.
+/// The Session is read like [`crate::ReadOnlySession`] so it is not written back by itself.
+///
+/// # Examples
+/// ```rust ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct UserId(i64);
+///
+/// impl SessionData for UserId {
+///     const KEY: &'static str = "user-id";
+/// }
+///
+/// async fn profile(SessionValue(user_id, _): SessionValue<UserId, AxumPgPool>) {}
+/// ```
+///
+pub struct SessionValue<D, T, M = ()>(pub D, pub PhantomData<fn() -> (T, M)>);
+
+impl<D, T, M> SessionValue<D, T, M> {
+    /// Returns the extracted value.
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<D: Debug, T, M> Debug for SessionValue<D, T, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SessionValue").field(&self.0).finish()
+    }
+}
+
+impl<D, T, M> Deref for SessionValue<D, T, M> {
+    type Target = D;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<D, T, M> DerefMut for SessionValue<D, T, M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[async_trait]
+impl<S, D, T, M> FromRequestParts<S> for SessionValue<D, T, M>
+where
+    S: Send + Sync,
+    D: SessionData,
+    T: AxumDatabasePool + Clone + Debug + Sync + Send + 'static,
+    M: Send + Sync + 'static,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let session = ReadOnlySession::<T, M>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        session
+            .get_typed::<D>()
+            .await
+            .map(|value| SessionValue(value, PhantomData))
+            .ok_or_else(D::rejection)
+    }
+}
@@ -0,0 +1,78 @@
+use crate::AxumSessionData;
+use dashmap::DashMap;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc,
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+/// Sessions waiting to be written to the database by the write behind task.
+///
+/// Each Session is only queued once so only its latest data is written.
+#[derive(Debug, Default)]
+pub(crate) struct WriteBehindQueue {
+    pub(crate) enabled: AtomicBool,
+    pub(crate) max_batch: AtomicUsize,
+    pub(crate) sessions: DashMap<String, AxumSessionData>,
+    /// Wakes the task once max_batch Sessions are queued.
+    pub(crate) full: Notify,
+}
+
+impl WriteBehindQueue {
+    /// Returns true if the write behind task is running.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Queues the Session replacing any older copy of it.
+    pub(crate) fn push(&self, session: AxumSessionData) {
+        self.sessions.insert(session.id.clone(), session);
+
+        if self.sessions.len() >= self.max_batch.load(Ordering::Relaxed) {
+            self.full.notify_one();
+        }
+    }
+
+    /// Removes and returns every queued Session.
+    pub(crate) fn take(&self) -> Vec<AxumSessionData> {
+        let ids: Vec<String> = self
+            .sessions
+            .iter()
+            .map(|sess| sess.key().clone())
+            .collect();
+
+        ids.into_iter()
+            .filter_map(|id| self.sessions.remove(&id).map(|(_, sess)| sess))
+            .collect()
+    }
+}
+
+/// Handle to the write behind task started by AxumSessionStore::spawn_write_behind.
+///
+/// Dropping the handle leaves the task running. Call shutdown during graceful shutdown
+/// so the queued Sessions are written before the server exits.
+///
+/// # Examples
+/// ```rust ignore
+/// let write_behind = session_store.spawn_write_behind(Duration::milliseconds(250), 500);
+///
+/// // During graceful shutdown.
+/// write_behind.shutdown().await;
+/// ```
+///
+#[derive(Debug)]
+pub struct SessionWriteBehindHandle {
+    pub(crate) shutdown: Arc<Notify>,
+    pub(crate) task: JoinHandle<()>,
+}
+
+impl SessionWriteBehindHandle {
+    /// Stops queuing Sessions, writes the queued Sessions and waits for the task to finish.
+    pub async fn shutdown(self) {
+        self.shutdown.notify_one();
+
+        if let Err(err) = self.task.await {
+            tracing::error!("Session write behind task failed: {}", err);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use axum_core::response::{IntoResponse, Response};
+use http::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A Type that is stored within the Session under its own Key.
+///
+/// Used with AxumSession::get_typed, set_typed and remove_typed so the Key and Type
+/// can not be mismatched like they can with AxumSession::get.
+///
+/// The Key defaults to the Type's name including its module path, so moving or renaming the
+/// Type will lose any data already stored. Set KEY to keep it stable. Two Types using the same
+/// Key will overwrite each other.
+///
+/// # Examples
+/// ```rust
+/// use axum_database_sessions::SessionData;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct UserState {
+///     id: i64,
+/// }
+///
+/// impl SessionData for UserState {
+///     const KEY: &'static str = "user-state";
+/// }
+///
+/// assert_eq!(UserState::key(), "user-state");
+/// ```
+///
+pub trait SessionData: Serialize + DeserializeOwned {
+    /// The Key the Type is stored under. An empty Key uses the Type's name.
+    const KEY: &'static str = "";
+
+    /// Returns the Key the Type is stored under.
+    fn key() -> &'static str {
+        if Self::KEY.is_empty() {
+            std::any::type_name::<Self>()
+        } else {
+            Self::KEY
+        }
+    }
+
+    /// Returns the response used by [`crate::SessionValue`] when the Type is missing from the Session.
+    ///
+    /// Defaults to 401 Unauthorized. Override it to redirect to a login page instead.
+    fn rejection() -> Response {
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use axum::{extract::Path, routing::get, Router};
+use axum_database_sessions::{AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+use common::{Client, CountingPool};
+
+fn app(store: &AxumSessionStore<CountingPool>) -> Router {
+    Router::new()
+        .route(
+            "/set/:value",
+            get(
+                |session: AxumSession<CountingPool>, Path(value): Path<String>| async move {
+                    session.set("value", value).await;
+                    session.id()
+                },
+            ),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.get::<String>("value").await.unwrap_or_default()
+            }),
+        )
+        .route(
+            "/clear",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.clear_all().await;
+            }),
+        )
+        .layer(AxumSessionLayer::new(store.clone()))
+}
+
+fn stored(pool: &CountingPool, id: &str) -> String {
+    pool.rows.lock().unwrap()[id].session.clone()
+}
+
+#[tokio::test]
+async fn clear_all_only_clears_the_current_session() {
+    let pool = CountingPool::default();
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    let mut alice = Client::new(app(&store));
+    let mut bob = alice.fork();
+
+    let alice_id = alice.get("/set/alice-data").await.body;
+    let bob_id = bob.get("/set/bob-data").await.body;
+
+    // Bob keeps using his Session while Alice clears hers.
+    let (_, bob_value) = tokio::join!(alice.get("/clear"), bob.get("/get"));
+    assert_eq!(bob_value.body, "bob-data");
+
+    assert_eq!(pool.len(), 2);
+    assert!(!stored(&pool, &alice_id).contains("alice-data"));
+    assert!(stored(&pool, &bob_id).contains("bob-data"));
+
+    assert_eq!(alice.get("/get").await.body, "");
+    assert_eq!(bob.get("/get").await.body, "bob-data");
+
+    // Removing every Session is left to the explicitly named store method.
+    store.clear_store().await.unwrap();
+    assert_eq!(pool.len(), 0);
+}
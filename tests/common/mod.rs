@@ -0,0 +1,334 @@
+#![allow(dead_code)]
+
+use async_trait::async_trait;
+use axum::{body::Body, Router};
+use axum_database_sessions::{AxumDatabasePool, SessionError, SessionRecord};
+use chrono::Utc;
+use http::{
+    header::{COOKIE, SET_COOKIE},
+    Request, StatusCode,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use tower_service::Service;
+
+/// The parts of a response the tests check.
+#[derive(Debug)]
+pub struct TestResponse {
+    pub status: StatusCode,
+    /// Every Set-Cookie header as sent, including its attributes.
+    pub set_cookies: Vec<String>,
+    pub body: String,
+}
+
+impl TestResponse {
+    /// The Set-Cookie headers for the cookie called name.
+    pub fn set_cookies_named(&self, name: &str) -> Vec<&str> {
+        self.set_cookies
+            .iter()
+            .filter(|cookie| cookie.starts_with(&format!("{}=", name)))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// The value of the last Set-Cookie for the cookie called name.
+    pub fn cookie_value(&self, name: &str) -> Option<String> {
+        self.set_cookies_named(name)
+            .last()
+            .map(|cookie| pair(cookie).1.to_owned())
+    }
+}
+
+/// Splits the name=value pair from the front of a Set-Cookie header.
+pub fn pair(cookie: &str) -> (&str, &str) {
+    let pair = cookie.split(';').next().unwrap_or_default();
+
+    pair.split_once('=').unwrap_or((pair, ""))
+}
+
+/// Sends requests to a Router while keeping the cookies it sets, like a browser would.
+pub struct Client {
+    pub app: Router,
+    pub cookies: HashMap<String, String>,
+}
+
+impl Client {
+    pub fn new(app: Router) -> Self {
+        Self {
+            app,
+            cookies: HashMap::new(),
+        }
+    }
+
+    /// Makes a second client sharing the Router but none of the cookies.
+    pub fn fork(&self) -> Self {
+        Self::new(self.app.clone())
+    }
+
+    pub async fn get(&mut self, uri: &str) -> TestResponse {
+        let request = Request::builder()
+            .uri(uri)
+            .header(COOKIE, self.cookie_header())
+            .body(Body::empty())
+            .unwrap();
+
+        self.send(request).await
+    }
+
+    pub async fn send(&mut self, request: Request<Body>) -> TestResponse {
+        let response = send(&mut self.app, request).await;
+
+        for cookie in &response.set_cookies {
+            let (name, value) = pair(cookie);
+
+            if value.is_empty() || cookie.contains("Max-Age=0") {
+                self.cookies.remove(name);
+            } else {
+                self.cookies.insert(name.to_owned(), value.to_owned());
+            }
+        }
+
+        response
+    }
+
+    pub fn cookie_header(&self) -> String {
+        self.cookies
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Sends a single request without keeping any cookies.
+pub async fn send(app: &mut Router, request: Request<Body>) -> TestResponse {
+    let response = app.call(request).await.unwrap();
+    let status = response.status();
+    let set_cookies = response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .map(|value| value.to_str().unwrap().to_owned())
+        .collect();
+    let mut body = response.into_body();
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = http_body::Body::data(&mut body).await {
+        bytes.extend_from_slice(&chunk.unwrap());
+    }
+
+    TestResponse {
+        status,
+        set_cookies,
+        body: String::from_utf8(bytes).unwrap(),
+    }
+}
+
+/// A stored row of the CountingPool.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub session: String,
+    pub expires: i64,
+    pub user_id: Option<String>,
+}
+
+/// A database kept within memory that counts the writes made to it.
+#[derive(Debug, Clone, Default)]
+pub struct CountingPool {
+    pub rows: Arc<Mutex<HashMap<String, Row>>>,
+    pub stores: Arc<AtomicUsize>,
+    pub batches: Arc<AtomicUsize>,
+    /// The ids given to load in order.
+    pub loads: Arc<Mutex<Vec<String>>>,
+}
+
+impl CountingPool {
+    pub fn stores(&self) -> usize {
+        self.stores.load(Ordering::SeqCst)
+    }
+
+    pub fn batches(&self) -> usize {
+        self.batches.load(Ordering::SeqCst)
+    }
+
+    pub fn loads(&self) -> Vec<String> {
+        self.loads.lock().unwrap().clone()
+    }
+
+    /// Every write made to the database, counting a batch as one.
+    pub fn statements(&self) -> usize {
+        self.stores() + self.batches()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.rows.lock().unwrap().contains_key(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.lock().unwrap().len()
+    }
+
+    /// Inserts a row without counting it as a write.
+    pub fn insert(&self, id: &str, session: &str, expires: i64) {
+        self.rows.lock().unwrap().insert(
+            id.to_owned(),
+            Row {
+                session: session.to_owned(),
+                expires,
+                user_id: None,
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl AxumDatabasePool for CountingPool {
+    async fn initiate(&self, _table_name: &str) -> Result<(), SessionError> {
+        Ok(())
+    }
+
+    async fn count(&self, _table_name: &str) -> Result<i64, SessionError> {
+        let now = Utc::now().timestamp();
+
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|row| row.expires > now)
+            .count() as i64)
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        self.store_with_user(id, session, expires, None, table_name)
+            .await
+    }
+
+    async fn store_with_user(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        user_id: Option<&str>,
+        _table_name: &str,
+    ) -> Result<(), SessionError> {
+        self.stores.fetch_add(1, Ordering::SeqCst);
+        self.rows.lock().unwrap().insert(
+            id.to_owned(),
+            Row {
+                session: session.to_owned(),
+                expires,
+                user_id: user_id.map(str::to_owned),
+            },
+        );
+        Ok(())
+    }
+
+    async fn store_batch(
+        &self,
+        sessions: &[SessionRecord],
+        _table_name: &str,
+    ) -> Result<(), SessionError> {
+        self.batches.fetch_add(1, Ordering::SeqCst);
+        let mut rows = self.rows.lock().unwrap();
+
+        for record in sessions {
+            rows.insert(
+                record.id.clone(),
+                Row {
+                    session: record.session.clone(),
+                    expires: record.expires,
+                    user_id: record.user_id.clone(),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn load(&self, id: &str, _table_name: &str) -> Result<Option<String>, SessionError> {
+        self.loads.lock().unwrap().push(id.to_owned());
+        let now = Utc::now().timestamp();
+
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .get(id)
+            .filter(|row| row.expires > now)
+            .map(|row| row.session.clone()))
+    }
+
+    async fn load_by_user_id(
+        &self,
+        user_id: &str,
+        offset: i64,
+        limit: i64,
+        _table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let now = Utc::now().timestamp();
+        let rows = self.rows.lock().unwrap();
+        let mut ids: Vec<_> = rows
+            .iter()
+            .filter(|(_, row)| row.expires > now && row.user_id.as_deref() == Some(user_id))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        ids.sort();
+        Ok(ids
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .filter_map(|id| rows.get(&id).map(|row| row.session.clone()))
+            .collect())
+    }
+
+    async fn delete_one_by_id(&self, id: &str, _table_name: &str) -> Result<(), SessionError> {
+        self.rows.lock().unwrap().remove(id);
+        Ok(())
+    }
+
+    async fn delete_by_user_id(
+        &self,
+        user_id: &str,
+        _table_name: &str,
+    ) -> Result<Vec<String>, SessionError> {
+        let mut rows = self.rows.lock().unwrap();
+        let ids: Vec<_> = rows
+            .iter()
+            .filter(|(_, row)| row.user_id.as_deref() == Some(user_id))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in &ids {
+            rows.remove(id);
+        }
+
+        Ok(ids)
+    }
+
+    async fn delete_by_expiry(&self, _table_name: &str) -> Result<u64, SessionError> {
+        let now = Utc::now().timestamp();
+        let mut rows = self.rows.lock().unwrap();
+        let before = rows.len();
+
+        rows.retain(|_, row| row.expires > now);
+        Ok((before - rows.len()) as u64)
+    }
+
+    async fn delete_all(&self, _table_name: &str) -> Result<(), SessionError> {
+        self.rows.lock().unwrap().clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+use axum_database_sessions::{
+    AxumNullPool, AxumSessionConfig, AxumSessionStore, ExpiryPolicy, OverflowPolicy,
+};
+use chrono::Duration;
+
+/// Every invalid configuration paired with the problems it reports.
+fn invalid_configs() -> Vec<(AxumSessionConfig, Vec<&'static str>)> {
+    let default = AxumSessionConfig::default;
+    let too_long = Duration::days(365 * 200);
+
+    vec![
+        (
+            default().with_cookie_name(""),
+            vec!["cookie name \"\" must not be empty or contain whitespace or separators like ; and ="],
+        ),
+        (
+            default().with_cookie_name("my session"),
+            vec!["cookie name \"my session\" must not be empty or contain whitespace or separators like ; and ="],
+        ),
+        (
+            default().with_storable_cookie_name("accepted=1"),
+            vec!["cookie name \"accepted=1\" must not be empty or contain whitespace or separators like ; and ="],
+        ),
+        (
+            default().with_session_header(Some("X Session".into())),
+            vec!["header \"X Session\" is not a valid header name"],
+        ),
+        (
+            default().with_bearer_token(Some("X:Token".into())),
+            vec!["header \"X:Token\" is not a valid header name"],
+        ),
+        (
+            default().with_storable_cookie_name("sqlx_session"),
+            vec!["cookie name and storable cookie name must be different but are both \"sqlx_session\""],
+        ),
+        (
+            default().with_cookie_path("app"),
+            vec!["cookie path \"app\" must start with / and not contain ; or control characters"],
+        ),
+        (
+            default().with_cookie_path("/app;"),
+            vec!["cookie path \"/app;\" must start with / and not contain ; or control characters"],
+        ),
+        (
+            default().with_cookie_domain(Some("".into())),
+            vec!["cookie domain \"\" must not be empty or contain whitespace, ; or control characters"],
+        ),
+        (
+            default().with_cookie_domain(Some("example .com".into())),
+            vec!["cookie domain \"example .com\" must not be empty or contain whitespace, ; or control characters"],
+        ),
+        (
+            default().with_cookie_name("__Secure-id").with_secure(false),
+            vec!["cookie name \"__Secure-id\" requires with_secure(true)"],
+        ),
+        (
+            default().with_cookie_name("__Host-id").with_secure(false),
+            vec!["cookie name \"__Host-id\" requires with_secure(true)"],
+        ),
+        (
+            default().with_cookie_name("__Host-id").with_cookie_path("/app"),
+            vec!["cookie name \"__Host-id\" requires with_cookie_domain(None) and with_cookie_path(\"/\")"],
+        ),
+        (
+            default()
+                .with_cookie_name("__Host-id")
+                .with_cookie_domain(Some("example.com".into())),
+            vec!["cookie name \"__Host-id\" requires with_cookie_domain(None) and with_cookie_path(\"/\")"],
+        ),
+        (
+            default().with_max_age(Some(Duration::zero())),
+            vec!["cookie max age must be greater than 0 or None"],
+        ),
+        (
+            default().with_max_age(Some(too_long)),
+            vec!["cookie max age must not be longer than 100 years"],
+        ),
+        (
+            default().with_storable_max_age(Some(Duration::zero())),
+            vec!["cookie storable max age must be greater than 0 or None"],
+        ),
+        (
+            default().with_storable_max_age(Some(too_long)),
+            vec!["cookie storable max age must not be longer than 100 years"],
+        ),
+        (
+            default().with_lifetime(Duration::zero()),
+            vec!["lifetime must be greater than 0"],
+        ),
+        (
+            default().with_lifetime(too_long).with_max_lifetime(too_long),
+            vec![
+                "lifetime must not be longer than 100 years",
+                "max lifetime must not be longer than 100 years",
+            ],
+        ),
+        (
+            default().with_max_lifetime(Duration::zero()),
+            vec![
+                "max lifetime must be greater than 0",
+                "max lifetime must not be shorter than lifetime",
+            ],
+        ),
+        (
+            default().with_max_lifetime(Duration::hours(1)),
+            vec!["max lifetime must not be shorter than lifetime"],
+        ),
+        (
+            default().with_memory_lifetime(Duration::zero()),
+            vec!["memory lifetime must be greater than 0"],
+        ),
+        (
+            default().with_memory_lifetime(too_long),
+            vec!["memory lifetime must not be longer than 100 years"],
+        ),
+        (
+            default().with_shutdown_timeout(Duration::zero()),
+            vec!["shutdown timeout must be greater than 0"],
+        ),
+        (
+            default().with_shutdown_timeout(too_long),
+            vec!["shutdown timeout must not be longer than 100 years"],
+        ),
+        (
+            default().with_expiry_policy(ExpiryPolicy::Hybrid {
+                idle: Duration::zero(),
+                max: Duration::days(1),
+            }),
+            vec!["expiry policy idle must be greater than 0 and not longer than max"],
+        ),
+        (
+            default().with_expiry_policy(ExpiryPolicy::Hybrid {
+                idle: Duration::days(2),
+                max: Duration::days(1),
+            }),
+            vec!["expiry policy idle must be greater than 0 and not longer than max"],
+        ),
+        (
+            default().with_expiry_policy(ExpiryPolicy::Hybrid {
+                idle: Duration::days(1),
+                max: too_long,
+            }),
+            vec!["expiry policy max must not be longer than 100 years"],
+        ),
+        (
+            default().with_cookie_refresh_interval(Duration::zero()),
+            vec!["cookie refresh interval must be greater than 0"],
+        ),
+        (
+            default().with_touch_granularity(Duration::seconds(-1)),
+            vec!["touch granularity must not be negative"],
+        ),
+        (
+            default().with_idle_timeout(Some(Duration::zero())),
+            vec!["idle timeout must be greater than 0 or None"],
+        ),
+        (
+            default().with_idle_timeout(Some(too_long)),
+            vec!["idle timeout must not be longer than 100 years"],
+        ),
+        (
+            default().with_memory_max_sessions(Some(0)),
+            vec!["memory max sessions must be greater than 0 or None"],
+        ),
+        (
+            default().with_ip_prefix(33, 64),
+            vec!["ip prefix /33 and /64 must not be longer than /32 and /128"],
+        ),
+        (
+            default().with_ip_prefix(24, 129),
+            vec!["ip prefix /24 and /129 must not be longer than /32 and /128"],
+        ),
+        (
+            default().with_excluded_paths(["/health", "metrics"]),
+            vec!["excluded path \"metrics\" must start with /"],
+        ),
+        (
+            default().with_table_name("sessions-old"),
+            vec!["table name \"sessions-old\" may only contain [A-Za-z0-9_]"],
+        ),
+    ]
+}
+
+#[test]
+fn each_invalid_config_reports_its_problems() {
+    for (config, expected) in invalid_configs() {
+        let error = config.validate().unwrap_err();
+
+        assert_eq!(error.problems, expected);
+    }
+}
+
+#[cfg(any(
+    feature = "postgres-rustls",
+    feature = "postgres-native",
+    feature = "postgres-deadpool",
+    feature = "scylla-db"
+))]
+#[test]
+fn invalid_schema_names_are_reported() {
+    let error = AxumSessionConfig::default()
+        .with_schema_name("my schema")
+        .validate()
+        .unwrap_err();
+
+    assert_eq!(
+        error.problems,
+        vec!["table name \"my schema.async_sessions\" may only contain [A-Za-z0-9_]"]
+    );
+}
+
+#[test]
+fn try_new_returns_the_problems() {
+    for (config, expected) in invalid_configs() {
+        let error = AxumSessionStore::<AxumNullPool>::try_new(None, config).unwrap_err();
+
+        assert_eq!(error.problems, expected);
+    }
+
+    let error = AxumSessionStore::<AxumNullPool>::try_new_with_reader(
+        Some(AxumNullPool),
+        Some(AxumNullPool),
+        AxumSessionConfig::default().with_cookie_path("app"),
+    )
+    .unwrap_err();
+    assert_eq!(error.problems.len(), 1);
+}
+
+#[test]
+fn every_problem_is_reported_at_once() {
+    let error = AxumSessionConfig::default()
+        .with_cookie_name("")
+        .with_cookie_path("app")
+        .with_memory_max_sessions(Some(0))
+        .validate()
+        .unwrap_err();
+
+    assert_eq!(error.problems.len(), 3);
+    assert_eq!(
+        error.to_string(),
+        format!("Invalid AxumSessionConfig: {}", error.problems.join(", "))
+    );
+}
+
+#[test]
+fn valid_configs_are_accepted() {
+    assert!(AxumSessionConfig::default().validate().is_ok());
+    assert!(AxumSessionStore::<AxumNullPool>::try_new(None, AxumSessionConfig::default()).is_ok());
+    assert!(AxumSessionStore::<AxumNullPool>::try_new(
+        None,
+        AxumSessionConfig::default()
+            .with_cookie_name("__Host-id")
+            .with_max_sessions_per_user(0, OverflowPolicy::Reject),
+    )
+    .is_ok());
+}
+
+#[test]
+#[should_panic(expected = "Invalid AxumSessionConfig: cookie path \"app\" must start with /")]
+fn new_panics_on_an_invalid_config() {
+    let _ = AxumSessionStore::<AxumNullPool>::new(
+        None,
+        AxumSessionConfig::default().with_cookie_path("app"),
+    );
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, CookieSecurity, Key,
+};
+use common::{Client, CountingPool};
+
+fn app(pool: &CountingPool, config: AxumSessionConfig) -> Router {
+    let store = AxumSessionStore::new(Some(pool.clone()), config);
+
+    Router::new()
+        .route(
+            "/set",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("name", "secure").await;
+                session.id()
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<CountingPool>| async move {
+                let name = session.get::<String>("name").await.unwrap_or_default();
+                format!("{}:{}", session.id(), name)
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+/// Replaces the byte at index with another base64 and uuid safe character.
+fn flip(value: &str, index: usize) -> String {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes[index] = if bytes[index] == b'a' { b'b' } else { b'a' };
+    String::from_utf8(bytes).unwrap()
+}
+
+async fn tampered_cookie_starts_a_new_session(security: CookieSecurity) {
+    let pool = CountingPool::default();
+    let config = AxumSessionConfig::default()
+        .with_key(Key::generate())
+        .with_cookie_security(security);
+    let mut client = Client::new(app(&pool, config));
+
+    let id = client.get("/set").await.body;
+    let cookie = client.cookies["sqlx_session"].clone();
+    assert_eq!(client.get("/get").await.body, format!("{}:secure", id));
+
+    for index in [0, cookie.len() / 2, cookie.len() - 1] {
+        let mut forged = client.fork();
+        forged
+            .cookies
+            .insert("sqlx_session".to_owned(), flip(&cookie, index));
+        let loads = pool.loads().len();
+
+        let response = forged.get("/get").await;
+        let (new_id, name) = response.body.split_once(':').unwrap();
+
+        assert_ne!(new_id, id, "byte {} was not checked", index);
+        assert_eq!(name, "");
+        assert_ne!(forged.cookies["sqlx_session"], cookie);
+        // The forged value is never looked up within the database, only the new Session's id.
+        assert!(!pool.loads()[loads..].contains(&id));
+    }
+
+    // The real cookie still works.
+    assert_eq!(client.get("/get").await.body, format!("{}:secure", id));
+}
+
+#[tokio::test]
+async fn tampered_signed_cookie_starts_a_new_session() {
+    tampered_cookie_starts_a_new_session(CookieSecurity::Signed).await;
+}
+
+#[tokio::test]
+async fn tampered_private_cookie_starts_a_new_session() {
+    tampered_cookie_starts_a_new_session(CookieSecurity::Private).await;
+}
+
+#[tokio::test]
+async fn private_cookies_hide_the_id_and_round_trip() {
+    let pool = CountingPool::default();
+    let config = AxumSessionConfig::default()
+        .with_key(Key::generate())
+        .with_cookie_security(CookieSecurity::Private);
+    let mut client = Client::new(app(&pool, config));
+
+    let id = client.get("/set").await.body;
+    let cookie = client.cookies["sqlx_session"].clone();
+
+    // The Session ID never appears on the wire.
+    assert!(!cookie.contains(&id));
+    assert_ne!(client.cookies["session_acceptance"], "true");
+
+    // The cookie made by one request is read by the next.
+    assert_eq!(client.get("/get").await.body, format!("{}:secure", id));
+    assert_eq!(client.get("/get").await.body, format!("{}:secure", id));
+}
+
+#[tokio::test]
+async fn cookies_are_plain_without_a_key_or_with_none() {
+    let with_none = AxumSessionConfig::default()
+        .with_key(Key::generate())
+        .with_cookie_security(CookieSecurity::None);
+
+    for config in [AxumSessionConfig::default(), with_none] {
+        let pool = CountingPool::default();
+        let mut client = Client::new(app(&pool, config));
+
+        let id = client.get("/set").await.body;
+
+        assert_eq!(client.cookies["sqlx_session"], id);
+        assert_eq!(client.cookies["session_acceptance"], "true");
+        assert_eq!(client.get("/get").await.body, format!("{}:secure", id));
+    }
+}
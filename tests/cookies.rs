@@ -0,0 +1,154 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, SameSite,
+};
+use common::Client;
+
+fn app(config: AxumSessionConfig) -> Router {
+    let store = AxumSessionStore::<AxumNullPool>::new(None, config);
+
+    Router::new()
+        .route(
+            "/",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                session.get::<String>("name").await.unwrap_or_default()
+            }),
+        )
+        .route(
+            "/set",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                session.set("name", "cookie").await;
+            }),
+        )
+        .route(
+            "/renew",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                session.renew().await;
+            }),
+        )
+        .route(
+            "/destroy",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                session.destroy().await;
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test]
+async fn set_cookie_is_only_sent_when_it_changes() {
+    let mut client = Client::new(app(AxumSessionConfig::default()));
+    let mut sent = 0;
+
+    for uri in ["/", "/", "/set", "/", "/"] {
+        sent += client
+            .get(uri)
+            .await
+            .set_cookies_named("sqlx_session")
+            .len();
+    }
+
+    assert_eq!(sent, 1);
+}
+
+#[tokio::test]
+async fn renew_and_destroy_always_send_set_cookie() {
+    let mut client = Client::new(app(AxumSessionConfig::default()));
+
+    client.get("/set").await;
+    let id = client.cookies["sqlx_session"].clone();
+
+    let response = client.get("/renew").await;
+    assert_eq!(response.set_cookies_named("sqlx_session").len(), 1);
+    assert_ne!(client.cookies["sqlx_session"], id);
+
+    let response = client.get("/destroy").await;
+    assert_eq!(response.set_cookies_named("sqlx_session").len(), 1);
+    assert!(!client.cookies.contains_key("sqlx_session"));
+}
+
+/// The attributes of a Set-Cookie header after its name=value pair.
+fn attributes(cookie: &str) -> Vec<&str> {
+    cookie.split("; ").skip(1).collect()
+}
+
+#[tokio::test]
+async fn secure_and_http_only_apply_to_every_set_cookie() {
+    for (secure, http_only) in [(true, true), (true, false), (false, true), (false, false)] {
+        let config = AxumSessionConfig::default()
+            .with_secure(secure)
+            .with_http_only(http_only)
+            .with_cookie_refresh_interval(chrono::Duration::milliseconds(1));
+        let mut client = Client::new(app(config));
+
+        let created = client.get("/set").await;
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let refreshed = client.get("/").await;
+        let removed = client.get("/destroy").await;
+
+        for (stage, response) in [
+            ("created", created),
+            ("refreshed", refreshed),
+            ("removed", removed),
+        ] {
+            let cookies = response.set_cookies_named("sqlx_session");
+            assert_eq!(cookies.len(), 1, "{} sent no cookie", stage);
+
+            let attributes = attributes(cookies[0]);
+            assert_eq!(
+                attributes.contains(&"Secure"),
+                secure,
+                "{} secure {} http only {}: {:?}",
+                stage,
+                secure,
+                http_only,
+                attributes
+            );
+            assert_eq!(
+                attributes.contains(&"HttpOnly"),
+                http_only,
+                "{} secure {} http only {}: {:?}",
+                stage,
+                secure,
+                http_only,
+                attributes
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn same_site_applies_to_every_set_cookie() {
+    for (same_site, expected) in [
+        (SameSite::Strict, "SameSite=Strict"),
+        (SameSite::Lax, "SameSite=Lax"),
+        (SameSite::None, "SameSite=None"),
+    ] {
+        let config = AxumSessionConfig::default()
+            .with_secure(false)
+            .with_cookie_same_site(same_site);
+        let mut client = Client::new(app(config));
+
+        let created = client.get("/set").await;
+        let removed = client.get("/destroy").await;
+
+        for response in [created, removed] {
+            for name in ["sqlx_session", "session_acceptance"] {
+                let cookies = response.set_cookies_named(name);
+                assert_eq!(cookies.len(), 1, "{} was not sent", name);
+
+                let attributes = attributes(cookies[0]);
+                assert!(attributes.contains(&expected), "{:?}", attributes);
+                // Browsers reject SameSite=None without Secure so it is forced on.
+                assert_eq!(
+                    attributes.contains(&"Secure"),
+                    same_site == SameSite::None,
+                    "{:?}",
+                    attributes
+                );
+            }
+        }
+    }
+}
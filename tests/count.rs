@@ -0,0 +1,81 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore,
+};
+use chrono::Duration;
+use common::Client;
+
+#[tokio::test]
+async fn memory_count_skips_expired_sessions() {
+    let store = AxumSessionStore::<AxumNullPool>::new(None, AxumSessionConfig::default());
+    let app =
+        Router::new()
+            .route(
+                "/live",
+                get(|session: AxumSession<AxumNullPool>| async move {
+                    session.set("live", true).await;
+                }),
+            )
+            .route(
+                "/short",
+                get(|session: AxumSession<AxumNullPool>| async move {
+                    session.set_expiry(Duration::seconds(1)).await;
+                }),
+            )
+            .route(
+                "/count",
+                get(|session: AxumSession<AxumNullPool>| async move {
+                    session.count().await.to_string()
+                }),
+            )
+            .layer(AxumSessionLayer::new(store));
+    let mut client = Client::new(app);
+
+    client.get("/live").await;
+    for uri in ["/live", "/live", "/short", "/short"] {
+        client.fork().get(uri).await;
+    }
+
+    assert_eq!(client.get("/count").await.body, "5");
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    // The expired Sessions are still within memory until they are cleaned up but not counted.
+    assert_eq!(client.get("/count").await.body, "3");
+}
+
+#[cfg(any(feature = "sqlite-rustls", feature = "sqlite-native"))]
+#[tokio::test]
+async fn database_count_skips_expired_sessions() {
+    use axum_database_sessions::{AxumDatabasePool, AxumSqlitePool};
+    use chrono::Utc;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    let pool: AxumSqlitePool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap()
+        .into();
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    store.initiate().await.unwrap();
+
+    let table_name = "async_sessions";
+    let now = Utc::now().timestamp();
+
+    for (id, expires) in [
+        ("a", now + 60),
+        ("b", now + 60),
+        ("c", now - 60),
+        ("d", now - 1),
+    ] {
+        pool.store(id, "{}", expires, table_name).await.unwrap();
+    }
+
+    assert_eq!(store.count().await.unwrap(), 2);
+
+    // Cleanup agrees with the count.
+    pool.delete_by_expiry(table_name).await.unwrap();
+    assert_eq!(store.count().await.unwrap(), 2);
+}
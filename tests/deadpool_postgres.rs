@@ -0,0 +1,87 @@
+#![cfg(feature = "postgres-deadpool")]
+//! Runs against the Postgres at POSTGRES_URL, like `postgres://postgres@127.0.0.1/postgres`,
+//! and is skipped when it is not set.
+
+use axum_database_sessions::{AxumDatabasePool, AxumDeadpoolPgPool};
+use chrono::{Duration, Utc};
+use deadpool_postgres::{Manager, Pool};
+use tokio_postgres::NoTls;
+
+fn pool() -> Option<(Pool, AxumDeadpoolPgPool)> {
+    let url = std::env::var("POSTGRES_URL").ok()?;
+    let config: tokio_postgres::Config = url.parse().expect("invalid POSTGRES_URL");
+    let pool = Pool::builder(Manager::new(config, NoTls)).build().unwrap();
+
+    Some((pool.clone(), pool.into()))
+}
+
+fn table_name() -> String {
+    format!("test_{}", uuid::Uuid::new_v4().simple())
+}
+
+#[tokio::test]
+async fn initiate_widens_old_integer_expires_columns() {
+    let Some((raw, pool)) = pool() else { return };
+    let table = table_name();
+
+    // The layout used by older versions.
+    raw.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            r#"CREATE TABLE {} ("id" VARCHAR(128) NOT NULL PRIMARY KEY, "expires" INTEGER NULL, "session" TEXT NOT NULL)"#,
+            table
+        ))
+        .await
+        .unwrap();
+
+    let expires = (Utc::now() + Duration::days(365 * 50)).timestamp();
+    assert!(pool.store("old", "{}", expires, &table).await.is_err());
+
+    pool.initiate(&table).await.unwrap();
+    pool.store("new", "{}", expires, &table).await.unwrap();
+    assert_eq!(
+        pool.load("new", &table).await.unwrap().as_deref(),
+        Some("{}")
+    );
+    pool.initiate(&table).await.unwrap();
+
+    pool.delete_all(&table).await.unwrap();
+}
+
+#[tokio::test]
+async fn sessions_are_found_by_user_id() {
+    let Some((_, pool)) = pool() else { return };
+    let table = table_name();
+    let expires = (Utc::now() + Duration::hours(1)).timestamp();
+
+    pool.initiate(&table).await.unwrap();
+
+    for (id, user_id) in [
+        ("a", Some("42")),
+        ("b", Some("42")),
+        ("c", Some("7")),
+        ("d", None),
+    ] {
+        pool.store_with_user(id, id, expires, user_id, &table)
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(
+        pool.load_by_user_id("42", 0, 10, &table).await.unwrap(),
+        vec!["a", "b"]
+    );
+    assert_eq!(
+        pool.load_by_user_id("42", 1, 10, &table).await.unwrap(),
+        vec!["b"]
+    );
+    assert_eq!(pool.load_ids(1, 2, &table).await.unwrap(), vec!["b", "c"]);
+
+    let mut ids = pool.delete_by_user_id("42", &table).await.unwrap();
+    ids.sort();
+    assert_eq!(ids, vec!["a", "b"]);
+    assert_eq!(pool.count(&table).await.unwrap(), 2);
+
+    pool.delete_all(&table).await.unwrap();
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use async_trait::async_trait;
+use axum::{extract::Path, routing::get, Router};
+use axum_database_sessions::{
+    AxumDatabasePool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore,
+    SessionError,
+};
+use common::{Client, CountingPool};
+
+fn app<T: AxumDatabasePool + Clone + std::fmt::Debug + Sync + Send + 'static>(
+    store: &AxumSessionStore<T>,
+) -> Router {
+    Router::new()
+        .route(
+            "/login/:user",
+            get(
+                |session: AxumSession<T>, Path(user): Path<String>| async move {
+                    session.renew().await;
+                    session.set_store_user_id(&user).await;
+                    session.set("user", user).await;
+                    session.id()
+                },
+            ),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<T>| async move {
+                session.get::<String>("user").await.unwrap_or_default()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store.clone()))
+}
+
+#[tokio::test]
+async fn destroy_all_for_user_revokes_every_device() {
+    let pool = CountingPool::default();
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    let app = app(&store);
+
+    let mut devices: Vec<Client> = (0..3).map(|_| Client::new(app.clone())).collect();
+    let mut ids = Vec::new();
+
+    for device in &mut devices {
+        ids.push(device.get("/login/42").await.body);
+        assert_eq!(device.get("/get").await.body, "42");
+    }
+
+    let mut other = Client::new(app.clone());
+    let other_id = other.get("/login/7").await.body;
+
+    assert_eq!(store.destroy_all_for_user("42").await.unwrap(), 3);
+
+    for (device, id) in devices.iter_mut().zip(&ids) {
+        assert!(!pool.contains(id));
+        assert_eq!(device.get("/get").await.body, "");
+    }
+
+    // Other users keep their Sessions.
+    assert!(pool.contains(&other_id));
+    assert_eq!(other.get("/get").await.body, "7");
+    assert_eq!(store.destroy_all_for_user("42").await.unwrap(), 0);
+}
+
+/// A database that can not find Sessions by user id, like most of the backends.
+#[derive(Clone, Debug, Default)]
+struct NoUserIdPool(CountingPool);
+
+#[async_trait]
+impl AxumDatabasePool for NoUserIdPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), SessionError> {
+        self.0.initiate(table_name).await
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, SessionError> {
+        self.0.count(table_name).await
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), SessionError> {
+        self.0.store(id, session, expires, table_name).await
+    }
+
+    async fn load(&self, id: &str, table_name: &str) -> Result<Option<String>, SessionError> {
+        self.0.load(id, table_name).await
+    }
+
+    async fn delete_one_by_id(&self, id: &str, table_name: &str) -> Result<(), SessionError> {
+        self.0.delete_one_by_id(id, table_name).await
+    }
+
+    async fn delete_by_expiry(&self, table_name: &str) -> Result<u64, SessionError> {
+        self.0.delete_by_expiry(table_name).await
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), SessionError> {
+        self.0.delete_all(table_name).await
+    }
+}
+
+#[tokio::test]
+async fn unsupported_databases_still_lose_the_sessions_within_memory() {
+    let pool = NoUserIdPool::default();
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    let mut device = Client::new(app(&store));
+
+    let id = device.get("/login/42").await.body;
+    assert!(pool.0.contains(&id));
+
+    let error = store.destroy_all_for_user("42").await.unwrap_err();
+    assert!(
+        matches!(error, SessionError::GenericNotSupportedError(_)),
+        "{:?}",
+        error
+    );
+
+    // Another instance sharing the database can not load it either.
+    assert!(!pool.0.contains(&id));
+    let other = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    let mut other_device = Client::new(app(&other));
+    other_device.cookies = device.cookies.clone();
+    assert_eq!(other_device.get("/get").await.body, "");
+    assert_eq!(device.get("/get").await.body, "");
+}
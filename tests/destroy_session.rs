@@ -0,0 +1,185 @@
+mod common;
+
+use axum::{extract::Path, routing::get, Router};
+use axum_database_sessions::{
+    AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore,
+};
+use common::{Client, CountingPool};
+use std::sync::Arc;
+use tokio::sync::{Barrier, Notify};
+
+#[tokio::test]
+async fn destroy_wins_over_an_in_flight_request() {
+    let pool = CountingPool::default();
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    let started = Arc::new(Notify::new());
+    let resume = Arc::new(Notify::new());
+
+    let app = Router::new()
+        .route(
+            "/id",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("value", "first").await;
+                session.id()
+            }),
+        )
+        .route(
+            "/slow",
+            get({
+                let (started, resume) = (started.clone(), resume.clone());
+
+                move |session: AxumSession<CountingPool>| async move {
+                    started.notify_one();
+                    resume.notified().await;
+                    session.set("value", "after destroy").await;
+                }
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.get::<String>("value").await.unwrap_or_default()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store.clone()));
+    let mut client = Client::new(app);
+
+    let id = client.get("/id").await.body;
+    assert!(pool.contains(&id));
+
+    let mut slow = Client::new(client.app.clone());
+    slow.cookies = client.cookies.clone();
+    let request = tokio::spawn(async move { slow.get("/slow").await });
+
+    // Destroy the Session while the request still holds it.
+    started.notified().await;
+    assert!(store.destroy_session(&id).await.unwrap());
+    resume.notify_one();
+
+    let response = request.await.unwrap();
+    assert!(response.status.is_success());
+
+    // The end of request save does not bring the Session back.
+    assert!(!pool.contains(&id));
+    assert!(store.get_session(&id).await.unwrap().is_none());
+    assert_eq!(client.get("/get").await.body, "");
+    assert!(!store.destroy_session(&id).await.unwrap());
+}
+
+#[tokio::test]
+async fn concurrent_requests_keep_both_writes() {
+    let store = AxumSessionStore::<AxumNullPool>::new(None, AxumSessionConfig::default());
+    let barrier = Arc::new(Barrier::new(2));
+
+    let app = Router::new()
+        .route(
+            "/start",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                session.set("started", true).await;
+            }),
+        )
+        .route(
+            "/set/:key",
+            get({
+                let barrier = barrier.clone();
+
+                move |session: AxumSession<AxumNullPool>, Path(key): Path<String>| async move {
+                    // Both requests are within their handlers at the same time.
+                    barrier.wait().await;
+                    session.set(&key, &key).await;
+                }
+            }),
+        )
+        .route(
+            "/keys",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                let mut keys = session.keys().await;
+                keys.sort();
+                keys.join(",")
+            }),
+        )
+        .layer(AxumSessionLayer::new(store));
+    let mut client = Client::new(app);
+
+    client.get("/start").await;
+    let mut first = Client::new(client.app.clone());
+    let mut second = Client::new(client.app.clone());
+    first.cookies = client.cookies.clone();
+    second.cookies = client.cookies.clone();
+
+    tokio::join!(first.get("/set/one"), second.get("/set/two"));
+
+    assert_eq!(client.get("/keys").await.body, "one,started,two");
+}
+
+fn logout_app(pool: &CountingPool) -> Router {
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+
+    Router::new()
+        .route(
+            "/login",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("user", "alice").await;
+                session.id()
+            }),
+        )
+        .route(
+            "/logout",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.destroy().await;
+            }),
+        )
+        .route(
+            "/logout_with_notice",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.destroy().await;
+                session.set_flash("notice", "logged out").await;
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<CountingPool>| async move {
+                let user = session.get::<String>("user").await.unwrap_or_default();
+                let notice = session
+                    .take_flash::<String>("notice")
+                    .await
+                    .unwrap_or_default();
+                format!("{}:{}:{}", session.id(), user, notice)
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test]
+async fn destroy_removes_the_cookie() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(logout_app(&pool));
+
+    let id = client.get("/login").await.body;
+    let response = client.get("/logout").await;
+
+    let removal = response.set_cookies_named("sqlx_session");
+    assert_eq!(removal.len(), 1);
+    assert!(removal[0].contains("Max-Age=0"), "{}", removal[0]);
+    assert!(!pool.contains(&id));
+    assert!(!client.cookies.contains_key("sqlx_session"));
+}
+
+#[tokio::test]
+async fn values_set_after_destroy_continue_under_a_new_cookie() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(logout_app(&pool));
+
+    let id = client.get("/login").await.body;
+    let response = client.get("/logout_with_notice").await;
+
+    let new_id = response.cookie_value("sqlx_session").unwrap();
+    assert_ne!(new_id, id);
+    assert!(!pool.contains(&id));
+
+    // The old data is gone while the flash set after destroy() is kept.
+    assert_eq!(
+        client.get("/get").await.body,
+        format!("{}::logged out", new_id)
+    );
+}
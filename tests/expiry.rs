@@ -0,0 +1,98 @@
+mod common;
+
+use axum::{extract::Path, routing::get, Router};
+use axum_database_sessions::{AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+use chrono::{Duration, Utc};
+use common::{Client, CountingPool, TestResponse};
+
+fn app(store: &AxumSessionStore<CountingPool>) -> Router {
+    Router::new()
+        .route(
+            "/expiry/:secs",
+            get(
+                |session: AxumSession<CountingPool>, Path(secs): Path<i64>| async move {
+                    session.set_expiry(Duration::seconds(secs)).await;
+                },
+            ),
+        )
+        .route(
+            "/expiry_far",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set_expiry(Duration::days(1_000_000)).await;
+            }),
+        )
+        .route(
+            "/clear",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.clear_expiry().await;
+            }),
+        )
+        .route(
+            "/expires_in",
+            get(|session: AxumSession<CountingPool>| async move {
+                session
+                    .expires_in()
+                    .await
+                    .map_or(0, |left| left.num_seconds())
+                    .to_string()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store.clone()))
+}
+
+fn store(pool: &CountingPool) -> AxumSessionStore<CountingPool> {
+    let config = AxumSessionConfig::default().with_memory_lifetime(Duration::milliseconds(1));
+
+    AxumSessionStore::new(Some(pool.clone()), config)
+}
+
+fn max_age(response: &TestResponse) -> Option<i64> {
+    let cookie = response.set_cookies_named("sqlx_session").pop()?;
+
+    cookie
+        .split("; ")
+        .find_map(|attribute| attribute.strip_prefix("Max-Age="))
+        .map(|age| age.parse().unwrap())
+}
+
+#[tokio::test]
+async fn expiry_sets_the_cookie_and_database_expiration() {
+    let pool = CountingPool::default();
+    let store = store(&pool);
+    let mut client = Client::new(app(&store));
+
+    let response = client.get("/expiry/43200").await;
+    assert_eq!(max_age(&response), Some(43200));
+
+    let expires = pool.rows.lock().unwrap().values().next().unwrap().expires;
+    assert!((expires - (Utc::now() + Duration::hours(12)).timestamp()).abs() <= 5);
+
+    // The override survives being unloaded from memory and loaded from the database.
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    store.cleanup().await.unwrap();
+    let left: i64 = client.get("/expires_in").await.body.parse().unwrap();
+    assert!((43190..=43200).contains(&left));
+
+    // Clearing the override falls back to the configured lifespans.
+    let response = client.get("/clear").await;
+    assert_eq!(max_age(&response), Some(Duration::days(100).num_seconds()));
+    let left: i64 = client.get("/expires_in").await.body.parse().unwrap();
+    assert!((Duration::hours(6).num_seconds() - left).abs() <= 10);
+}
+
+#[tokio::test]
+async fn out_of_range_expiry_is_clamped() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&store(&pool)));
+
+    let response = client.get("/expiry/-1").await;
+    assert!(response.status.is_success());
+    assert_eq!(max_age(&response), Some(1));
+
+    let response = client.get("/expiry/0").await;
+    assert!(response.status.is_success());
+
+    let response = client.get("/expiry_far").await;
+    assert!(response.status.is_success());
+    assert_eq!(max_age(&response), Some(100 * 365 * 24 * 60 * 60));
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use axum_database_sessions::{AxumSessionConfig, AxumSessionStore, SessionError};
+use chrono::{Duration, Utc};
+use common::CountingPool;
+
+#[tokio::test]
+async fn export_fails_when_the_database_can_not_list_ids() {
+    let pool = CountingPool::default();
+    pool.insert("one", "{}", (Utc::now() + Duration::hours(1)).timestamp());
+    let store = AxumSessionStore::new(Some(pool), AxumSessionConfig::default());
+
+    let mut exported = Vec::new();
+    let error = store.export(&mut exported).await.unwrap_err();
+
+    assert!(
+        matches!(error, SessionError::GenericNotSupportedError(_)),
+        "{:?}",
+        error
+    );
+    // Nothing is written so a failed backup is never mistaken for an empty one.
+    assert!(exported.is_empty());
+
+    let error = store.ids(0, 10).await.unwrap_err();
+    assert!(
+        matches!(error, SessionError::GenericNotSupportedError(_)),
+        "{:?}",
+        error
+    );
+}
+
+#[cfg(any(feature = "sqlite-rustls", feature = "sqlite-native"))]
+#[tokio::test]
+async fn export_and_import_every_stored_session() {
+    use axum_database_sessions::AxumSqlitePool;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn store() -> AxumSessionStore<AxumSqlitePool> {
+        let pool: AxumSqlitePool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+            .into();
+        let store = AxumSessionStore::new(Some(pool), AxumSessionConfig::default());
+
+        store.initiate().await.unwrap();
+        store
+    }
+
+    let old_store = store().await;
+    let line = |id: usize| {
+        format!(
+            r#"{{"id":"{}","data":{{"count":"{}"}},"expires":"2999-01-01T00:00:00Z","autoremove":"2999-01-01T00:00:00Z","destroy":false,"longterm":false,"storable":true,"update":false}}"#,
+            uuid::Uuid::new_v4(),
+            id
+        )
+    };
+    // More than one page of IDs.
+    let backup: String =
+        std::iter::once(r#"{"format":"axum_database_sessions","version":1}"#.to_owned())
+            .chain((0..1500).map(line))
+            .map(|line| line + "\n")
+            .collect();
+
+    assert_eq!(old_store.import(backup.as_bytes()).await.unwrap(), 1500);
+    assert_eq!(old_store.ids(0, 2000).await.unwrap().len(), 1500);
+
+    let mut exported = Vec::new();
+    assert_eq!(old_store.export(&mut exported).await.unwrap(), 1500);
+
+    let new_store = store().await;
+    assert_eq!(new_store.import(exported.as_slice()).await.unwrap(), 1500);
+    assert_eq!(new_store.count().await.unwrap(), 1500);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use axum::{response::Redirect, routing::get, Router};
+use axum_database_sessions::{AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+use common::{Client, CountingPool};
+use http::StatusCode;
+
+fn app(pool: &CountingPool) -> Router {
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+
+    Router::new()
+        .route(
+            "/save",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set_flash("notice", "Your profile was saved").await;
+                Redirect::to("/show")
+            }),
+        )
+        .route(
+            "/show",
+            get(|session: AxumSession<CountingPool>| async move {
+                session
+                    .take_flash::<String>("notice")
+                    .await
+                    .unwrap_or_default()
+            }),
+        )
+        .route("/other", get(|_: AxumSession<CountingPool>| async move {}))
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test]
+async fn flash_is_read_once_after_a_redirect() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool));
+
+    let response = client.get("/save").await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+
+    assert_eq!(client.get("/show").await.body, "Your profile was saved");
+    assert_eq!(client.get("/show").await.body, "");
+}
+
+#[tokio::test]
+async fn flash_not_taken_is_dropped_after_the_next_request() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool));
+
+    client.get("/save").await;
+    client.get("/other").await;
+
+    assert_eq!(client.get("/show").await.body, "");
+}
+
+#[tokio::test]
+async fn flash_is_stored_within_the_database() {
+    let pool = CountingPool::default();
+    let mut first = Client::new(app(&pool));
+    let mut second = Client::new(app(&pool));
+
+    first.get("/save").await;
+    second.cookies = first.cookies.clone();
+
+    // Another server reads the flash set by the redirecting one.
+    assert_eq!(second.get("/show").await.body, "Your profile was saved");
+    assert_eq!(second.get("/show").await.body, "");
+}
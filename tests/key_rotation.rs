@@ -0,0 +1,70 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, CookieSecurity, Key,
+};
+use common::{Client, CountingPool};
+
+fn app(pool: &CountingPool, config: AxumSessionConfig) -> Router {
+    let store = AxumSessionStore::new(Some(pool.clone()), config);
+
+    Router::new()
+        .route(
+            "/set",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("name", "rotated").await;
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.get::<String>("name").await.unwrap_or_default()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+async fn rotation(security: CookieSecurity) {
+    let pool = CountingPool::default();
+    let old_key = Key::generate();
+    let new_key = Key::generate();
+    let config = AxumSessionConfig::default().with_cookie_security(security);
+
+    let mut old = Client::new(app(&pool, config.clone().with_key(old_key.clone())));
+    old.get("/set").await;
+
+    // The server now uses the new key and still accepts the old one.
+    let mut rotated = Client::new(app(
+        &pool,
+        config
+            .clone()
+            .with_key(new_key.clone())
+            .with_secondary_keys(vec![old_key]),
+    ));
+    rotated.cookies = old.cookies.clone();
+
+    let response = rotated.get("/get").await;
+    assert_eq!(response.body, "rotated");
+    assert_eq!(response.set_cookies_named("sqlx_session").len(), 1);
+
+    // The re-issued cookie is only sent once.
+    let response = rotated.get("/get").await;
+    assert_eq!(response.body, "rotated");
+    assert!(response.set_cookies.is_empty());
+
+    // The re-issued cookie uses the new key so the old key can be removed.
+    let mut current = Client::new(app(&pool, config.with_key(new_key)));
+    current.cookies = rotated.cookies.clone();
+    assert_eq!(current.get("/get").await.body, "rotated");
+}
+
+#[tokio::test]
+async fn private_cookies_move_to_the_primary_key() {
+    rotation(CookieSecurity::Private).await;
+}
+
+#[tokio::test]
+async fn signed_cookies_move_to_the_primary_key() {
+    rotation(CookieSecurity::Signed).await;
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, OverflowPolicy,
+};
+use common::{Client, CountingPool};
+
+fn app(pool: &CountingPool, max: usize, policy: OverflowPolicy) -> Router {
+    let config = AxumSessionConfig::default().with_max_sessions_per_user(max, policy);
+    let store = AxumSessionStore::new(Some(pool.clone()), config);
+
+    Router::new()
+        .route(
+            "/login",
+            get(|session: AxumSession<CountingPool>| async move {
+                match session.try_set_store_user_id("42").await {
+                    Ok(()) => "ok".to_owned(),
+                    Err(err) => err.to_string(),
+                }
+            }),
+        )
+        .route(
+            "/user",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.store_user_id().await.unwrap_or_default()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test]
+async fn a_max_of_zero_allows_one_session() {
+    let pool = CountingPool::default();
+    let app = app(&pool, 0, OverflowPolicy::DestroyOldest);
+    let mut first = Client::new(app.clone());
+    let mut second = Client::new(app);
+
+    assert_eq!(first.get("/login").await.body, "ok");
+    assert_eq!(second.get("/login").await.body, "ok");
+
+    // The second login replaced the first device's Session.
+    assert_eq!(first.get("/user").await.body, "");
+    assert_eq!(second.get("/user").await.body, "42");
+}
+
+#[tokio::test]
+async fn reject_keeps_the_existing_sessions() {
+    let pool = CountingPool::default();
+    let app = app(&pool, 1, OverflowPolicy::Reject);
+    let mut first = Client::new(app.clone());
+    let mut second = Client::new(app);
+
+    assert_eq!(first.get("/login").await.body, "ok");
+    assert_ne!(second.get("/login").await.body, "ok");
+
+    assert_eq!(first.get("/user").await.body, "42");
+    assert_eq!(second.get("/user").await.body, "");
+}
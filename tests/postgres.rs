@@ -0,0 +1,111 @@
+#![cfg(any(feature = "postgres-rustls", feature = "postgres-native"))]
+//! Runs against the Postgres at POSTGRES_URL, like `postgres://postgres@127.0.0.1/postgres`,
+//! and is skipped when it is not set.
+
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumDatabasePool, AxumPgPool, AxumSession, AxumSessionConfig, AxumSessionLayer,
+    AxumSessionStore,
+};
+use chrono::{Duration, Utc};
+use common::Client;
+
+async fn pool() -> Option<(sqlx::PgPool, AxumPgPool)> {
+    let url = std::env::var("POSTGRES_URL").ok()?;
+    let pool = sqlx::PgPool::connect(&url)
+        .await
+        .expect("failed to connect to POSTGRES_URL");
+
+    Some((pool.clone(), pool.into()))
+}
+
+fn table_name() -> String {
+    format!("test_{}", uuid::Uuid::new_v4().simple())
+}
+
+fn app(store: &AxumSessionStore<AxumPgPool>) -> Router {
+    Router::new()
+        .route(
+            "/remember",
+            get(|session: AxumSession<AxumPgPool>| async move {
+                session.set("name", "alice").await;
+                session.set_remember(Some(Duration::days(365 * 20))).await;
+                session.id()
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<AxumPgPool>| async move {
+                session.get::<String>("name").await.unwrap_or_default()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store.clone()))
+}
+
+async fn store(pool: &AxumPgPool, table: &str) -> AxumSessionStore<AxumPgPool> {
+    let config = AxumSessionConfig::default().with_table_name(table.to_owned());
+    let store = AxumSessionStore::new(Some(pool.clone()), config);
+
+    store.initiate().await.unwrap();
+    store
+}
+
+#[tokio::test]
+async fn long_remember_me_sessions_are_stored() {
+    let Some((_, pool)) = pool().await else {
+        return;
+    };
+    let table = table_name();
+    let mut client = Client::new(app(&store(&pool, &table).await));
+
+    let response = client.get("/remember").await;
+    assert!(response.status.is_success());
+
+    // The expiration is past 2038 so it only fits within a 64 bit column.
+    let other = store(&pool, &table).await;
+    let stored = other.get_session(&response.body).await.unwrap().unwrap();
+    assert!(stored.expires > Utc::now() + Duration::days(365 * 19));
+
+    // Another instance loads it from the database.
+    let mut other_client = Client::new(app(&other));
+    other_client.cookies = client.cookies.clone();
+    assert_eq!(other_client.get("/get").await.body, "alice");
+
+    pool.delete_all(&table).await.unwrap();
+}
+
+#[tokio::test]
+async fn initiate_widens_old_integer_expires_columns() {
+    let Some((raw, pool)) = pool().await else {
+        return;
+    };
+    let table = table_name();
+
+    // The layout used by older versions.
+    sqlx::query(&format!(
+        r#"CREATE TABLE {} ("id" VARCHAR(128) NOT NULL PRIMARY KEY, "expires" INTEGER NULL, "session" TEXT NOT NULL)"#,
+        table
+    ))
+    .execute(&raw)
+    .await
+    .unwrap();
+
+    let expires = (Utc::now() + Duration::days(365 * 50)).timestamp();
+    assert!(pool.store("old", "{}", expires, &table).await.is_err());
+
+    pool.initiate(&table).await.unwrap();
+    pool.store("new", "{}", expires, &table).await.unwrap();
+    assert_eq!(
+        pool.load("new", &table).await.unwrap().as_deref(),
+        Some("{}")
+    );
+    // Running it again leaves the widened column alone.
+    pool.initiate(&table).await.unwrap();
+
+    sqlx::query(&format!("DROP TABLE {}", table))
+        .execute(&raw)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, ReadOnlySession,
+};
+use common::{Client, CountingPool};
+
+fn app(pool: &CountingPool) -> Router {
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+
+    Router::new()
+        .route(
+            "/set",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("name", "alice").await;
+                session.set_flash("notice", "welcome").await;
+            }),
+        )
+        .route(
+            "/read_only",
+            get(|session: ReadOnlySession<CountingPool>| async move {
+                session.get::<String>("name").await.unwrap_or_default()
+            }),
+        )
+        .route(
+            "/flash",
+            get(|session: AxumSession<CountingPool>| async move {
+                session
+                    .take_flash::<String>("notice")
+                    .await
+                    .unwrap_or_default()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test]
+async fn read_only_requests_never_store_the_session() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool));
+
+    client.get("/set").await;
+    let statements = pool.statements();
+
+    for _ in 0..5 {
+        let response = client.get("/read_only").await;
+        assert_eq!(response.body, "alice");
+        assert!(
+            response.set_cookies.is_empty(),
+            "{:?}",
+            response.set_cookies
+        );
+    }
+
+    assert_eq!(pool.statements(), statements);
+
+    // The flash value set before is kept for the next full request.
+    assert_eq!(client.get("/flash").await.body, "welcome");
+}
+
+#[tokio::test]
+async fn unchanged_sessions_are_not_stored() {
+    let pool = CountingPool::default();
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    let app = Router::new()
+        .route(
+            "/set",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("name", "alice").await;
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.get::<String>("name").await.unwrap_or_default()
+            }),
+        )
+        .route(
+            "/same",
+            get(|session: AxumSession<CountingPool>| async move {
+                // Setting what is already stored does not mark the Session as changed.
+                session.set("name", "alice").await;
+                session.set_longterm(false).await;
+                session.clear_expiry().await;
+            }),
+        )
+        .layer(AxumSessionLayer::new(store));
+    let mut client = Client::new(app);
+
+    client.get("/set").await;
+    let stores = pool.stores();
+
+    assert_eq!(client.get("/get").await.body, "alice");
+    client.get("/same").await;
+    assert_eq!(pool.stores(), stores);
+
+    client.get("/set").await;
+    assert_eq!(pool.stores(), stores);
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "redis")]
+//! Runs against the Redis server at REDIS_URL, like `redis://127.0.0.1/`, and is skipped when it is not set.
+
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumDatabasePool, AxumRedisPool, AxumSession, AxumSessionConfig, AxumSessionLayer,
+    AxumSessionStore, SessionError,
+};
+use chrono::{Duration, Utc};
+use common::Client;
+
+fn pool() -> Option<AxumRedisPool> {
+    let url = std::env::var("REDIS_URL").ok()?;
+
+    Some(
+        redis::Client::open(url)
+            .expect("REDIS_URL is not a valid url")
+            .into(),
+    )
+}
+
+fn table_name() -> String {
+    format!("test_{}", uuid::Uuid::new_v4().simple())
+}
+
+#[tokio::test]
+async fn store_load_delete() {
+    let Some(pool) = pool() else { return };
+    let table = table_name();
+    let other = table_name();
+    let expires = (Utc::now() + Duration::hours(1)).timestamp();
+
+    pool.store("one", "first", expires, &table).await.unwrap();
+    pool.store("two", "second", expires, &table).await.unwrap();
+    pool.store("one", "other", expires, &other).await.unwrap();
+
+    assert_eq!(
+        pool.load("one", &table).await.unwrap().as_deref(),
+        Some("first")
+    );
+    assert_eq!(
+        pool.load("one", &other).await.unwrap().as_deref(),
+        Some("other")
+    );
+    assert_eq!(pool.count(&table).await.unwrap(), 2);
+
+    pool.delete_one_by_id("one", &table).await.unwrap();
+    assert_eq!(pool.load("one", &table).await.unwrap(), None);
+    assert_eq!(pool.count(&table).await.unwrap(), 1);
+
+    // delete_all only removes the keys using the table name prefix.
+    pool.delete_all(&table).await.unwrap();
+    assert_eq!(pool.count(&table).await.unwrap(), 0);
+    assert_eq!(pool.count(&other).await.unwrap(), 1);
+
+    pool.delete_all(&other).await.unwrap();
+}
+
+#[tokio::test]
+async fn expires_using_redis() {
+    let Some(pool) = pool() else { return };
+    let table = table_name();
+    let expired = (Utc::now() - Duration::seconds(1)).timestamp();
+
+    pool.store("old", "expired", expired, &table).await.unwrap();
+
+    assert_eq!(pool.load("old", &table).await.unwrap(), None);
+    assert_eq!(pool.delete_by_expiry(&table).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn session_round_trip() {
+    let Some(pool) = pool() else { return };
+    let config = AxumSessionConfig::default()
+        .with_table_name(table_name())
+        .with_memory_lifetime(Duration::milliseconds(1));
+    let store = AxumSessionStore::new(Some(pool), config);
+    store.initiate().await.unwrap();
+
+    let app = Router::new()
+        .route(
+            "/set",
+            get(|session: AxumSession<AxumRedisPool>| async move {
+                session.set("name", "redis").await;
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<AxumRedisPool>| async move {
+                session.get::<String>("name").await.unwrap_or_default()
+            }),
+        )
+        .route(
+            "/id",
+            get(|session: AxumSession<AxumRedisPool>| async move { session.id() }),
+        )
+        .route(
+            "/destroy",
+            get(|session: AxumSession<AxumRedisPool>| async move {
+                session.destroy().await;
+            }),
+        )
+        .layer(AxumSessionLayer::new(store.clone()));
+    let mut client = Client::new(app);
+
+    client.get("/set").await;
+    let id = client.get("/id").await.body;
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    store.cleanup().await.unwrap();
+
+    // The session comes back from Redis once memory let go of it.
+    assert_eq!(client.get("/get").await.body, "redis");
+    assert_eq!(store.count().await.unwrap(), 1);
+
+    client.get("/destroy").await;
+    assert!(store.get_session(&id).await.unwrap().is_none());
+    assert_eq!(client.get("/get").await.body, "");
+
+    store.clear_store().await.unwrap();
+}
+
+#[tokio::test]
+async fn lost_connection_is_an_error() {
+    // Nothing listens on the port so every call fails to connect.
+    let pool: AxumRedisPool = redis::Client::open("redis://127.0.0.1:1/").unwrap().into();
+    let expires = (Utc::now() + Duration::hours(1)).timestamp();
+
+    assert!(matches!(
+        pool.store("one", "first", expires, "sessions").await,
+        Err(SessionError::Redis(_))
+    ));
+    assert!(matches!(
+        pool.load("one", "sessions").await,
+        Err(SessionError::Redis(_))
+    ));
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "redis-clusterdb")]
+//! Runs against the Redis cluster nodes within REDIS_CLUSTER_URLS, a comma separated list like
+//! `redis://127.0.0.1:7000/,redis://127.0.0.1:7001/`, and is skipped when it is not set.
+
+use axum_database_sessions::{AxumDatabasePool, AxumRedisClusterPool, SessionError};
+use chrono::{Duration, Utc};
+use redis::cluster::ClusterClient;
+
+fn pool() -> Option<AxumRedisClusterPool> {
+    let urls = std::env::var("REDIS_CLUSTER_URLS").ok()?;
+    let nodes: Vec<_> = urls.split(',').map(str::trim).collect();
+
+    Some(
+        ClusterClient::new(nodes)
+            .expect("REDIS_CLUSTER_URLS are not valid urls")
+            .into(),
+    )
+}
+
+fn table_name() -> String {
+    format!("test_{}", uuid::Uuid::new_v4().simple())
+}
+
+#[tokio::test]
+async fn store_load_delete() {
+    let Some(pool) = pool() else { return };
+    let table = table_name();
+    let expires = (Utc::now() + Duration::hours(1)).timestamp();
+
+    pool.store("one", "first", expires, &table).await.unwrap();
+    pool.store("two", "second", expires, &table).await.unwrap();
+
+    assert_eq!(
+        pool.load("one", &table).await.unwrap().as_deref(),
+        Some("first")
+    );
+    assert_eq!(pool.count(&table).await.unwrap(), 2);
+
+    pool.delete_one_by_id("one", &table).await.unwrap();
+    assert_eq!(pool.load("one", &table).await.unwrap(), None);
+    assert_eq!(pool.count(&table).await.unwrap(), 1);
+
+    pool.delete_all(&table).await.unwrap();
+    assert_eq!(pool.count(&table).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn expired_sessions_leave_the_index() {
+    let Some(pool) = pool() else { return };
+    let table = table_name();
+    let expired = (Utc::now() - Duration::seconds(1)).timestamp();
+    let expires = (Utc::now() + Duration::hours(1)).timestamp();
+
+    pool.store("old", "expired", expired, &table).await.unwrap();
+    pool.store("new", "active", expires, &table).await.unwrap();
+
+    assert_eq!(pool.load("old", &table).await.unwrap(), None);
+    assert_eq!(pool.count(&table).await.unwrap(), 1);
+    assert_eq!(pool.delete_by_expiry(&table).await.unwrap(), 1);
+
+    pool.delete_all(&table).await.unwrap();
+}
+
+#[tokio::test]
+async fn lost_connection_is_an_error() {
+    // Nothing listens on the port so every call fails to connect.
+    let pool: AxumRedisClusterPool = ClusterClient::new(vec!["redis://127.0.0.1:1/"])
+        .unwrap()
+        .into();
+    let expires = (Utc::now() + Duration::hours(1)).timestamp();
+
+    assert!(matches!(
+        pool.store("one", "first", expires, "sessions").await,
+        Err(SessionError::Redis(_))
+    ));
+    assert!(matches!(
+        pool.load("one", "sessions").await,
+        Err(SessionError::Redis(_))
+    ));
+}
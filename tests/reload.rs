@@ -0,0 +1,67 @@
+mod common;
+
+use axum::{extract::Path, routing::get, Router};
+use axum_database_sessions::{AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+use common::{Client, CountingPool};
+
+/// One server sharing the database with the others.
+fn app(pool: &CountingPool) -> Router {
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+
+    Router::new()
+        .route(
+            "/set/:value",
+            get(
+                |session: AxumSession<CountingPool>, Path(value): Path<String>| async move {
+                    session.set("value", value).await;
+                    session.id()
+                },
+            ),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.get::<String>("value").await.unwrap_or_default()
+            }),
+        )
+        .route(
+            "/reload",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.reload().await.unwrap();
+                session.get::<String>("value").await.unwrap_or_default()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test]
+async fn reload_sees_changes_from_another_server() {
+    let pool = CountingPool::default();
+    let mut first = Client::new(app(&pool));
+    let mut second = Client::new(app(&pool));
+
+    first.get("/set/first").await;
+    second.cookies = first.cookies.clone();
+    second.get("/set/second").await;
+
+    // The first server still has its own copy within memory until it reloads.
+    assert_eq!(first.get("/get").await.body, "first");
+    assert_eq!(first.get("/reload").await.body, "second");
+    assert_eq!(first.get("/get").await.body, "second");
+}
+
+#[tokio::test]
+async fn reload_clears_a_session_removed_from_the_database() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool));
+
+    let id = client.get("/set/value").await.body;
+    pool.rows.lock().unwrap().remove(&id);
+
+    assert_eq!(client.get("/reload").await.body, "");
+    assert_eq!(client.get("/get").await.body, "");
+
+    // The Session continues as a new empty Session.
+    assert_eq!(client.get("/set/again").await.body, id);
+    assert_eq!(client.get("/get").await.body, "again");
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use axum::{extract::Path, routing::get, Router};
+use axum_database_sessions::{AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+use chrono::{Duration, Utc};
+use common::{Client, CountingPool, TestResponse};
+
+fn app(pool: &CountingPool) -> Router {
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+
+    Router::new()
+        .route(
+            "/remember/:secs",
+            get(
+                |session: AxumSession<CountingPool>, Path(secs): Path<i64>| async move {
+                    session.set_remember(Some(Duration::seconds(secs))).await;
+                },
+            ),
+        )
+        .route(
+            "/forget",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set_remember(None).await;
+            }),
+        )
+        .route(
+            "/longterm",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set_longterm(true).await;
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+fn max_age(response: &TestResponse) -> Option<i64> {
+    let cookie = response.set_cookies_named("sqlx_session").pop()?;
+
+    cookie
+        .split("; ")
+        .find_map(|attribute| attribute.strip_prefix("Max-Age="))
+        .map(|age| age.parse().unwrap())
+}
+
+/// Seconds from now until the only Session within the database expires.
+fn database_expires_in(pool: &CountingPool) -> i64 {
+    pool.rows.lock().unwrap().values().next().unwrap().expires - Utc::now().timestamp()
+}
+
+#[tokio::test]
+async fn remember_sets_the_cookie_and_database_expiration() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool));
+    let week = Duration::days(7).num_seconds();
+
+    let response = client.get(&format!("/remember/{}", week)).await;
+    assert_eq!(max_age(&response), Some(week));
+    assert!((week - database_expires_in(&pool)).abs() <= 5);
+
+    // set_longterm uses the configured max lifetime instead.
+    client.get("/longterm").await;
+    let max_lifetime = Duration::days(60).num_seconds();
+    assert!((max_lifetime - database_expires_in(&pool)).abs() <= 5);
+
+    // Clearing it reverts to the standard lifetime.
+    client.get(&format!("/remember/{}", week)).await;
+    let response = client.get("/forget").await;
+    assert_eq!(max_age(&response), Some(Duration::days(100).num_seconds()));
+    let lifetime = Duration::hours(6).num_seconds();
+    assert!((lifetime - database_expires_in(&pool)).abs() <= 5);
+}
+
+#[tokio::test]
+async fn out_of_range_remember_is_clamped() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool));
+
+    let response = client.get("/remember/-5").await;
+    assert!(response.status.is_success());
+    assert_eq!(max_age(&response), Some(1));
+
+    let response = client.get(&format!("/remember/{}", i64::MAX / 1000)).await;
+    assert!(response.status.is_success());
+    assert_eq!(max_age(&response), Some(100 * 365 * 24 * 60 * 60));
+}
+
+#[test]
+fn config_rejects_out_of_range_lifetimes() {
+    for lifetime in [Duration::seconds(-1), Duration::days(365 * 200)] {
+        let error = AxumSessionConfig::default()
+            .with_max_lifetime(lifetime)
+            .validate()
+            .unwrap_err();
+
+        assert!(
+            error
+                .problems
+                .iter()
+                .any(|problem| problem.starts_with("max lifetime")),
+            "{:?}",
+            error.problems
+        );
+    }
+
+    let error = AxumSessionConfig::default()
+        .with_max_age(Some(Duration::days(365 * 200)))
+        .validate()
+        .unwrap_err();
+    assert_eq!(
+        error.problems,
+        vec!["cookie max age must not be longer than 100 years".to_owned()]
+    );
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+use common::{Client, CountingPool};
+
+fn app(pool: &CountingPool) -> Router {
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+
+    Router::new()
+        .route(
+            "/login",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("name", "alice").await;
+                session.id()
+            }),
+        )
+        .route(
+            "/renew",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.renew().await;
+                session.id()
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<CountingPool>| async move {
+                let name = session.get::<String>("name").await.unwrap_or_default();
+                format!("{}:{}", session.id(), name)
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test]
+async fn renew_moves_the_data_to_a_new_id() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool));
+
+    let old_id = client.get("/login").await.body;
+    let old_cookies = client.cookies.clone();
+
+    let response = client.get("/renew").await;
+    let new_id = response.body.clone();
+    assert_ne!(new_id, old_id);
+    assert_eq!(response.cookie_value("sqlx_session"), Some(new_id.clone()));
+
+    // The data survives under the new id.
+    assert_eq!(client.get("/get").await.body, format!("{}:alice", new_id));
+    assert!(pool.contains(&new_id));
+    assert!(!pool.contains(&old_id));
+
+    // The old cookie no longer resolves to the data and only starts a new empty Session.
+    let mut attacker = client.fork();
+    attacker.cookies = old_cookies;
+    let body = attacker.get("/get").await.body;
+    let (id, name) = body.split_once(':').unwrap();
+    assert_ne!(id, new_id);
+    assert_eq!(name, "");
+}
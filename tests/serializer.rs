@@ -0,0 +1,166 @@
+#![cfg(any(feature = "bincode-serializer", feature = "msgpack-serializer"))]
+
+mod common;
+
+use axum::{body::Body, extract::ConnectInfo, routing::get, Router};
+use axum_database_sessions::{
+    AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, IpBinding, SessionError,
+    SessionSerializer,
+};
+use common::{Client, CountingPool, TestResponse};
+use http::{header::COOKIE, Request};
+use std::net::SocketAddr;
+
+const AVATAR: [u8; 6] = [0, 159, 146, 150, 10, 255];
+
+fn store(pool: &CountingPool, serializer: SessionSerializer) -> AxumSessionStore<CountingPool> {
+    let config = AxumSessionConfig::default()
+        .with_serializer(serializer)
+        .with_ip_binding(IpBinding::Reject);
+
+    AxumSessionStore::new(Some(pool.clone()), config)
+}
+
+fn app(pool: &CountingPool, serializer: SessionSerializer) -> Router {
+    Router::new()
+        .route(
+            "/save",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("name", "Ferris").await;
+                session.set_bytes("avatar", AVATAR.to_vec()).await;
+                session.set_flash("notice", "Your profile was saved").await;
+            }),
+        )
+        .route(
+            "/show",
+            get(|session: AxumSession<CountingPool>| async move {
+                format!(
+                    "{:?} {:?} {:?}",
+                    session.get::<String>("name").await,
+                    session.get_bytes("avatar").await,
+                    session.take_flash::<String>("notice").await,
+                )
+            }),
+        )
+        .layer(AxumSessionLayer::new(store(pool, serializer)))
+}
+
+/// Sends a request as if it came from the given client IP.
+async fn get_from(client: &mut Client, uri: &str, ip: &str) -> TestResponse {
+    let mut request = Request::builder()
+        .uri(uri)
+        .header(COOKIE, client.cookie_header())
+        .body(Body::empty())
+        .unwrap();
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::new(ip.parse().unwrap(), 443)));
+
+    client.send(request).await
+}
+
+/// Stores a Session on one server and reads it back on others which only share the database.
+async fn round_trip(serializer: SessionSerializer, prefix: &str) {
+    let pool = CountingPool::default();
+    let mut first = Client::new(app(&pool, serializer));
+
+    get_from(&mut first, "/save", "10.0.0.1").await;
+
+    let rows = pool.rows.lock().unwrap().clone();
+    assert_eq!(rows.len(), 1);
+    assert!(rows.values().all(|row| row.session.starts_with(prefix)));
+
+    let mut second = Client::new(app(&pool, serializer));
+    second.cookies = first.cookies.clone();
+
+    assert_eq!(
+        get_from(&mut second, "/show", "10.0.0.1").await.body,
+        format!(
+            "{:?} {:?} {:?}",
+            Some("Ferris"),
+            Some(AVATAR.to_vec()),
+            Some("Your profile was saved")
+        )
+    );
+
+    // The bound IP survived the round trip so another IP is given a new Session.
+    let mut third = Client::new(app(&pool, serializer));
+    third.cookies = first.cookies.clone();
+
+    assert_eq!(
+        get_from(&mut third, "/show", "10.0.0.2").await.body,
+        "None None None"
+    );
+}
+
+/// Returns the ID of the only Session stored within the pool.
+fn stored_id(pool: &CountingPool) -> String {
+    let rows = pool.rows.lock().unwrap();
+    assert_eq!(rows.len(), 1);
+    rows.keys().next().unwrap().clone()
+}
+
+/// Stores a Session using one serializer and loads it using another.
+async fn mismatch(stored: SessionSerializer, configured: SessionSerializer) -> SessionError {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool, stored));
+
+    get_from(&mut client, "/save", "10.0.0.1").await;
+
+    store(&pool, configured)
+        .get_session(&stored_id(&pool))
+        .await
+        .unwrap_err()
+}
+
+#[cfg(feature = "bincode-serializer")]
+#[tokio::test]
+async fn bincode_round_trips_data_bytes_flash_and_ip() {
+    round_trip(SessionSerializer::Bincode, "bincode:").await;
+}
+
+#[cfg(feature = "msgpack-serializer")]
+#[tokio::test]
+async fn msgpack_round_trips_data_bytes_flash_and_ip() {
+    round_trip(SessionSerializer::MessagePack, "msgpack:").await;
+}
+
+#[cfg(feature = "bincode-serializer")]
+#[tokio::test]
+async fn bincode_rejects_sessions_stored_as_json() {
+    let error = mismatch(SessionSerializer::Json, SessionSerializer::Bincode).await;
+    assert!(
+        matches!(&error, SessionError::SerializerMismatch(found, expected) if found == "json" && expected == "bincode"),
+        "{:?}",
+        error
+    );
+
+    let error = mismatch(SessionSerializer::Bincode, SessionSerializer::Json).await;
+    assert!(
+        matches!(&error, SessionError::SerializerMismatch(found, expected) if found == "bincode" && expected == "json"),
+        "{:?}",
+        error
+    );
+}
+
+#[cfg(feature = "msgpack-serializer")]
+#[tokio::test]
+async fn msgpack_rejects_sessions_stored_as_json() {
+    let error = mismatch(SessionSerializer::Json, SessionSerializer::MessagePack).await;
+    assert!(
+        matches!(&error, SessionError::SerializerMismatch(found, expected) if found == "json" && expected == "msgpack"),
+        "{:?}",
+        error
+    );
+}
+
+#[cfg(all(feature = "bincode-serializer", feature = "msgpack-serializer"))]
+#[tokio::test]
+async fn msgpack_rejects_sessions_stored_as_bincode() {
+    let error = mismatch(SessionSerializer::Bincode, SessionSerializer::MessagePack).await;
+    assert!(
+        matches!(&error, SessionError::SerializerMismatch(found, expected) if found == "bincode" && expected == "msgpack"),
+        "{:?}",
+        error
+    );
+}
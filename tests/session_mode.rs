@@ -0,0 +1,73 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionMode, AxumSessionStore,
+};
+use common::{Client, CountingPool};
+
+fn app(pool: &CountingPool, mode: AxumSessionMode) -> Router {
+    let config = AxumSessionConfig::default().with_mode(mode);
+    let store = AxumSessionStore::new(Some(pool.clone()), config);
+
+    Router::new()
+        .route(
+            "/set",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("name", "alice").await;
+                format!("{}:{}", session.id(), session.is_accepted().await)
+            }),
+        )
+        .route(
+            "/accept",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set_store(true).await;
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test]
+async fn always_mode_starts_sessions_as_accepted() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool, AxumSessionMode::Always));
+
+    let response = client.get("/set").await;
+    let (id, accepted) = response.body.split_once(':').unwrap();
+
+    assert_eq!(accepted, "true");
+    assert_eq!(response.cookie_value("sqlx_session").as_deref(), Some(id));
+    assert_eq!(
+        response.cookie_value("session_acceptance").as_deref(),
+        Some("true")
+    );
+    assert!(pool.contains(id));
+}
+
+#[tokio::test]
+async fn storable_mode_waits_for_acceptance() {
+    let pool = CountingPool::default();
+    let mut client = Client::new(app(&pool, AxumSessionMode::Storable));
+
+    let response = client.get("/set").await;
+    let (id, accepted) = response.body.split_once(':').unwrap();
+
+    assert_eq!(accepted, "false");
+    assert_eq!(
+        response.cookie_value("session_acceptance").as_deref(),
+        Some("false")
+    );
+    assert!(!pool.contains(id));
+
+    let response = client.get("/accept").await;
+    assert_eq!(
+        response.cookie_value("session_acceptance").as_deref(),
+        Some("true")
+    );
+
+    // The acceptance cookie is read by the following requests which are then stored.
+    let response = client.get("/set").await;
+    let (id, accepted) = response.body.split_once(':').unwrap();
+    assert_eq!(accepted, "true");
+    assert!(pool.contains(id));
+}
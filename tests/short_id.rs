@@ -0,0 +1,32 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+use common::{Client, CountingPool};
+use sha2::{Digest, Sha256};
+
+#[tokio::test]
+async fn short_id_is_the_start_of_the_ids_sha256() {
+    let store = AxumSessionStore::new(Some(CountingPool::default()), AxumSessionConfig::default());
+    let app = Router::new()
+        .route(
+            "/",
+            get(|session: AxumSession<CountingPool>| async move {
+                format!("{} {}", session.id(), session.short_id())
+            }),
+        )
+        .layer(AxumSessionLayer::new(store));
+
+    let response = Client::new(app).get("/").await;
+    let (id, short_id) = response.body.split_once(' ').unwrap();
+
+    let digest = Sha256::digest(id.as_bytes());
+    let expected: String = digest[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    assert_eq!(short_id, expected);
+    assert_eq!(short_id.len(), 16);
+    assert!(!id.contains(short_id));
+}
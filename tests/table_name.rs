@@ -0,0 +1,86 @@
+#![cfg(any(feature = "sqlite-rustls", feature = "sqlite-native"))]
+
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore, AxumSqlitePool,
+};
+use common::Client;
+use sqlx::sqlite::SqlitePoolOptions;
+
+/// One in memory database shared by every store using the pool.
+async fn pool() -> AxumSqlitePool {
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap()
+        .into()
+}
+
+async fn store(pool: &AxumSqlitePool, table_name: &str) -> AxumSessionStore<AxumSqlitePool> {
+    let config = AxumSessionConfig::default().with_table_name(table_name.to_owned());
+    let store = AxumSessionStore::new(Some(pool.clone()), config);
+
+    store.initiate().await.unwrap();
+    store
+}
+
+fn app(store: &AxumSessionStore<AxumSqlitePool>, value: &'static str) -> Router {
+    Router::new()
+        .route(
+            "/set",
+            get(move |session: AxumSession<AxumSqlitePool>| async move {
+                session.set("app", value).await;
+                session.id()
+            }),
+        )
+        .layer(AxumSessionLayer::new(store.clone()))
+}
+
+#[tokio::test]
+async fn tables_do_not_interfere() {
+    let pool = pool().await;
+    let one = store(&pool, "app_one").await;
+    let two = store(&pool, "app_two").await;
+    let mut first = Client::new(app(&one, "one"));
+    let mut second = Client::new(app(&two, "two"));
+
+    let first_id = first.get("/set").await.body;
+    let second_id = second.get("/set").await.body;
+
+    assert_eq!(one.count().await.unwrap(), 1);
+    assert_eq!(two.count().await.unwrap(), 1);
+
+    // Each store only finds the Sessions within its own table.
+    let snapshot = one.get_session(&first_id).await.unwrap().unwrap();
+    assert_eq!(snapshot.get::<String>("app").as_deref(), Some("one"));
+    assert!(one.get_session(&second_id).await.unwrap().is_none());
+    assert!(two.get_session(&first_id).await.unwrap().is_none());
+
+    // Clearing one table leaves the other alone.
+    one.clear_store().await.unwrap();
+    assert_eq!(one.count().await.unwrap(), 0);
+    assert_eq!(two.count().await.unwrap(), 1);
+    assert!(two.get_session(&second_id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn invalid_table_names_are_rejected() {
+    let pool = pool().await;
+
+    for name in ["sessions; DROP TABLE users", "sessions-old", "", "séance"] {
+        let config = AxumSessionConfig::default().with_table_name(name.to_owned());
+        let error = AxumSessionStore::try_new(Some(pool.clone()), config).unwrap_err();
+
+        assert!(
+            error
+                .problems
+                .iter()
+                .any(|problem| problem.starts_with("table name")),
+            "{:?} was accepted",
+            name
+        );
+    }
+}
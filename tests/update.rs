@@ -0,0 +1,104 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{
+    AxumNullPool, AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore,
+};
+use common::Client;
+
+const TASKS: usize = 100;
+
+fn app() -> Router {
+    let store = AxumSessionStore::<AxumNullPool>::new(None, AxumSessionConfig::default());
+
+    Router::new()
+        .route(
+            "/increment",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                // Yield first so the parallel requests interleave.
+                tokio::task::yield_now().await;
+                session
+                    .update("count", |count: Option<usize>| Some(count.unwrap_or(0) + 1))
+                    .await;
+            }),
+        )
+        .route(
+            "/spawn",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                let tasks: Vec<_> = (0..TASKS)
+                    .map(|_| {
+                        let session = session.clone();
+
+                        tokio::spawn(async move {
+                            session
+                                .update("spawned", |count: Option<usize>| {
+                                    Some(count.unwrap_or(0) + 1)
+                                })
+                                .await
+                        })
+                    })
+                    .collect();
+
+                for task in tasks {
+                    task.await.unwrap();
+                }
+            }),
+        )
+        .route(
+            "/get",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                let count = session.get::<usize>("count").await.unwrap_or(0);
+                let spawned = session.get::<usize>("spawned").await.unwrap_or(0);
+                format!("{},{}", count, spawned)
+            }),
+        )
+        .route(
+            "/remove",
+            get(|session: AxumSession<AxumNullPool>| async move {
+                session.update("count", |_: Option<usize>| None).await;
+            }),
+        )
+        .layer(AxumSessionLayer::new(store))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn parallel_requests_do_not_lose_increments() {
+    let mut client = Client::new(app());
+
+    client.get("/get").await;
+
+    let requests: Vec<_> = (0..TASKS)
+        .map(|_| {
+            let mut request = client.fork();
+            request.cookies = client.cookies.clone();
+
+            tokio::spawn(async move { request.get("/increment").await })
+        })
+        .collect();
+
+    for request in requests {
+        assert!(request.await.unwrap().status.is_success());
+    }
+
+    assert_eq!(client.get("/get").await.body, format!("{},0", TASKS));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn parallel_tasks_within_a_request_do_not_lose_increments() {
+    let mut client = Client::new(app());
+
+    client.get("/spawn").await;
+
+    assert_eq!(client.get("/get").await.body, format!("0,{}", TASKS));
+}
+
+#[tokio::test]
+async fn returning_none_removes_the_key() {
+    let mut client = Client::new(app());
+
+    client.get("/increment").await;
+    assert_eq!(client.get("/get").await.body, "1,0");
+
+    client.get("/remove").await;
+    assert_eq!(client.get("/get").await.body, "0,0");
+}
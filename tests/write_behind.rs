@@ -0,0 +1,62 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_database_sessions::{AxumSession, AxumSessionConfig, AxumSessionLayer, AxumSessionStore};
+use chrono::Duration;
+use common::{Client, CountingPool};
+
+const REQUESTS: usize = 1000;
+
+fn app(store: &AxumSessionStore<CountingPool>) -> Router {
+    Router::new()
+        .route(
+            "/set",
+            get(|session: AxumSession<CountingPool>| async move {
+                session.set("value", session.id()).await;
+            }),
+        )
+        .layer(AxumSessionLayer::new(store.clone()))
+}
+
+/// Sends REQUESTS concurrent requests that each change their own Session.
+async fn mutate(store: &AxumSessionStore<CountingPool>) {
+    let client = Client::new(app(store));
+
+    let requests: Vec<_> = (0..REQUESTS)
+        .map(|_| {
+            let mut client = client.fork();
+            tokio::spawn(async move { client.get("/set").await })
+        })
+        .collect();
+
+    for request in requests {
+        assert!(request.await.unwrap().status.is_success());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn write_behind_batches_concurrent_writes() {
+    // Every request writes its own Session without write behind.
+    let pool = CountingPool::default();
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    mutate(&store).await;
+
+    assert_eq!(pool.len(), REQUESTS);
+    assert_eq!(pool.statements(), REQUESTS);
+
+    // With write behind the same load is written within a few batches.
+    let pool = CountingPool::default();
+    let store = AxumSessionStore::new(Some(pool.clone()), AxumSessionConfig::default());
+    let write_behind = store.spawn_write_behind(Duration::milliseconds(250), 500);
+    mutate(&store).await;
+    write_behind.shutdown().await;
+
+    assert_eq!(pool.len(), REQUESTS);
+    assert_eq!(pool.stores(), 0);
+    assert!(
+        pool.statements() <= REQUESTS / 100,
+        "{} statements for {} writes",
+        pool.statements(),
+        REQUESTS
+    );
+}